@@ -45,10 +45,23 @@ enqueue(async (client, inject) => {
 		],
 	});
 
+	/** A directory for `test-ldap`, which has the users and groups in `fixture.ldif`. */
+	const ldap = client
+		.container()
+		.from('bitnami/openldap:2.6')
+		.withDirectory('/ldifs', client.host().directory('src/server/ldap', { include: ['fixture.ldif'] }))
+		.withEnvVariable('LDAP_CUSTOM_LDIF_DIR', '/ldifs')
+		.withEnvVariable('LDAP_ROOT', 'dc=example,dc=org')
+		.withExposedPort(1389)
+		.asService()
+		;
+
 	const withCargo = (await inject(WITH_CARGO_HACK)).instance(Container);
 	const output = await withCargo
 		.withServiceBinding('db', postgres)
+		.withServiceBinding('ldap', ldap)
 		.withEnvVariable('DATABASE_URL', 'postgresql://user:password@db/winvoice-server')
+		.withEnvVariable('LDAP_URL', 'ldap://ldap:1389')
 		.withEnvVariable('RUSTFLAGS', "-C target-feature=-crt-static")
 		.withExecCargoHack('test', {
 			// NOTE: tests *must* be run one at a time as they create and destroy the test roles,
@@ -56,7 +69,7 @@ enqueue(async (client, inject) => {
 			commandArgs: ['--', '--test-threads', '1'],
 
 			// FIXME: `watchman` not available on alpine, so just skip the for now…
-			hackArgs: [...hackArgs, '--include-features', 'test-postgres'],
+			hackArgs: [...hackArgs, '--include-features', 'test-ldap,test-postgres'],
 		})
 		.stdout()
 		;
@@ -0,0 +1,75 @@
+//! Contains [`Entity`], which is any one of the things that the [`winvoice_server`](crate) can manage.
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{Contact, Department, Employee, Expense, Job, Location, Organization, Timesheet};
+
+use crate::schema::{Role, User};
+
+/// Any entity which can be created through the API, tagged with the kind of entity that it is.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entity
+{
+	/// See [`Contact`].
+	Contact(Contact),
+
+	/// See [`Department`].
+	Department(Department),
+
+	/// See [`Employee`].
+	Employee(Employee),
+
+	/// See [`Expense`]. Expenses are always created several at a time.
+	Expenses(Vec<Expense>),
+
+	/// See [`Job`].
+	Job(Job),
+
+	/// See [`Location`].
+	Location(Location),
+
+	/// See [`Organization`].
+	Organization(Organization),
+
+	/// See [`Role`].
+	Role(Role),
+
+	/// See [`Timesheet`].
+	Timesheet(Timesheet),
+
+	/// See [`User`].
+	User(User),
+}
+
+/// Implement [`From`] for every `$Entity` which is stored in the [`Entity`] variant of the same name.
+macro_rules! impl_from {
+	($($Entity:ident),+) => {
+		$(
+			impl From<$Entity> for Entity
+			{
+				fn from(entity: $Entity) -> Self
+				{
+					Self::$Entity(entity)
+				}
+			}
+		)+
+	};
+}
+
+impl_from!(Contact, Department, Employee, Job, Location, Organization, Role, Timesheet, User);
+
+impl From<Expense> for Entity
+{
+	fn from(expense: Expense) -> Self
+	{
+		Self::Expenses(vec![expense])
+	}
+}
+
+impl From<Vec<Expense>> for Entity
+{
+	fn from(expenses: Vec<Expense>) -> Self
+	{
+		Self::Expenses(expenses)
+	}
+}
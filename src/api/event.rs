@@ -0,0 +1,73 @@
+//! Contains [`Event`], which describes a change to some [`Entity`]s.
+
+use serde::{Deserialize, Serialize};
+
+use super::Entity;
+
+/// A change which was made to some [`Entity`]s.
+///
+/// Subscribers to [`routes::EVENTS`](super::routes::EVENTS) only receive the entities which they have permission to
+/// retrieve.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Event
+{
+	/// The entities which were changed.
+	entities: Vec<Entity>,
+
+	/// What happened to the [`entities`](Self::entities).
+	kind: EventKind,
+}
+
+impl Event
+{
+	/// The entities which were changed.
+	#[allow(dead_code)]
+	pub fn entities(&self) -> &[Entity]
+	{
+		self.entities.as_ref()
+	}
+
+	/// The entities which were changed.
+	#[allow(clippy::missing_const_for_fn)] // destructor cannot be evaluated at compile-time
+	pub fn into_entities(self) -> Vec<Entity>
+	{
+		self.entities
+	}
+
+	/// What happened to the [`entities`](Self::entities).
+	pub const fn kind(&self) -> EventKind
+	{
+		self.kind
+	}
+
+	/// Create a new [`Event`].
+	pub const fn new(kind: EventKind, entities: Vec<Entity>) -> Self
+	{
+		Self { entities, kind }
+	}
+}
+
+/// What happened to the [`Entity`]s of an [`Event`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind
+{
+	/// A new [`Timesheet`](winvoice_schema::Timesheet) pushed the [`Job`](winvoice_schema::Job)s past one of the
+	/// [thresholds](crate::schema::Budget::thresholds) of their budget.
+	BudgetThreshold,
+
+	/// The [`Job`](winvoice_schema::Job)s were closed.
+	Close,
+
+	/// The entities were created.
+	Create,
+
+	/// The entities were deleted.
+	Delete,
+
+	/// The [`Job`](winvoice_schema::Job)s were exported as invoices.
+	Export,
+
+	/// The entities were updated.
+	Update,
+}
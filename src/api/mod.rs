@@ -1,6 +1,8 @@
 //! This module contains strongly-typed versions of all JSON information sent via the
 //! server.
 
+mod entity;
+mod event;
 pub mod request;
 pub mod response;
 pub mod routes;
@@ -9,11 +11,40 @@ mod status;
 use std::sync::OnceLock;
 
 use semver::{BuildMetadata, Prerelease, Version};
-pub use status::{Code, Status};
+pub use entity::Entity;
+pub use event::{Event, EventKind};
+pub use status::{Code, Status, Violation};
 
 /// The header which is used to advertise the semantic version that the client accepts.
 pub const HEADER: &str = "api-version";
 
+/// The header which is used to make a `PUT` request idempotent.
+///
+/// The first response to a request with a given key is stored, and returned again for any later request by the
+/// same [`User`](crate::schema::User) to the same route with the same key, instead of creating another entity.
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+
+/// The header which is set on a response that was stored because of the [`IDEMPOTENCY_HEADER`], rather than
+/// created for the request which received it.
+pub const IDEMPOTENCY_REPLAY_HEADER: &str = "idempotent-replayed";
+
+/// The header which is used to opt into accepting overlapping [`Timesheet`](winvoice_schema::Timesheet)s.
+///
+/// When this header is set to [`OVERLAP_WARN`], overlapping timesheets are reported in the
+/// [`Status`] warnings instead of being rejected with [`Code::TimesheetOverlap`].
+pub const OVERLAP_HEADER: &str = "timesheet-overlap";
+
+/// The value of the [`OVERLAP_HEADER`] which reports overlaps as warnings.
+pub const OVERLAP_WARN: &str = "warn";
+
+/// The header of a webhook delivery which contains the id of the [`Delivery`](crate::schema::Delivery), so that
+/// the receiver can discard duplicates.
+pub const WEBHOOK_DELIVERY_HEADER: &str = "winvoice-delivery";
+
+/// The header of a webhook delivery which contains the HMAC-SHA256 of its body, keyed by the secret of the
+/// [`Webhook`](crate::schema::Webhook). It is hex-encoded and prefixed by `sha256=`.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "winvoice-signature";
+
 /// The current API version.
 static VERSION: OnceLock<Version> = OnceLock::new();
 
@@ -0,0 +1,39 @@
+//! Contains a request to create many entities at once.
+
+mod arg;
+mod operation;
+
+pub use arg::Arg;
+pub use operation::Operation;
+use serde::{Deserialize, Serialize};
+
+/// The request to create many entities in a single transaction.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Batch
+{
+	/// The [`Operation`]s to perform, in order.
+	operations: Vec<Operation>,
+}
+
+impl Batch
+{
+	/// Create a new [`Batch`] request.
+	#[allow(dead_code)]
+	pub const fn new(operations: Vec<Operation>) -> Self
+	{
+		Self { operations }
+	}
+
+	/// The [`Operation`]s to perform, in order.
+	pub fn operations(&self) -> &[Operation]
+	{
+		self.operations.as_ref()
+	}
+
+	/// The [`Operation`]s to perform, in order.
+	#[allow(clippy::missing_const_for_fn)] // destructor cannot be evaluated at compile-time
+	pub fn into_operations(self) -> Vec<Operation>
+	{
+		self.operations
+	}
+}
@@ -0,0 +1,36 @@
+//! Contains [`Arg`].
+
+use serde::{Deserialize, Serialize};
+
+/// An argument to an [`Operation`](super::Operation), which is either given outright, or is the
+/// [`Entity`](crate::api::Entity) created by an earlier operation in the same [`Batch`](super::Batch).
+///
+/// # Example
+///
+/// ```json
+/// { "ref": 0 }
+/// ```
+///
+/// refers to whatever the first operation of the batch created.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Arg<T>
+{
+	/// The index of an earlier operation in the [`Batch`](super::Batch).
+	Ref
+	{
+		/// The index of an earlier operation in the [`Batch`](super::Batch).
+		r#ref: usize,
+	},
+
+	/// The argument itself.
+	Value(T),
+}
+
+impl<T> From<T> for Arg<T>
+{
+	fn from(value: T) -> Self
+	{
+		Self::Value(value)
+	}
+}
@@ -0,0 +1,172 @@
+//! Contains [`Operation`].
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	ContactKind,
+	Currency,
+	Department,
+	Employee,
+	Id,
+	Invoice,
+	Job,
+	Location,
+	Money,
+	Organization,
+};
+
+use super::Arg;
+use crate::schema::Role;
+
+/// One entity to create as part of a [`Batch`](super::Batch).
+///
+/// Each variant has the same fields as the arguments of a `PUT` to the [route](crate::api::routes) for that entity,
+/// except that other entities may be given as an [`Arg`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation
+{
+	/// Create a [`Contact`](winvoice_schema::Contact).
+	Contact
+	{
+		/// See [`Contact::kind`](winvoice_schema::Contact::kind).
+		kind: ContactKind,
+
+		/// See [`Contact::label`](winvoice_schema::Contact::label).
+		label: String,
+	},
+
+	/// Create a [`Department`].
+	Department
+	{
+		/// See [`Department::name`].
+		name: String,
+	},
+
+	/// Create an [`Employee`].
+	Employee
+	{
+		/// See [`Employee::department`].
+		department: Arg<Department>,
+
+		/// See [`Employee::name`].
+		name: String,
+
+		/// See [`Employee::title`].
+		title: String,
+	},
+
+	/// Create [`Expense`](winvoice_schema::Expense)s.
+	Expenses
+	{
+		/// The category, cost, and description of each expense.
+		expenses: Vec<(String, Money, String)>,
+
+		/// The [`Id`] of the [`Timesheet`](winvoice_schema::Timesheet) which the expenses are for. When given as
+		/// an [`Arg::Ref`], the operation referred to must have created a timesheet.
+		timesheet: Arg<Id>,
+	},
+
+	/// Create a [`Job`].
+	Job
+	{
+		/// See [`Job::client`].
+		client: Arg<Organization>,
+
+		/// See [`Job::date_close`].
+		date_close: Option<DateTime<Utc>>,
+
+		/// See [`Job::date_open`].
+		date_open: DateTime<Utc>,
+
+		/// See [`Job::departments`].
+		departments: Vec<Arg<Department>>,
+
+		/// See [`Job::increment`].
+		#[serde(with = "humantime_serde")]
+		increment: Duration,
+
+		/// See [`Job::invoice`].
+		invoice: Invoice,
+
+		/// See [`Job::notes`].
+		notes: String,
+
+		/// See [`Job::objectives`].
+		objectives: String,
+	},
+
+	/// Create a [`Location`].
+	Location
+	{
+		/// See [`Location::currency`].
+		currency: Option<Currency>,
+
+		/// See [`Location::name`].
+		name: String,
+
+		/// See [`Location::outer`].
+		outer: Option<Arg<Location>>,
+	},
+
+	/// Create an [`Organization`].
+	Organization
+	{
+		/// See [`Organization::location`].
+		location: Arg<Location>,
+
+		/// See [`Organization::name`].
+		name: String,
+	},
+
+	/// Create a [`Role`].
+	Role
+	{
+		/// See [`Role::name`].
+		name: String,
+
+		/// See [`Role::password_ttl`].
+		#[serde(default, with = "humantime_serde::option")]
+		password_ttl: Option<Duration>,
+	},
+
+	/// Create a [`Timesheet`](winvoice_schema::Timesheet).
+	Timesheet
+	{
+		/// See [`Timesheet::employee`](winvoice_schema::Timesheet::employee).
+		employee: Arg<Employee>,
+
+		/// The category, cost, and description of each [`Expense`](winvoice_schema::Expense).
+		expenses: Vec<(String, Money, String)>,
+
+		/// See [`Timesheet::job`](winvoice_schema::Timesheet::job).
+		job: Arg<Job>,
+
+		/// See [`Timesheet::time_begin`](winvoice_schema::Timesheet::time_begin).
+		time_begin: DateTime<Utc>,
+
+		/// See [`Timesheet::time_end`](winvoice_schema::Timesheet::time_end).
+		time_end: Option<DateTime<Utc>>,
+
+		/// See [`Timesheet::work_notes`](winvoice_schema::Timesheet::work_notes).
+		work_notes: String,
+	},
+
+	/// Create a [`User`](crate::schema::User).
+	User
+	{
+		/// See [`User::employee`](crate::schema::User::employee).
+		employee: Option<Arg<Employee>>,
+
+		/// The password of the user, in plaintext.
+		password: String,
+
+		/// See [`User::role`](crate::schema::User::role).
+		role: Arg<Role>,
+
+		/// See [`User::username`](crate::schema::User::username).
+		username: String,
+	},
+}
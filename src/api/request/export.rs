@@ -7,6 +7,11 @@ use winvoice_schema::{Currency, Job};
 #[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Export
 {
+	/// Whether to only export the [`Timesheet`](winvoice_schema::Timesheet)s which have been
+	/// [approved](crate::schema::ReviewStatus::Approved).
+	#[serde(default)]
+	approved_only: bool,
+
 	/// The currency to export the [`Job`]s in. If [`None`], it will detect the
 	/// [currency](winvoice_schema::Location::currency) for each [client](Organization).
 	currency: Option<Currency>,
@@ -19,9 +24,16 @@ impl Export
 {
 	/// Create a new [`Export`] request.
 	#[allow(dead_code)]
-	pub const fn new(currency: Option<Currency>, jobs: Vec<Job>) -> Self
+	pub const fn new(approved_only: bool, currency: Option<Currency>, jobs: Vec<Job>) -> Self
+	{
+		Self { approved_only, currency, jobs }
+	}
+
+	/// Whether to only export the [`Timesheet`](winvoice_schema::Timesheet)s which have been
+	/// [approved](crate::schema::ReviewStatus::Approved).
+	pub const fn approved_only(&self) -> bool
 	{
-		Self { currency, jobs }
+		self.approved_only
 	}
 
 	/// The [`Format`] that the [`jobs`](Export::jobs) will be exported to.
@@ -0,0 +1,86 @@
+//! Contains a request to create many entities of the same kind from the rows of a CSV file.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of entity which each row of an [`Import`] creates.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind
+{
+	/// Each row is an [`Expense`](winvoice_schema::Expense). The fields are `timesheet` (an
+	/// [`Id`](winvoice_schema::Id)), `category`, `cost`, and `description` (optional).
+	Expense,
+
+	/// Each row is a [`Job`](winvoice_schema::Job). The fields are `client` (the name of an
+	/// [`Organization`](winvoice_schema::Organization)), `date_open`, `date_close` (optional), `departments` (names
+	/// separated by `;`, optional), `increment`, `hourly_rate`, `notes` (optional), and `objectives`.
+	Job,
+
+	/// Each row is an [`Organization`](winvoice_schema::Organization). The fields are `location` (the name of a
+	/// [`Location`](winvoice_schema::Location)) and `name`.
+	Organization,
+
+	/// Each row is a [`Timesheet`](winvoice_schema::Timesheet). The fields are `employee` (the name of an
+	/// [`Employee`](winvoice_schema::Employee)), `job` (an [`Id`](winvoice_schema::Id)), `time_begin`, `time_end`
+	/// (optional), and `work_notes` (optional).
+	Timesheet,
+}
+
+/// The request to create an entity for each row of a CSV file.
+///
+/// Other entities are referred to by name (or [`Id`](winvoice_schema::Id), for jobs and timesheets), dates are
+/// RFC 3339, durations are [`humantime`](https://docs.rs/humantime), and money is written with its currency (e.g.
+/// `20.00 USD`).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Import
+{
+	/// Which column of the [`csv`](Self::csv) each field is in, by header. Fields which are not in this map are read
+	/// from the column whose header is the same as the field.
+	#[serde(default)]
+	columns: BTreeMap<String, String>,
+
+	/// The CSV file, including its header row.
+	csv: String,
+
+	/// Whether to check every row without saving anything.
+	#[serde(default)]
+	dry_run: bool,
+
+	/// The [`ImportKind`] of every row.
+	kind: ImportKind,
+}
+
+impl Import
+{
+	/// Create a new [`Import`] request.
+	pub const fn new(columns: BTreeMap<String, String>, csv: String, dry_run: bool, kind: ImportKind) -> Self
+	{
+		Self { columns, csv, dry_run, kind }
+	}
+
+	/// Which column of the [`csv`](Self::csv) each field is in, by header.
+	pub const fn columns(&self) -> &BTreeMap<String, String>
+	{
+		&self.columns
+	}
+
+	/// The CSV file, including its header row.
+	pub fn csv(&self) -> &str
+	{
+		self.csv.as_ref()
+	}
+
+	/// Whether to check every row without saving anything.
+	pub const fn dry_run(&self) -> bool
+	{
+		self.dry_run
+	}
+
+	/// The [`ImportKind`] of every row.
+	pub const fn kind(&self) -> ImportKind
+	{
+		self.kind
+	}
+}
@@ -9,14 +9,18 @@
 //! [`/whoami`](super::routes::WHO_AM_I), **all requests** expect a JSON body, the schema of which depending on the
 //! nature of the request. Requests to `DELETE` should use match a [`Delete`] request, `POST` to [`Post`], etc.
 
+mod batch;
 mod delete;
 mod export;
+mod import;
 mod post;
 mod put;
 
 /// The request to [update](winvoice_adapter::Updatable::update) some information.
 pub type Patch<T> = Delete<T>;
+pub use batch::{Arg, Batch, Operation};
 pub use delete::Delete;
 pub use export::Export;
-pub use post::Post;
+pub use import::{Import, ImportKind};
+pub use post::{Expand, Post};
 pub use put::Put;
@@ -2,12 +2,32 @@
 
 use serde::{Deserialize, Serialize};
 
+/// How the entities which a retrieved entity refers to (e.g. the [`Job`](winvoice_schema::Job) of a
+/// [`Timesheet`](winvoice_schema::Timesheet)) are included in the response to a [`Post`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expand
+{
+	/// Each referenced entity is replaced by its [`Id`](winvoice_schema::Id), and the response is a
+	/// [`SideLoaded`](crate::api::response::SideLoaded) which has each of them once.
+	Ids,
+
+	/// Each referenced entity is nested in full inside the entity which refers to it, and the response is a
+	/// [`Post`](crate::api::response::Post).
+	#[default]
+	Nested,
+}
+
 /// The request to [retrieve](winvoice_adapter::Retrievable::retrieve) some information.
 #[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Post<Match>
 {
 	/// See [`Retrieve::condition`]
 	condition: Match,
+
+	/// See [`Expand`].
+	#[serde(default)]
+	expand: Expand,
 }
 
 impl<Match> Post<Match>
@@ -16,7 +36,7 @@ impl<Match> Post<Match>
 	#[allow(dead_code)]
 	pub const fn new(condition: Match) -> Self
 	{
-		Self { condition }
+		Self { condition, expand: Expand::Nested }
 	}
 
 	/// The condition used to filter which entities should be retrieved.
@@ -31,6 +51,13 @@ impl<Match> Post<Match>
 		&self.condition
 	}
 
+	/// See [`Expand`].
+	#[allow(dead_code)]
+	pub const fn expand(&self) -> Expand
+	{
+		self.expand
+	}
+
 	/// HACK: can't be an `Into` impl because rust-lang/rust#31844
 	///
 	/// # See also
@@ -41,4 +68,11 @@ impl<Match> Post<Match>
 	{
 		self.condition
 	}
+
+	/// Set the [`Expand`] of the request.
+	#[allow(clippy::missing_const_for_fn, dead_code)] // destructor cannot be evaluated at compile-time
+	pub fn with_expand(self, expand: Expand) -> Self
+	{
+		Self { expand, ..self }
+	}
 }
@@ -18,6 +18,8 @@ mod login;
 mod logout;
 mod post;
 mod put;
+mod redirect;
+mod side_loaded;
 mod version;
 mod who_am_i;
 
@@ -30,5 +32,7 @@ pub use login::Login;
 pub use logout::Logout;
 pub use post::Post;
 pub use put::Put;
+pub use redirect::Redirect;
+pub use side_loaded::{Related, SideLoaded};
 pub use version::Version;
 pub use who_am_i::WhoAmI;
@@ -0,0 +1,42 @@
+//! This module contains the response which directs the client somewhere else.
+
+mod as_ref;
+mod from;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::Status;
+
+/// The response which directs the client to another URL, such as the login page of an identity provider.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Redirect
+{
+	/// The [`Status`] of the request.
+	status: Status,
+
+	/// The URL which the client should go to.
+	url: Option<String>,
+}
+
+impl Redirect
+{
+	/// Create a new [`Redirect`] response.
+	pub const fn new(status: Status, url: Option<String>) -> Self
+	{
+		Self { status, url }
+	}
+
+	/// The [`Status`] of the request.
+	#[allow(dead_code)]
+	pub const fn status(&self) -> &Status
+	{
+		&self.status
+	}
+
+	/// The URL which the client should go to.
+	#[allow(dead_code)]
+	pub fn url(&self) -> Option<&str>
+	{
+		self.url.as_deref()
+	}
+}
@@ -0,0 +1,12 @@
+//! Implementations for [`AsRef`] for [`Redirect`]
+
+use super::Redirect;
+use crate::api::Code;
+
+impl AsRef<Code> for Redirect
+{
+	fn as_ref(&self) -> &Code
+	{
+		self.status.as_ref()
+	}
+}
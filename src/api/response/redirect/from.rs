@@ -0,0 +1,12 @@
+//! Contains [`From`] implementations for a [`Redirect`].
+
+use super::Redirect;
+use crate::api::Status;
+
+impl From<Status> for Redirect
+{
+	fn from(status: Status) -> Self
+	{
+		Self::new(status, None)
+	}
+}
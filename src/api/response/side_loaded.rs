@@ -0,0 +1,63 @@
+//! This module contains the response for a [retrieve](winvoice_adapter::Retrievable) operation whose
+//! [`Expand`](crate::api::request::Expand) is [`Ids`](crate::api::request::Expand::Ids).
+
+mod as_ref;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use winvoice_schema::Id;
+
+use crate::api::Status;
+
+/// The entities which are referred to by the [entities](SideLoaded::entities) of a [`SideLoaded`], by the kind of
+/// entity (e.g. `"job"`) and then by [`Id`].
+pub type Related = BTreeMap<String, BTreeMap<Id, Value>>;
+
+/// The POST & [`winvoice_adapter::Retrievable::retrieve`] request response, where each entity which is referred to is
+/// included once, rather than nested in every entity which refers to it.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SideLoaded
+{
+	/// The entities in the database which [match](winvoice_match)ed the
+	/// [request](crate::api::request::Post) parameters, with each entity they refer to replaced by its [`Id`].
+	entities: Vec<Value>,
+
+	/// See [`Related`].
+	related: Related,
+
+	/// The [`Status`] of this request.
+	status: Status,
+}
+
+impl SideLoaded
+{
+	/// Create a new [`SideLoaded`] response.
+	pub const fn new(entities: Vec<Value>, related: Related, status: Status) -> Self
+	{
+		Self { entities, related, status }
+	}
+
+	/// The entities in the database which [match](winvoice_match)ed the
+	/// [request](crate::api::request::Post) parameters, with each entity they refer to replaced by its [`Id`].
+	#[allow(dead_code)]
+	pub fn entities(&self) -> &[Value]
+	{
+		self.entities.as_ref()
+	}
+
+	/// See [`Related`].
+	#[allow(dead_code)]
+	pub const fn related(&self) -> &Related
+	{
+		&self.related
+	}
+
+	/// The [`Status`] of this request.
+	#[allow(dead_code)]
+	pub const fn status(&self) -> &Status
+	{
+		&self.status
+	}
+}
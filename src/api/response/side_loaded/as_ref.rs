@@ -0,0 +1,12 @@
+//! Implementations for [`AsRef`] for [`SideLoaded`]
+
+use super::SideLoaded;
+use crate::api::Code;
+
+impl AsRef<Code> for SideLoaded
+{
+	fn as_ref(&self) -> &Code
+	{
+		self.status.as_ref()
+	}
+}
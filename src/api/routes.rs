@@ -1,6 +1,45 @@
 //! The API endpoints for the [`winvoice_server`](crate)
 //!
 //! All endpoints accept `DELETE`, `PATCH`, `POST`, and `PUT` unless otherwise specified.
+//!
+//! The endpoints which retrieve entities with a `POST` request also accept a `GET` request, whose match condition is
+//! either:
+//!
+//! * URL-encoded JSON in the `condition` query parameter; or
+//! * the [`Id`](winvoice_schema::Id) of a [`SavedQuery`](crate::schema::SavedQuery) for the same endpoint, which
+//!   the same user saved, in the `query` query parameter (see [`QUERY`]).
+//!
+//! Successful responses to a `GET` request have an `ETag` header, and a request with an `If-None-Match` header which
+//! matches the `ETag` is answered with `304 Not Modified`.
+//!
+//! By default, the entities which a retrieved entity refers to are nested inside of it. When the `expand` of a
+//! [`Post`](super::request::Post) request (or the `expand` query parameter of a `GET` request) is `"ids"`, they are
+//! replaced by their [`Id`](winvoice_schema::Id)s instead, and the response is a
+//! [`SideLoaded`](super::response::SideLoaded) which has each of them once, keyed by kind and
+//! [`Id`](winvoice_schema::Id).
+//!
+//! The body of a `PATCH` or `PUT` request is validated before it is handled. If any of its values are not valid (e.g. a
+//! timesheet which ends before it begins, or an organization without a name), the request is rejected with a
+//! [`ValidationError`](super::Code::ValidationError), and the [`Status`](super::Status) lists the path to each of those
+//! values in the body as a [`Violation`](super::Violation).
+//!
+//! A request which cannot be read at all is rejected with a [`Status`](super::Status) too:
+//!
+//! * a body without a JSON `Content-Type` with [`InvalidContentType`](super::Code::InvalidContentType);
+//! * a body which is not JSON, or query parameters which cannot be read, with
+//!   [`EncodingError`](super::Code::EncodingError);
+//! * a JSON body which does not have the shape the endpoint expects with a
+//!   [`ValidationError`](super::Code::ValidationError), whose violation has the path to the value which could not be
+//!   read; and
+//! * a required header which is missing or cannot be read with [`InvalidHeader`](super::Code::InvalidHeader).
+
+/// The API endpoint for creating many entities at once, across entity types.
+///
+/// Accepts a `PUT` request with a [`Batch`](super::request::Batch) body only. Every
+/// [`Operation`](super::request::Operation) is checked against the same permissions as a `PUT` to the route for its
+/// entity, and either all operations are committed or none are. Responds with a [`Post`](super::response::Post) of
+/// the [`Entity`](super::Entity)s which were created, in the same order as the operations.
+pub const BATCH: &str = "/batch";
 
 /// The endpoint for [`winvoice_schema::Contact`]s
 pub const CONTACT: &str = "/contact";
@@ -14,9 +53,31 @@ pub const EMPLOYEE: &str = "/employee";
 /// The API endpoint for [`winvoice_schema::Expense`]
 pub const EXPENSE: &str = "/expense";
 
+/// The API endpoint for the files (e.g. receipts) attached to a [`winvoice_schema::Expense`]. Access is governed by
+/// the same permissions as the expense itself.
+///
+/// * `DELETE` takes a [`Delete`](super::request::Delete) body of [`Attachment`](crate::schema::Attachment) ids.
+/// * `GET` takes the [`Id`](winvoice_schema::Id) of an attachment in the `id` query parameter, and responds with the
+///   contents of the file.
+/// * `POST` takes a [`Post`](super::request::Post) body of an expense's id, and responds with a
+///   [`Post`](super::response::Post) of its attachments.
+/// * `PUT` takes the id of an expense and the name of the file in the `expense` and `name` query parameters, and the
+///   contents of the file as its body. The `Content-Type` header must be one which the server allows, and the file
+///   must be within its size limit. Responds with a [`Put`](super::response::Put) of the attachment.
+pub const EXPENSE_ATTACHMENT: &str = "/expense/attachment";
+
+/// The API endpoint for subscribing to changes made to entities, from any replica of the server.
+///
+/// Accepts a `GET` request only. Responds with a stream of [server-sent
+/// events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events), where each `message` is an
+/// [`Event`](super::Event) containing only the entities which the user has permission to retrieve. If the subscriber
+/// falls too far behind, a `lagged` event is sent with the number of events that were skipped.
+pub const EVENTS: &str = "/events";
+
 /// The API endpoint for exporting [`winvoice_schema::Job`]s
 ///
-/// Accepts a `POST` request with a JSON [`Export`](super::request::Export) body only.
+/// Accepts a `POST` request with a JSON [`Export`](super::request::Export) body only. It may ask to export only the
+/// timesheets which have been [approved](TIMESHEET_REVIEW).
 ///
 /// The heading of the exported document is controlled by the [`Contact`](winvoice_schema::Contact) with the label
 /// 'Name' (case-sensitive).
@@ -25,18 +86,139 @@ pub const EXPORT: &str = "/job/export";
 /// The API endpoint for checking if the server is capable of receiving requests.
 pub const HEALTHY: &str = "/_status/healthy";
 
+/// The API endpoint for creating an entity for each row of a CSV file.
+///
+/// Accepts a `PUT` request with an [`Import`](super::request::Import) body only. Every row is checked before any are
+/// created, and any rows which cannot be imported are listed in the warnings of the response. Otherwise, the rows are
+/// created with the same permissions and guarantees as a [`BATCH`], unless it is a dry run, in which case nothing is
+/// saved. Responds with a [`Post`](super::response::Post) of the [`Entity`](super::Entity)s which were (or would be)
+/// created, in the same order as the rows.
+pub const IMPORT: &str = "/import";
+
+/// The API endpoint for [`Invitation`](crate::schema::Invitation)s of somebody to become a
+/// [`User`](crate::schema::User), who chooses their own password.
+///
+/// Accepts a `DELETE` request with a [`Delete`](super::request::Delete) body of invitation
+/// [`Id`](winvoice_schema::Id)s, a `GET` request, and a `PUT` request with a [`Put`](super::request::Put) body of the
+/// [`Employee`](winvoice_schema::Employee) (if any), [`Role`](crate::schema::Role), how long until the invitation
+/// expires, and username of the user only. Creating an invitation takes the same permissions as creating the user.
+///
+/// The response to a `PUT` request has the `link` of the invitation, which is the only time its token is known. It
+/// should be given to the invitee, who redeems it with [`INVITATION_ACCEPT`].
+pub const INVITATION: &str = "/invitation";
+
+/// The API endpoint for redeeming an [`Invitation`](crate::schema::Invitation), which is the `link` of the invitation.
+///
+/// Like [`LOGIN`], does not require logging in first. Takes a `POST` request with the token of the invitation in the
+/// `token` query parameter, and a body of the password of the new [`User`](crate::schema::User) as a JSON string.
+/// Responds as [`LOGIN`] does, after logging in as the new user. An invitation can only be redeemed once, and not after
+/// it expires.
+pub const INVITATION_ACCEPT: &str = "/invitation/accept";
+
 /// The API endpoint for [`winvoice_schema::Job`]
 pub const JOB: &str = "/job";
 
+/// The API endpoint for the [`Budget`](crate::schema::Budget)s of [`winvoice_schema::Job`]s.
+///
+/// * `DELETE` accepts a [`Delete`](super::request::Delete) body of job [`Id`](winvoice_schema::Id)s, and removes their
+///   budgets.
+/// * `POST` accepts a [`Post`](super::request::Post) body of a [`MatchJob`](winvoice_match::MatchJob), and responds
+///   with a [`Post`](super::response::Post) of the [`BudgetUsage`](crate::schema::BudgetUsage) of each matching job
+///   which has a budget.
+/// * `PUT` accepts a [`Put`](super::request::Put) body of a budget, and sets it as the budget of its job.
+///
+/// Whenever a new timesheet pushes a job past one of the thresholds of its budget, the response which created it has
+/// a warning, and a [`BudgetThreshold`](super::EventKind::BudgetThreshold) event is published.
+pub const JOB_BUDGET: &str = "/job/budget";
+
+/// The API endpoint for closing [`winvoice_schema::Job`]s, using the server's clock.
+///
+/// Accepts a `PATCH` request with a [`Patch`](super::request::Patch) body of job [`Id`](winvoice_schema::Id)s
+/// only. Responds with a [`Post`](super::response::Post) of the jobs which were closed.
+pub const JOB_CLOSE: &str = "/job/close";
+
+/// The API endpoint for marking the [`Invoice`](winvoice_schema::Invoice) of closed [`winvoice_schema::Job`]s as
+/// issued, using the server's clock.
+///
+/// Accepts the same requests as [`JOB_CLOSE`]. Once the invoice is issued, the timesheets and expenses of the job
+/// are locked unless a user has the [`InvoicedJob`](crate::permissions::Object::InvoicedJob) permission.
+pub const JOB_INVOICE_ISSUE: &str = "/job/invoice/issue";
+
+/// The API endpoint for marking the issued [`Invoice`](winvoice_schema::Invoice) of [`winvoice_schema::Job`]s as
+/// paid, using the server's clock.
+///
+/// Accepts the same requests as [`JOB_CLOSE`].
+pub const JOB_INVOICE_PAY: &str = "/job/invoice/pay";
+
+/// The API endpoint for reopening closed [`winvoice_schema::Job`]s whose invoice has not been issued.
+///
+/// Accepts the same requests as [`JOB_CLOSE`].
+pub const JOB_REOPEN: &str = "/job/reopen";
+
 /// The API endpoint for [`winvoice_schema::Location`]
 pub const LOCATION: &str = "/location";
 
+/// The API endpoint for the [`winvoice_schema::Location`]s which a location is inside of.
+///
+/// Accepts a `POST` request with a [`Post`](super::request::Post) body of the location's [`Id`](winvoice_schema::Id)
+/// only. Responds with a [`Post`](super::response::Post) of its [`outer`](winvoice_schema::Location::outer) locations,
+/// the nearest first.
+pub const LOCATION_ANCESTORS: &str = "/location/ancestors";
+
+/// The API endpoint for the [`winvoice_schema::Location`]s which are inside of a location, however deeply.
+///
+/// Accepts the same requests as [`LOCATION_ANCESTORS`].
+pub const LOCATION_DESCENDANTS: &str = "/location/descendants";
+
+/// The API endpoint for moving [`winvoice_schema::Location`]s, and every location inside of them, inside of another
+/// location.
+///
+/// Accepts a `PATCH` request with a [`Patch`](super::request::Patch) body of pairs of a location's
+/// [`Id`](winvoice_schema::Id) and the [`Id`](winvoice_schema::Id) of the location to move it inside of (or `null`, to
+/// move it outside of every other location) only. Responds with a [`Post`](super::response::Post) of the locations
+/// which were moved. A location cannot be moved inside of itself, or inside of a location which is inside of it, and
+/// if any of the moves would do so, none are made.
+pub const LOCATION_MOVE: &str = "/location/move";
+
+/// The API endpoint for the [`winvoice_schema::Organization`]s which are located anywhere within a
+/// [`winvoice_schema::Location`].
+///
+/// Accepts the same requests as [`LOCATION_ANCESTORS`]. Responds with a [`Post`](super::response::Post) of the
+/// organizations which are located in the location, or in any location inside of it.
+pub const LOCATION_ORGANIZATIONS: &str = "/location/organizations";
+
 /// The API endpoint for logging in.
 ///
 /// Unlike other endpoints, takes a `POST` request with a [basic authorization
 /// header](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Authorization#basic_authentication)
+///
+/// A user whose password has expired is rejected with [`PasswordExpired`](super::Code::PasswordExpired), and must
+/// change it with [`PASSWORD`].
 pub const LOGIN: &str = "/login";
 
+/// The API endpoint for starting to log in through the [OpenID Connect](https://openid.net/connect/) identity provider
+/// which the server is configured with. Not available otherwise.
+///
+/// Like [`LOGIN`], does not require logging in first. Takes a `GET` request, and responds with a
+/// [`Redirect`](super::response::Redirect) to the login page of the identity provider. Afterwards, the identity
+/// provider sends the user to the redirect URL which the server is configured with, along with the `code` and `state`
+/// query parameters which should be passed on to [`LOGIN_OIDC_CALLBACK`] by the same client (i.e. with the same
+/// session cookie).
+///
+/// When a [`User`](crate::schema::User) is logged in already, the login links their identity at the identity provider
+/// to them instead, so that they may log in through it from then on.
+pub const LOGIN_OIDC: &str = "/login/oidc";
+
+/// The API endpoint for finishing a login which was started with [`LOGIN_OIDC`].
+///
+/// Takes a `POST` request with the `code` and `state` query parameters which the identity provider sent the user back
+/// with. Responds as [`LOGIN`] does. The [`User`](crate::schema::User) is the one whose identity (i.e. `sub` claim)
+/// was linked to them, and their [`Role`](crate::schema::Role) is taken from the first of their groups which is the
+/// name of a role. When nobody has the identity, a user is created (with the username from a claim of the identity
+/// provider) if the server is configured to do so, and rejected otherwise. An identity is never given to an existing
+/// user who has not linked it.
+pub const LOGIN_OIDC_CALLBACK: &str = "/login/oidc/callback";
+
 /// The API endpoint for logging out
 ///
 /// Unlike other endpoints, takes a `POST` request with no body.
@@ -45,15 +227,122 @@ pub const LOGOUT: &str = "/logout";
 /// The API endpoint for [`winvoice_schema::Organization`]
 pub const ORGANIZATION: &str = "/organization";
 
+/// The API endpoint for changing the password of a [`User`](crate::schema::User), even after it has expired.
+///
+/// Like [`LOGIN`], takes a `POST` request with a basic authorization header of the current credentials. The body is the
+/// new password as a JSON string, which must not be empty or the same as the current one. Responds as [`LOGIN`] does,
+/// after logging in with the new password.
+pub const PASSWORD: &str = "/password";
+
+/// The API endpoint for requesting to reset a forgotten password.
+///
+/// Like [`LOGIN`], does not require logging in first. Takes a `POST` request with a body of the username of the
+/// [`User`](crate::schema::User) as a JSON string. A link to [`PASSWORD_RESET_REDEEM`] which expires after a short
+/// time is delivered to the user, replacing any link they were sent before. So as not to reveal which users exist, the
+/// response is the same whether there is such a user or not.
+pub const PASSWORD_RESET: &str = "/password/reset";
+
+/// The API endpoint for redeeming the link which is delivered by [`PASSWORD_RESET`].
+///
+/// Takes a `POST` request with the token in the `token` query parameter, and a body of the new password as a JSON
+/// string. Responds as [`LOGIN`] does, after logging in with the new password. A link can only be redeemed once, and
+/// not after it expires. Every other session of the user is logged out.
+pub const PASSWORD_RESET_REDEEM: &str = "/password/reset/redeem";
+
+/// The API endpoint for saving match conditions, so that they can be used in a `GET` request by their
+/// [`Id`](winvoice_schema::Id).
+///
+/// Accepts a `PUT` request with a [`Put`](super::request::Put) body of the condition and the endpoint it is for only.
+/// Responds with a [`Put`](super::response::Put) of the [`SavedQuery`](crate::schema::SavedQuery), which only the
+/// user who saved it can use.
+pub const QUERY: &str = "/query";
+
+/// The API endpoint for the [`Rate`](crate::schema::Rate)s which [`winvoice_schema::Employee`]s bill at, in place of
+/// the [`Invoice::hourly_rate`](winvoice_schema::Invoice::hourly_rate) of a job.
+///
+/// Accepts a `DELETE` request with a [`Delete`](super::request::Delete) body of rates, a `GET` request, and a `PUT`
+/// request with a [`Put`](super::request::Put) body of a rate, which replaces any rate for the same employee and
+/// [scope](crate::schema::RateScope).
+///
+/// When [exporting](EXPORT), each timesheet is priced by the rate for its employee on its job, or else on the client of
+/// its job, or else by the rate of the job.
+pub const RATE: &str = "/rate";
+
 /// The API endpoint for [`Role`](crate::schema::Role)
+///
+/// A role which some users still have is only deleted if the `reassign_to` query parameter of the `DELETE` request is
+/// the [`Id`](winvoice_schema::Id) of another role, which those users are given first. Otherwise, the request is
+/// rejected with [`RoleInUse`](super::Code::RoleInUse).
 pub const ROLE: &str = "/role";
 
+/// The API endpoint for searching the text of organizations, jobs, timesheets, expenses, and contacts at once.
+///
+/// Accepts a `POST` request with a [`Post`](super::request::Post) body of the search terms only. Responds with a
+/// [`Post`](super::response::Post) of the matching [`Entity`](super::Entity)s which the user can retrieve, the most
+/// relevant first.
+pub const SEARCH: &str = "/search";
+
 /// The API endpoint for [`winvoice_schema::Timesheet`]
 pub const TIMESHEET: &str = "/timesheet";
 
+/// The API endpoint for the [`Review`](crate::schema::Review)s of [`winvoice_schema::Timesheet`]s.
+///
+/// * `POST` accepts a [`Post`](super::request::Post) body of a [`MatchTimesheet`](winvoice_match::MatchTimesheet), and
+///   responds with a [`Post`](super::response::Post) of the reviews of the matching timesheets.
+/// * `PATCH` accepts a [`Patch`](super::request::Patch) body of reviews which approve or reject submitted timesheets,
+///   and responds with a [`Post`](super::response::Post) of the reviews which were made. Requires the
+///   [`Approve`](crate::permissions::Action::Approve) permission, and users may not review their own timesheets.
+///
+/// Once a timesheet is submitted or approved, it is locked unless a user can approve it.
+pub const TIMESHEET_REVIEW: &str = "/timesheet/review";
+
+/// The API endpoint for starting a [`winvoice_schema::Timesheet`] for the logged in
+/// [`User`](crate::schema::User)'s [`Employee`](winvoice_schema::Employee), using the server's clock.
+///
+/// Accepts a `PUT` request with a [`Put`](super::request::Put) body of the expenses, job, and work notes only.
+/// Only one timesheet may be running for a given employee at a time.
+pub const TIMESHEET_START: &str = "/timesheet/start";
+
+/// The API endpoint for stopping the running [`winvoice_schema::Timesheet`] of the logged in
+/// [`User`](crate::schema::User)'s [`Employee`](winvoice_schema::Employee), using the server's clock.
+///
+/// Accepts a `PATCH` request with no body only. Responds with a [`Post`](super::response::Post) of the timesheets
+/// which were stopped.
+pub const TIMESHEET_STOP: &str = "/timesheet/stop";
+
+/// The API endpoint for submitting [`winvoice_schema::Timesheet`]s for [review](TIMESHEET_REVIEW).
+///
+/// Accepts a `PATCH` request with a [`Patch`](super::request::Patch) body of timesheet
+/// [`Id`](winvoice_schema::Id)s only. Responds with a [`Post`](super::response::Post) of the
+/// [`Review`](crate::schema::Review)s of the timesheets which were submitted. Timesheets which are running cannot be
+/// submitted.
+pub const TIMESHEET_SUBMIT: &str = "/timesheet/submit";
+
 /// The API endpoint for [`User`](crate::schema::User)
+///
+/// A `DELETE` or `PATCH` request (or a `DELETE` request to [`ROLE`] which reassigns users) which would leave no user
+/// who can update users, or no user who can update roles, is rejected with
+/// [`LastManager`](super::Code::LastManager).
 pub const USER: &str = "/user";
 
+/// The API endpoint for [`Webhook`](crate::schema::Webhook)s, which [`Event`](super::Event)s are delivered to.
+///
+/// Accepts a `DELETE` request with a [`Delete`](super::request::Delete) body of webhook [`Id`](winvoice_schema::Id)s,
+/// a `GET` request, and a `PUT` request with a [`Put`](super::request::Put) body of the
+/// [`EventKind`](super::EventKind)s, secret, and URL only.
+///
+/// Each [`Event`](super::Event) of a subscribed kind is `POST`ed to the URL as JSON, signed by the secret as described
+/// by [`WEBHOOK_SIGNATURE_HEADER`](super::WEBHOOK_SIGNATURE_HEADER). Deliveries which fail are retried with an
+/// exponential backoff.
+pub const WEBHOOK: &str = "/webhook";
+
+/// The API endpoint for the log of [`Delivery`](crate::schema::Delivery)s to a [`Webhook`](crate::schema::Webhook).
+///
+/// Accepts a `POST` request with a [`Post`](super::request::Post) body of the webhook's
+/// [`Id`](winvoice_schema::Id) only. Responds with a [`Post`](super::response::Post) of its deliveries, most recent
+/// first.
+pub const WEBHOOK_DELIVERIES: &str = "/webhook/deliveries";
+
 /// The API endpoint for retrieving the currently logged in [`User`](crate::schema::User)'s information.
 ///
 /// Like [`LOGOUT`], takes a `POST` request with no body.
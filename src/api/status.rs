@@ -2,12 +2,14 @@
 
 mod as_ref;
 mod code;
+mod violation;
 
 #[cfg(feature = "bin")]
 mod from;
 
 pub use code::Code;
 use serde::{Deserialize, Serialize};
+pub use violation::Violation;
 
 /// The status of an operation.
 #[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
@@ -18,6 +20,14 @@ pub struct Status
 
 	/// The specific message attached to the status.
 	message: String,
+
+	/// The values of the request which were not valid, if the [`code`](Self::code) is [`Code::ValidationError`].
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	violations: Vec<Violation>,
+
+	/// Problems which did not prevent the operation from completing, but which the client may want to know about.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	warnings: Vec<String>,
 }
 
 impl Status
@@ -38,6 +48,34 @@ impl Status
 	/// Create a new [`Status`].
 	pub const fn new(code: Code, message: String) -> Self
 	{
-		Self { code, message }
+		Self { code, message, violations: Vec::new(), warnings: Vec::new() }
+	}
+
+	/// The values of the request which were not valid, if the [`code`](Self::code) is [`Code::ValidationError`].
+	#[allow(dead_code)]
+	pub fn violations(&self) -> &[Violation]
+	{
+		self.violations.as_ref()
+	}
+
+	/// Problems which did not prevent the operation from completing, but which the client may want to know about.
+	#[allow(dead_code)]
+	pub fn warnings(&self) -> &[String]
+	{
+		self.warnings.as_ref()
+	}
+
+	/// Attach `violations` to this [`Status`].
+	#[allow(clippy::missing_const_for_fn)] // destructor cannot be evaluated at compile-time
+	pub fn with_violations(self, violations: Vec<Violation>) -> Self
+	{
+		Self { violations, ..self }
+	}
+
+	/// Attach `warnings` to this [`Status`].
+	#[allow(clippy::missing_const_for_fn)] // destructor cannot be evaluated at compile-time
+	pub fn with_warnings(self, warnings: Vec<String>) -> Self
+	{
+		Self { warnings, ..self }
 	}
 }
@@ -18,6 +18,12 @@ pub enum Code
 	/// the server.
 	ApiVersionMismatch = 10,
 
+	/// An attachment was larger than the server allows.
+	AttachmentTooLarge = 22,
+
+	/// An attachment was of a MIME type which the server does not allow.
+	AttachmentTypeNotAllowed = 23,
+
 	/// Specific arguments that were used to start the server were not accepted by the
 	/// database.
 	BadArguments = 4,
@@ -34,6 +40,29 @@ pub enum Code
 	/// An error occurred while attempting to exchange [currencies](winvoice_schema::Currency).
 	ExchangeError = 15,
 
+	/// A request was sent with the same [idempotency key](crate::api::IDEMPOTENCY_HEADER) as another request which
+	/// has not finished yet.
+	IdempotencyKeyInUse = 21,
+
+	/// A request was sent with the same [idempotency key](crate::api::IDEMPOTENCY_HEADER) as an earlier request, but
+	/// with a different body.
+	IdempotencyKeyReused = 30,
+
+	/// A request referred to something which did not exist, or was not of the expected type (e.g. an operation in a
+	/// batch referring to another operation's result, or a saved query for another route).
+	InvalidReference = 20,
+
+	/// A resource was asked to change its state (e.g. a [`Job`](winvoice_schema::Job) being closed), but its
+	/// current state does not allow it.
+	InvalidTransition = 19,
+
+	/// A request had a body, but its `Content-Type` header was missing or did not match the kind of body the endpoint
+	/// accepts.
+	InvalidContentType = 25,
+
+	/// A header which the request needed was missing, or could not be read.
+	InvalidHeader = 26,
+
 	/// There was an attempt to log in, but it failed because the credentials provided were not
 	/// accepted by the database.
 	InvalidCredentials = 2,
@@ -45,6 +74,14 @@ pub enum Code
 	/// The requested operation has completed without error.
 	Success = 1,
 
+	/// A change to some [`User`](crate::schema::User)s would have left no user who can update users, or no user who
+	/// can update [`Role`](crate::schema::Role)s, so nobody could ever change the permissions of users again.
+	LastManager = 29,
+
+	/// A [`Location`](winvoice_schema::Location) was asked to move inside of itself, or inside of one of the
+	/// locations which are inside of it.
+	LocationCycle = 27,
+
 	/// Valid credentials were provided, and then an error occurred when attempting to login.
 	LoginError = 9,
 
@@ -59,6 +96,25 @@ pub enum Code
 	/// user.
 	PermissionsError = 11,
 
+	/// A [`Timesheet`](winvoice_schema::Timesheet) would have covered the same time as another timesheet of the
+	/// same [`Employee`](winvoice_schema::Employee).
+	TimesheetOverlap = 18,
+
+	/// A [`Timesheet`](winvoice_schema::Timesheet) was expected to be running for a user's
+	/// [`Employee`](winvoice_schema::Employee), but none was.
+	TimesheetNotRunning = 17,
+
+	/// A [`Timesheet`](winvoice_schema::Timesheet) could not be started because one is already running for a
+	/// user's [`Employee`](winvoice_schema::Employee).
+	TimesheetRunning = 16,
+
+	/// The body of a request was larger than the server allows.
+	RequestTooLarge = 31,
+
+	/// A [`Role`](crate::schema::Role) could not be deleted because some [`User`](crate::schema::User)s still have
+	/// it, and no other role was given to reassign them to.
+	RoleInUse = 28,
+
 	/// The SQL which was generated from a [`winvoice_match`] was incorrect. This is likely a bug
 	/// in Winvoice.
 	SqlError = 8,
@@ -66,4 +122,9 @@ pub enum Code
 	/// A user has attempted to perform an operation on the database while not having the correct
 	/// permissions.
 	Unauthorized = 3,
+
+	/// Some values in the body of a request were not valid (e.g. an empty name, or a
+	/// [`Timesheet`](winvoice_schema::Timesheet) which ends before it begins). The [`Status`](super::Status) lists
+	/// each of them as a [`Violation`](super::Violation).
+	ValidationError = 24,
 }
@@ -13,6 +13,8 @@ impl Display for Code
 			{
 				"The API version specified in the version header is incompatible with the version running on the server"
 			},
+			Self::AttachmentTooLarge => "The attachment is larger than the server allows",
+			Self::AttachmentTypeNotAllowed => "The attachment is of a type which the server does not allow",
 			Self::BadArguments =>
 			{
 				"Specific arguments that were used to start the server were not accepted by the database. If you are a \
@@ -20,12 +22,37 @@ impl Display for Code
 			},
 			Self::CryptError => "An error occurred while decrypting sensitive data",
 			Self::Database => "There was an issue while interfacing with the database adapter",
+			Self::IdempotencyKeyInUse =>
+			{
+				"Another request with the same idempotency key has not finished yet. Try again once it has"
+			},
+			Self::IdempotencyKeyReused =>
+			{
+				"The idempotency key was already used by a request with a different body. Send a new key with each new \
+				 request"
+			},
+			Self::InvalidContentType =>
+			{
+				"The `Content-Type` of the request body is missing, or is not one which the endpoint accepts"
+			},
+			Self::InvalidHeader => "A header which the request needs is missing, or could not be read",
+			Self::InvalidReference => "The request referred to something which did not exist, or was of the wrong type",
+			Self::InvalidTransition =>
+			{
+				"A resource was asked to change its state, but its current state does not allow it"
+			},
 			Self::InvalidCredentials =>
 			{
 				"There was an attempt to log in, but it failed because the credentials provided were incorrect"
 			},
 			Self::EncodingError => "An error occurred while attempting to de/encode a value",
 			Self::ExchangeError => "An error occurred while attempting to exchange currencies",
+			Self::LastManager =>
+			{
+				"The change would leave nobody who can update users or roles, so it was not made. Give another user \
+				 that permission first"
+			},
+			Self::LocationCycle => "A location cannot be moved inside of itself, or inside of a location within it",
 			Self::LoginError => "Valid credentials were provided, and then an error occurred when attempting to login",
 			Self::Other => "An unknown operation occurred",
 			Self::PasswordExpired => "A valid login was presented, but the password has expired and must be changed",
@@ -33,6 +60,8 @@ impl Display for Code
 			{
 				"An error occurred while attempting to resolve the permissions of this request's active user"
 			},
+			Self::RequestTooLarge => "The body of the request is larger than the server allows",
+			Self::RoleInUse => "Some users still have the role, and no other role was given to reassign them to",
 			Self::SqlError =>
 			{
 				"The SQL which was generated from a `winvoice_match` was incorrect. This is likely a bug in Winvoice"
@@ -43,10 +72,14 @@ impl Display for Code
 				"The requested operation has completed without error, but some actions may have been skipped because \
 				 of missing permissions"
 			},
+			Self::TimesheetOverlap => "A timesheet would cover the same time as another timesheet of the same employee",
+			Self::TimesheetNotRunning => "There is no running timesheet for this user's employee record",
+			Self::TimesheetRunning => "There is already a running timesheet for this user's employee record",
 			Self::Unauthorized =>
 			{
 				"A user has attempted to perform an operation while not having the correct permissions"
 			},
+			Self::ValidationError => "Some values in the request are not valid",
 		}
 		.fmt(f)
 	}
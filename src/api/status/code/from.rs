@@ -24,11 +24,27 @@ impl From<Code> for StatusCode
 		match code
 		{
 			Code::ApiVersionMismatch => Self::GONE,
-			Code::InvalidCredentials | Code::PasswordExpired => Self::UNPROCESSABLE_ENTITY,
+			Code::AttachmentTooLarge | Code::RequestTooLarge => Self::PAYLOAD_TOO_LARGE,
+			Code::AttachmentTypeNotAllowed | Code::InvalidContentType => Self::UNSUPPORTED_MEDIA_TYPE,
+			Code::IdempotencyKeyReused |
+			Code::InvalidCredentials |
+			Code::PasswordExpired |
+			Code::ValidationError => Self::UNPROCESSABLE_ENTITY,
 			Code::Success | Code::SuccessForPermissions => Self::OK,
+			Code::IdempotencyKeyInUse |
+			Code::InvalidTransition |
+			Code::LastManager |
+			Code::LocationCycle |
+			Code::RoleInUse |
+			Code::TimesheetNotRunning |
+			Code::TimesheetOverlap |
+			Code::TimesheetRunning => Self::CONFLICT,
 			Code::Unauthorized => Self::FORBIDDEN,
 
-			Code::ApiVersionHeaderMissing | Code::EncodingError => Self::BAD_REQUEST,
+			Code::ApiVersionHeaderMissing |
+			Code::EncodingError |
+			Code::InvalidHeader |
+			Code::InvalidReference => Self::BAD_REQUEST,
 
 			Code::BadArguments |
 			Code::CryptError |
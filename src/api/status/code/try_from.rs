@@ -14,19 +14,35 @@ impl TryFrom<u8> for Code
 		{
 			v if v == Self::ApiVersionHeaderMissing as u8 => Self::ApiVersionHeaderMissing,
 			v if v == Self::ApiVersionMismatch as u8 => Self::ApiVersionMismatch,
+			v if v == Self::AttachmentTooLarge as u8 => Self::AttachmentTooLarge,
+			v if v == Self::AttachmentTypeNotAllowed as u8 => Self::AttachmentTypeNotAllowed,
 			v if v == Self::BadArguments as u8 => Self::BadArguments,
 			v if v == Self::CryptError as u8 => Self::CryptError,
 			v if v == Self::Database as u8 => Self::Database,
 			v if v == Self::EncodingError as u8 => Self::EncodingError,
+			v if v == Self::IdempotencyKeyInUse as u8 => Self::IdempotencyKeyInUse,
+			v if v == Self::IdempotencyKeyReused as u8 => Self::IdempotencyKeyReused,
+			v if v == Self::InvalidContentType as u8 => Self::InvalidContentType,
 			v if v == Self::InvalidCredentials as u8 => Self::InvalidCredentials,
+			v if v == Self::InvalidHeader as u8 => Self::InvalidHeader,
+			v if v == Self::InvalidReference as u8 => Self::InvalidReference,
+			v if v == Self::InvalidTransition as u8 => Self::InvalidTransition,
+			v if v == Self::LastManager as u8 => Self::LastManager,
+			v if v == Self::LocationCycle as u8 => Self::LocationCycle,
 			v if v == Self::LoginError as u8 => Self::LoginError,
 			v if v == Self::Other as u8 => Self::Other,
 			v if v == Self::PasswordExpired as u8 => Self::PasswordExpired,
 			v if v == Self::PermissionsError as u8 => Self::PermissionsError,
+			v if v == Self::RequestTooLarge as u8 => Self::RequestTooLarge,
+			v if v == Self::RoleInUse as u8 => Self::RoleInUse,
 			v if v == Self::SqlError as u8 => Self::SqlError,
 			v if v == Self::Success as u8 => Self::Success,
 			v if v == Self::SuccessForPermissions as u8 => Self::SuccessForPermissions,
+			v if v == Self::TimesheetNotRunning as u8 => Self::TimesheetNotRunning,
+			v if v == Self::TimesheetOverlap as u8 => Self::TimesheetOverlap,
+			v if v == Self::TimesheetRunning as u8 => Self::TimesheetRunning,
 			v if v == Self::Unauthorized as u8 => Self::Unauthorized,
+			v if v == Self::ValidationError as u8 => Self::ValidationError,
 
 			// HACK: `TryFromIntError` has a private constructor… why?
 			_ => return Err(<u8 as TryFrom<u16>>::try_from(300).unwrap_err()),
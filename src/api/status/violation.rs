@@ -0,0 +1,35 @@
+//! Contains a [`Violation`] of the rules which the values in a request must follow.
+
+use serde::{Deserialize, Serialize};
+
+/// A value in the body of a request which is not valid, as reported with a [`Code::ValidationError`](super::Code).
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Violation
+{
+	/// What is wrong with the value.
+	message: String,
+
+	/// The path to the value in the body of the request, e.g. `entities[0].name` or `args[1]`.
+	path: String,
+}
+
+impl Violation
+{
+	/// Create a new [`Violation`].
+	pub const fn new(path: String, message: String) -> Self
+	{
+		Self { message, path }
+	}
+
+	/// What is wrong with the value.
+	pub fn message(&self) -> &str
+	{
+		self.message.as_ref()
+	}
+
+	/// The path to the value in the body of the request, e.g. `entities[0].name` or `args[1]`.
+	pub fn path(&self) -> &str
+	{
+		self.path.as_ref()
+	}
+}
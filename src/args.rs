@@ -1,6 +1,14 @@
+#[cfg(feature = "postgres")]
+mod action;
+#[cfg(feature = "postgres")]
+mod backup;
 mod command;
 #[cfg(feature = "postgres")]
+mod import;
+#[cfg(feature = "postgres")]
 mod postgres;
+#[cfg(feature = "postgres")]
+mod restore;
 
 use core::time::Duration;
 use std::{net::SocketAddr, path::PathBuf};
@@ -35,6 +43,7 @@ use {
 use crate::{
 	dyn_result::{DynError, DynResult},
 	lock,
+	server::{Attachments, Bootstrap, Ldap, LocalStorage, LogFileNotifier, Oidc, PasswordResets, SmtpNotifier},
 	utils,
 };
 
@@ -50,6 +59,41 @@ pub struct Args
 	#[arg(default_value = "127.0.0.1:3000", long, short, value_name = "IP:PORT")]
 	address: SocketAddr,
 
+	/// The password of the first user, which is created when there are no users. It must not be empty, and must be
+	/// changed upon the first login.
+	///
+	/// When neither this nor `--admin-password-file` is specified, a password is generated and printed.
+	#[arg(conflicts_with = "admin_password_file", env = "WINVOICE_ADMIN_PASSWORD", long, value_name = "PASSWORD")]
+	admin_password: Option<String>,
+
+	/// A file which contains the `--admin-password`. Trailing whitespace is ignored.
+	#[arg(env = "WINVOICE_ADMIN_PASSWORD_FILE", long, value_name = "FILE")]
+	admin_password_file: Option<PathBuf>,
+
+	/// How long the passwords of users with the role of the first user last before they must be changed.
+	///
+	/// When unspecified, they last forever.
+	#[arg(env = "WINVOICE_ADMIN_PASSWORD_TTL", long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+	admin_password_ttl: Option<Duration>,
+
+	/// The username of the first user, which is created when there are no users.
+	#[arg(default_value = "admin", env = "WINVOICE_ADMIN_USERNAME", long, value_name = "USERNAME")]
+	admin_username: String,
+
+	/// The directory where files attached to expenses are stored.
+	///
+	/// When unspecified, uses the `winvoice-server/attachments` folder of [`dirs::data_local_dir`].
+	#[arg(long, value_name = "DIR")]
+	attachment_dir: Option<PathBuf>,
+
+	/// The largest file which may be attached to an expense, in bytes.
+	#[arg(default_value_t = 10 * 1024 * 1024, long, value_name = "BYTES")]
+	attachment_max_size: usize,
+
+	/// The MIME types of the files which may be attached to expenses, separated by commas.
+	#[arg(default_value = "application/pdf,image/jpeg,image/png", long, value_delimiter = ',', value_name = "TYPES")]
+	attachment_types: Vec<String>,
+
 	/// The file containing the certificate to use for TLS. Must be in PEM format.
 	#[arg(long, short, requires = "key", value_name = "FILE")]
 	certificate: Option<PathBuf>,
@@ -84,10 +128,50 @@ pub struct Args
 	#[arg(long, short = 'O', value_name = "FILE")]
 	cors_allow_origin: PathBuf,
 
+	/// The amount of time that the response to a `PUT` request with an idempotency key is stored, and returned
+	/// to any request with the same key instead of creating another entity.
+	#[arg(
+		default_value = "24hr",
+		long,
+		short = 'i',
+		value_name = "DURATION",
+		value_parser = humantime::parse_duration,
+	)]
+	idempotency_ttl: Duration,
+
 	/// The file containing the key to use for TLS. Must be in PEM format.
 	#[arg(long, short, requires = "certificate", value_name = "FILE")]
 	key: Option<PathBuf>,
 
+	/// The DN which the groups of users are searched for under in the `--ldap-url`. The first of them whose common name
+	/// is the name of a role becomes the role of the user.
+	///
+	/// When unspecified, users have no groups.
+	#[arg(long, value_name = "DN")]
+	ldap_group_base: Option<String>,
+
+	/// The filter which finds the groups of a user under the `--ldap-group-base`, where `{dn}` is the DN of the user
+	/// and `{username}` is their username.
+	#[arg(default_value = "(member={dn})", long, value_name = "FILTER")]
+	ldap_group_filter: String,
+
+	/// Create the users who are authenticated by the `--ldap-url` but do not exist yet, as long as one of their groups
+	/// is the name of a role.
+	#[arg(long)]
+	ldap_provision: bool,
+
+	/// The URL of an LDAP directory (e.g. Active Directory) which users are authenticated with, by binding as them.
+	/// When it does not accept their credentials, they are checked against the passwords of the users in the database
+	/// instead (e.g. for the first user). The users which the directory manages (i.e. which it created) are never
+	/// checked against their password in the database, and the directory never logs in a user which it does not manage.
+	#[arg(env = "WINVOICE_LDAP_URL", long, requires = "ldap_user_dn", value_name = "URL")]
+	ldap_url: Option<String>,
+
+	/// The DN which is bound as to authenticate a user with the `--ldap-url`, where `{username}` is their username
+	/// (e.g. `uid={username},ou=people,dc=example,dc=com`, or `{username}@example.com` for Active Directory).
+	#[arg(env = "WINVOICE_LDAP_USER_DN", long, value_name = "DN")]
+	ldap_user_dn: Option<String>,
+
 	/// The directory where the log is stored.
 	///
 	/// When unspecified, uses [`dirs::state_dir`] or [`dirs::data_local_dir`]— whichever can be
@@ -113,6 +197,59 @@ pub struct Args
 	)]
 	log_level: LevelFilter,
 
+	/// The file which messages to users (such as the links which reset their passwords) are appended to, for an
+	/// administrator to pass on. Ignored when `--smtp-address` is specified.
+	///
+	/// When unspecified, uses `notifications.log` in the `--log-dir`.
+	#[arg(long, value_name = "FILE")]
+	notification_log: Option<PathBuf>,
+
+	/// The client ID which the server is registered with at the `--oidc-issuer`.
+	#[arg(env = "WINVOICE_OIDC_CLIENT_ID", long, value_name = "ID")]
+	oidc_client_id: Option<String>,
+
+	/// The client secret which the server is registered with at the `--oidc-issuer`, if it has one.
+	#[arg(env = "WINVOICE_OIDC_CLIENT_SECRET", long, value_name = "SECRET")]
+	oidc_client_secret: Option<String>,
+
+	/// The claim of the ID tokens from the `--oidc-issuer` which lists the groups of a user. The first of them which
+	/// is the name of a role becomes the role of the user.
+	#[arg(default_value = "groups", long, value_name = "CLAIM")]
+	oidc_groups_claim: String,
+
+	/// The URL of an OpenID Connect identity provider which users may log in through, in addition to logging in with
+	/// a password.
+	#[arg(
+		env = "WINVOICE_OIDC_ISSUER",
+		long,
+		requires_all = ["oidc_client_id", "oidc_redirect_url"],
+		value_name = "URL",
+	)]
+	oidc_issuer: Option<String>,
+
+	/// Create the users who log in through the `--oidc-issuer` but do not exist yet, as long as one of their groups is
+	/// the name of a role.
+	#[arg(long)]
+	oidc_provision: bool,
+
+	/// Where the `--oidc-issuer` sends users after they log in, which must be registered with it. The page there should
+	/// pass the query parameters it was sent with on to the server to finish logging in.
+	#[arg(env = "WINVOICE_OIDC_REDIRECT_URL", long, value_name = "URL")]
+	oidc_redirect_url: Option<String>,
+
+	/// The scopes which are requested from the `--oidc-issuer` (in addition to `openid`), separated by commas.
+	#[arg(default_value = "profile,email", long, value_delimiter = ',', value_name = "SCOPES")]
+	oidc_scopes: Vec<String>,
+
+	/// The claim of the ID tokens from the `--oidc-issuer` which is the username of a user who is created by the
+	/// `--oidc-provision`. Users are found by their `sub` claim, not their username.
+	#[arg(default_value = "preferred_username", long, value_name = "CLAIM")]
+	oidc_username_claim: String,
+
+	/// The amount of time that the link which resets the password of a user is valid for.
+	#[arg(default_value = "15min", long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+	password_reset_ttl: Duration,
+
 	/// A [`casbin`] model. See [the docs](https://casbin.org/docs/supported-models) for more
 	/// information.
 	///
@@ -136,6 +273,18 @@ pub struct Args
 	)]
 	session_ttl: Duration,
 
+	/// The `HOST:PORT` of an SMTP relay which mail is sent through to deliver messages to users (such as the links
+	/// which reset their passwords). The relay must accept mail without authentication or TLS (e.g. one running on
+	/// the same host), and the usernames of users must be their email addresses.
+	///
+	/// When unspecified, messages are appended to the `--notification-log` instead.
+	#[arg(long, value_name = "HOST:PORT")]
+	smtp_address: Option<String>,
+
+	/// The address which mail sent through the `--smtp-address` is from.
+	#[arg(default_value = "winvoice@localhost", long, value_name = "ADDRESS")]
+	smtp_from: String,
+
 	/// The maximum duration to run commands server before timing out (e.g. "5s", "15min").
 	///
 	/// When this argument is passed without a value (i.e. `--timeout`), a duration of 30 seconds
@@ -156,7 +305,17 @@ impl Args
 	/// Run the Winvoice server.
 	pub async fn run(self) -> DynResult<()>
 	{
-		let _guard = init_tracing(self.log_level, self.log_dir, &self.log_rotation)?;
+		let log_dir = self
+			.log_dir
+			.or_else(|| {
+				dirs::state_dir().or_else(dirs::data_local_dir).map(|mut d| {
+					d.push("winvoice-server");
+					d
+				})
+			})
+			.ok_or_else(|| "Could not find suitable `--log-dir`. Please specify it manually.".to_owned())?;
+
+		let _guard = init_tracing(self.log_level, log_dir.clone(), &self.log_rotation)?;
 
 		let model_path = self.permissions_model.map(|m| -> &'static str { m.leak() });
 		let policy_path: &'static str = self.permissions_policy.leak();
@@ -176,6 +335,72 @@ impl Args
 			_ => None,
 		};
 
+		let attachments = {
+			let dir = self
+				.attachment_dir
+				.or_else(|| {
+					dirs::data_local_dir().map(|mut d| {
+						d.push("winvoice-server");
+						d.push("attachments");
+						d
+					})
+				})
+				.ok_or_else(|| "Could not find suitable `--attachment-dir`. Please specify it manually.".to_owned())?;
+
+			Attachments::new(self.attachment_max_size, LocalStorage::new(dir), self.attachment_types)
+		};
+
+		let admin_password = match self.admin_password_file
+		{
+			Some(f) => Some(fs::read_to_string(f).await?.trim_end().to_owned()),
+			None => self.admin_password,
+		};
+
+		if admin_password.as_deref().map_or(false, |p| p.trim().is_empty())
+		{
+			return Err("The `--admin-password` must not be empty".to_owned().into());
+		}
+
+		let password_resets = match self.smtp_address
+		{
+			Some(a) => PasswordResets::new(SmtpNotifier::new(a, self.smtp_from), self.password_reset_ttl),
+			None => PasswordResets::new(
+				LogFileNotifier::new(self.notification_log.unwrap_or_else(|| log_dir.join("notifications.log"))),
+				self.password_reset_ttl,
+			),
+		};
+
+		let ldap = match (self.ldap_url, self.ldap_user_dn)
+		{
+			(Some(_), Some(dn)) if !dn.contains("{username}") =>
+			{
+				return Err("`--ldap-user-dn` must contain `{username}`".to_owned().into());
+			},
+			(Some(url), Some(dn)) =>
+			{
+				let ldap = Ldap::new(url, dn).with_provision(self.ldap_provision);
+				Some(match self.ldap_group_base
+				{
+					Some(base) => ldap.with_groups(base, self.ldap_group_filter),
+					None => ldap,
+				})
+			},
+			_ => None,
+		};
+
+		let oidc = match (self.oidc_issuer, self.oidc_client_id, self.oidc_redirect_url)
+		{
+			(Some(issuer), Some(client_id), Some(redirect_url)) => Some(
+				Oidc::discover(issuer, client_id, self.oidc_client_secret, redirect_url)
+					.await?
+					.with_claims(self.oidc_username_claim, self.oidc_groups_claim)
+					.with_provision(self.oidc_provision)
+					.with_scopes(&self.oidc_scopes),
+			),
+			_ => None,
+		};
+
+		let bootstrap = Bootstrap::new(admin_password, self.admin_password_ttl, self.admin_username);
 		let origins = origins_file.lines().into_iter().map(HeaderValue::from_str).collect::<Result<Vec<_>, _>>()?;
 
 		#[cfg(feature = "watchman")]
@@ -191,10 +416,16 @@ impl Args
 					$(#[cfg(feature = $feature)]
 					Command::$Variant(p) => p.run(
 						self.address,
+						attachments,
+						bootstrap,
 						self.connection_idle,
 						self.cookie_domain,
 						self.cookie_secret.unwrap_or_else(utils::cookie_secret),
 						origins,
+						self.idempotency_ttl,
+						ldap,
+						oidc,
+						password_resets,
 						permissions,
 						self.session_ttl,
 						self.timeout,
@@ -210,17 +441,8 @@ impl Args
 
 /// Initialize [`tracing`] using the [`tracing_appender`] implementation of
 /// [`tracing_subscriber`].
-fn init_tracing(log_level: LevelFilter, log_dir: Option<PathBuf>, log_rotation: &str) -> DynResult<WorkerGuard>
+fn init_tracing(log_level: LevelFilter, dir: PathBuf, log_rotation: &str) -> DynResult<WorkerGuard>
 {
-	let dir = log_dir
-		.or_else(|| {
-			dirs::state_dir().or_else(dirs::data_local_dir).map(|mut d| {
-				d.push("winvoice-server");
-				d
-			})
-		})
-		.ok_or_else(|| "Could not find suitable `--log-dir`. Please specify it manually.".to_owned())?;
-
 	let (non_blocking, guard) = tracing_appender::non_blocking(match log_rotation
 	{
 		"daily" => tracing_appender::rolling::daily,
@@ -0,0 +1,35 @@
+use clap::Subcommand;
+use sqlx::{Database, Executor};
+
+use crate::{schema::Adapter, server::ServerState, DynResult};
+
+/// A task which Winvoice can perform on the database instead of running the server.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Action
+{
+	#[allow(missing_docs)]
+	Backup(super::backup::Backup),
+
+	#[allow(missing_docs)]
+	Import(super::import::Import),
+
+	#[allow(missing_docs)]
+	Restore(super::restore::Restore),
+}
+
+impl Action
+{
+	/// Perform the task.
+	pub async fn run<A>(self, state: &ServerState<A::Db>) -> DynResult<()>
+	where
+		A: Adapter,
+		for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+	{
+		match self
+		{
+			Self::Backup(b) => b.run::<A>(state).await,
+			Self::Import(i) => i.run::<A>(state).await,
+			Self::Restore(r) => r.run::<A>(state).await,
+		}
+	}
+}
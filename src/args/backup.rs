@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use casbin::MgmtApi;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use winvoice_adapter::Retrievable;
+use winvoice_schema::{Contact, Department, Employee, Job, Location, Organization, Timesheet};
+
+use crate::{
+	schema::{
+		Adapter,
+		Budget,
+		BudgetAdapter,
+		Identity,
+		IdentityAdapter,
+		Rate,
+		RateAdapter,
+		Review,
+		ReviewAdapter,
+		ReviewStatus,
+		Role,
+		User,
+	},
+	server::ServerState,
+	DynResult,
+};
+
+/// The version of the [`Archive`] format which [`Backup`] writes, and the newest which
+/// [`Restore`](super::restore::Restore) reads.
+///
+/// * Version 2 added the `budgets`, `rates`, and `reviews`, which are empty when a version 1 archive is read.
+/// * Version 3 added the `identities`, which are empty when an older archive is read.
+pub(super) const VERSION: u32 = 3;
+
+/// Everything in a Winvoice database, in a form which does not depend on the adapter that it came from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct Archive
+{
+	/// The [`Budget`] of every [`Job`] which has one.
+	#[serde(default)]
+	pub(super) budgets: Vec<Budget>,
+
+	/// Every [`Contact`].
+	pub(super) contacts: Vec<Contact>,
+
+	/// Every [`Department`].
+	pub(super) departments: Vec<Department>,
+
+	/// Every [`Employee`].
+	pub(super) employees: Vec<Employee>,
+
+	/// The [`Identity`] of every [`User`] at an identity provider.
+	#[serde(default)]
+	pub(super) identities: Vec<Identity>,
+
+	/// The role assignments of the permissions policy (i.e. the `g` rules), without the `g`.
+	pub(super) grouping_policy: Vec<Vec<String>>,
+
+	/// Every [`Job`].
+	pub(super) jobs: Vec<Job>,
+
+	/// Every [`Location`].
+	pub(super) locations: Vec<Location>,
+
+	/// Every [`Organization`].
+	pub(super) organizations: Vec<Organization>,
+
+	/// The rules of the permissions policy (i.e. the `p` rules), without the `p`.
+	pub(super) policy: Vec<Vec<String>>,
+
+	/// Every [`Rate`].
+	#[serde(default)]
+	pub(super) rates: Vec<Rate>,
+
+	/// The [`Review`] of every [`Timesheet`] which has been submitted.
+	#[serde(default)]
+	pub(super) reviews: Vec<Review>,
+
+	/// Every [`Role`].
+	pub(super) roles: Vec<Role>,
+
+	/// Every [`Timesheet`], and its [`Expense`](winvoice_schema::Expense)s.
+	pub(super) timesheets: Vec<Timesheet>,
+
+	/// Every [`User`], and the hash of their password (which is not serialized with the user).
+	pub(super) users: Vec<(User, String)>,
+
+	/// The version of the format. See [`VERSION`].
+	pub(super) version: u32,
+}
+
+impl Archive
+{
+	/// Retrieve everything in the database of the `state`, and its permissions.
+	pub(super) async fn retrieve<A>(state: &ServerState<A::Db>) -> DynResult<Self>
+	where
+		A: Adapter,
+	{
+		let pool = state.pool();
+		let (contacts, departments, employees, jobs, locations, organizations, roles, timesheets, users) =
+			futures::try_join!(
+				A::Contact::retrieve(pool, Default::default()),
+				A::Department::retrieve(pool, Default::default()),
+				A::Employee::retrieve(pool, Default::default()),
+				A::Job::retrieve(pool, Default::default()),
+				A::Location::retrieve(pool, Default::default()),
+				A::Organization::retrieve(pool, Default::default()),
+				A::Role::retrieve(pool, Default::default()),
+				A::Timesheet::retrieve(pool, Default::default()),
+				A::User::retrieve(pool, Default::default()),
+			)?;
+
+		let ids = timesheets.iter().map(|t| t.id).collect::<Vec<_>>();
+		let mut reviews = A::Review::retrieve(pool, &ids).await?;
+		reviews.retain(|r| r.status() != ReviewStatus::Draft);
+
+		let job_ids = jobs.iter().map(|j| j.id).collect::<Vec<_>>();
+		let user_ids = users.iter().map(User::id).collect::<Vec<_>>();
+		let (budgets, identities, rates) = futures::try_join!(
+			A::Budget::retrieve(pool, &job_ids),
+			A::Identity::retrieve(pool, &user_ids),
+			A::Rate::retrieve(pool),
+		)?;
+
+		let (grouping_policy, policy) = {
+			let permissions = state.permissions().read().await;
+			(permissions.get_grouping_policy(), permissions.get_policy())
+		};
+
+		Ok(Self {
+			budgets,
+			contacts,
+			departments,
+			employees,
+			grouping_policy,
+			identities,
+			jobs,
+			locations,
+			organizations,
+			policy,
+			rates,
+			reviews,
+			roles,
+			timesheets,
+			users: users
+				.into_iter()
+				.map(|u| {
+					let hash = u.password().to_owned();
+					(u, hash)
+				})
+				.collect(),
+			version: VERSION,
+		})
+	}
+}
+
+/// Write every entity, role, user, and permission in the database to a JSON file, which can be restored into
+/// another database with `restore`.
+///
+/// Attachments, saved queries, and webhooks are not included.
+#[derive(Args, Clone, Debug)]
+pub struct Backup
+{
+	/// The file to write the backup to.
+	#[arg(value_name = "FILE")]
+	file: PathBuf,
+}
+
+impl Backup
+{
+	/// Write the backup.
+	pub async fn run<A>(self, state: &ServerState<A::Db>) -> DynResult<()>
+	where
+		A: Adapter,
+	{
+		let archive = Archive::retrieve::<A>(state).await?;
+		fs::write(&self.file, serde_json::to_vec_pretty(&archive)?).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use axum::http::HeaderMap;
+use clap::{
+	builder::{PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use sqlx::{Database, Executor};
+use tokio::fs;
+use winvoice_adapter::Retrievable;
+
+use crate::{
+	api::request::{self, ImportKind},
+	r#match::MatchUser,
+	schema::Adapter,
+	server::{import, ServerState},
+	DynResult,
+};
+
+/// Create an entity for each row of a CSV file, the same way as the import endpoint.
+#[derive(Args, Clone, Debug)]
+pub struct Import
+{
+	/// Which column of the file a field is in, as `FIELD=HEADER`. Fields which are not given are read from the column
+	/// with the same header as the field.
+	#[arg(long, short, value_name = "FIELD=HEADER", value_parser = parse_column)]
+	column: Vec<(String, String)>,
+
+	/// Check every row, but do not save anything.
+	#[arg(long, short)]
+	dry_run: bool,
+
+	/// The CSV file to import, including its header row.
+	#[arg(value_name = "FILE")]
+	file: PathBuf,
+
+	/// What kind of entity each row is.
+	#[arg(
+		long,
+		short,
+		value_parser = PossibleValuesParser::new(["expense", "job", "organization", "timesheet"]).map(|k| {
+			match k.as_str()
+			{
+				"expense" => ImportKind::Expense,
+				"job" => ImportKind::Job,
+				"organization" => ImportKind::Organization,
+				_ => ImportKind::Timesheet,
+			}
+		}),
+	)]
+	kind: ImportKind,
+
+	/// The username of the user to import as. The rows are checked against the permissions of this user.
+	#[arg(long, short)]
+	user: String,
+}
+
+impl Import
+{
+	/// Import the file, and print the report.
+	pub async fn run<A>(self, state: &ServerState<A::Db>) -> DynResult<()>
+	where
+		A: Adapter,
+		for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+	{
+		let csv = fs::read_to_string(&self.file).await?;
+		let condition = MatchUser { username: self.user.clone().into(), ..Default::default() };
+		let user = A::User::retrieve(state.pool(), condition)
+			.await?
+			.pop()
+			.ok_or_else(|| format!("There is no user named {:?}", self.user))?;
+
+		let request = request::Import::new(self.column.into_iter().collect(), csv, self.dry_run, self.kind);
+		let response = import::execute::<A>(state, &user, &HeaderMap::new(), request).await.unwrap_or_else(|e| e);
+
+		println!("{}", serde_json::to_string_pretty(response.content())?);
+		match response.status().is_success()
+		{
+			true => Ok(()),
+			false => Err(response.content().status().message().into()),
+		}
+	}
+}
+
+/// Parse a `FIELD=HEADER` pair.
+fn parse_column(s: &str) -> Result<(String, String), String>
+{
+	s.split_once('=')
+		.map(|(field, header)| (field.to_owned(), header.to_owned()))
+		.ok_or_else(|| format!("{s:?} is not of the form `FIELD=HEADER`"))
+}
@@ -11,9 +11,10 @@ use sqlx::{
 };
 use winvoice_adapter_postgres::PgSchema;
 
+use super::action::Action;
 use crate::{
 	lock::Lock,
-	server::{Server, ServerState},
+	server::{Attachments, Bootstrap, Ldap, Oidc, PasswordResets, Server, ServerState},
 	DynResult,
 };
 
@@ -21,6 +22,10 @@ use crate::{
 #[derive(Args, Clone, Debug)]
 pub struct Postgres
 {
+	/// Instead of running the server, perform a task on the database and exit.
+	#[command(subcommand)]
+	action: Option<Action>,
+
 	/// The name of the database where Winvoice should perform its operations.
 	#[arg(env = "PGDATABASE", long, short)]
 	database: String,
@@ -76,10 +81,16 @@ impl Postgres
 	pub async fn run(
 		self,
 		address: SocketAddr,
+		attachments: Attachments,
+		bootstrap: Bootstrap,
 		connection_idle: Duration,
 		cookie_domain: Option<String>,
 		cookie_secret: Vec<u8>,
 		cors_allow_origin: Vec<HeaderValue>,
+		idempotency_ttl: Duration,
+		ldap: Option<Ldap>,
+		oidc: Option<Oidc>,
+		password_resets: PasswordResets,
 		permissions: Lock<Enforcer>,
 		session_ttl: Duration,
 		timeout: Option<Duration>,
@@ -117,12 +128,25 @@ impl Postgres
 		let pool =
 			PoolOptions::<sqlx::Postgres>::new().idle_timeout(connection_idle).connect_with(connect_options).await?;
 
+		let state = ServerState::new(permissions, pool);
+		if let Some(action) = self.action
+		{
+			Server::<PgSchema>::init(state.pool(), &bootstrap).await?;
+			return action.run::<PgSchema>(&state).await;
+		}
+
 		Server::<PgSchema>::new(address, tls)
 			.serve(
+				attachments,
+				bootstrap,
 				cookie_domain,
 				cookie_secret,
 				cors_allow_origin,
-				ServerState::new(permissions, pool),
+				idempotency_ttl,
+				ldap,
+				oidc,
+				password_resets,
+				state,
 				session_ttl,
 				timeout,
 			)
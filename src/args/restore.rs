@@ -0,0 +1,513 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use casbin::{CoreApi, Enforcer, MgmtApi};
+use clap::Args;
+use sqlx::{Database, Executor};
+use tokio::fs;
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		DepartmentAdapter,
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+	Updatable,
+};
+use winvoice_schema::{ContactKind, Id, Location};
+
+use super::backup::{Archive, VERSION};
+use crate::{
+	schema::{
+		Adapter,
+		Budget,
+		BudgetAdapter,
+		Identity,
+		IdentityAdapter,
+		Rate,
+		RateAdapter,
+		RateScope,
+		ReviewAdapter,
+		ReviewStatus,
+		RoleAdapter,
+		UserAdapter,
+	},
+	server::ServerState,
+	DynResult,
+};
+
+/// Read a file written by `backup`, and create everything in it. The entities are given new ids.
+///
+/// The database must be empty, except for the user which is created when the server first starts (which is replaced
+/// by the users in the backup). The permissions in the backup are added to the permissions policy.
+#[derive(Args, Clone, Debug)]
+pub struct Restore
+{
+	/// The file which the backup was written to.
+	#[arg(value_name = "FILE")]
+	file: PathBuf,
+}
+
+/// Get the restored version of the entity which had the `id` in the backup.
+fn restored<T>(entities: &HashMap<Id, T>, kind: &str, id: Id) -> Result<T, String>
+where
+	T: Clone,
+{
+	entities.get(&id).cloned().ok_or_else(|| format!("The backup refers to a {kind} ({id}) which it does not contain"))
+}
+
+impl Restore
+{
+	/// Restore the backup.
+	pub async fn run<A>(self, state: &ServerState<A::Db>) -> DynResult<()>
+	where
+		A: Adapter,
+		for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+	{
+		let archive: Archive = serde_json::from_slice(&fs::read(&self.file).await?)?;
+		if !(1..=VERSION).contains(&archive.version)
+		{
+			let message = format!("Version {} of the backup format is not supported", archive.version);
+			return Err(format!("{message} (expected 1 to {VERSION})").into());
+		}
+
+		let existing = Archive::retrieve::<A>(state).await?;
+		if !(existing.contacts.is_empty() &&
+			existing.departments.is_empty() &&
+			existing.employees.is_empty() &&
+			existing.jobs.is_empty() &&
+			existing.locations.is_empty() &&
+			existing.organizations.is_empty() &&
+			existing.timesheets.is_empty() &&
+			existing.roles.len() <= 1 &&
+			existing.users.len() <= 1)
+		{
+			return Err("The database must be empty in order to restore a backup into it".into());
+		}
+
+		let mut tx = state.pool().begin().await?;
+		A::User::delete(&mut *tx, existing.users.iter().map(|(u, _)| u)).await?;
+		A::Role::delete(&mut *tx, existing.roles.iter()).await?;
+
+		// outer locations must be created before the locations inside of them
+		let mut restored_locations = HashMap::<Id, Location>::with_capacity(archive.locations.len());
+		let mut pending = archive.locations;
+		while !pending.is_empty()
+		{
+			let (ready, rest): (Vec<_>, Vec<_>) = pending
+				.into_iter()
+				.partition(|l| l.outer.as_ref().map_or(true, |o| restored_locations.contains_key(&o.id)));
+
+			if ready.is_empty()
+			{
+				return Err("The locations of the backup refer to outer locations which it does not contain".into());
+			}
+
+			for l in ready
+			{
+				let outer = l.outer.as_ref().map(|o| restored(&restored_locations, "location", o.id)).transpose()?;
+				let location = A::Location::create(&mut *tx, l.currency, l.name, outer).await?;
+				restored_locations.insert(l.id, location);
+			}
+
+			pending = rest;
+		}
+
+		for c in archive.contacts
+		{
+			let kind = match c.kind
+			{
+				ContactKind::Address(l) => ContactKind::Address(restored(&restored_locations, "location", l.id)?),
+				k => k,
+			};
+
+			A::Contact::create(&mut *tx, kind, c.label).await?;
+		}
+
+		let mut restored_departments = HashMap::with_capacity(archive.departments.len());
+		for d in archive.departments
+		{
+			restored_departments.insert(d.id, A::Department::create(&mut *tx, d.name).await?);
+		}
+
+		let mut restored_employees = HashMap::with_capacity(archive.employees.len());
+		for e in archive.employees
+		{
+			let department = restored(&restored_departments, "department", e.department.id)?;
+			restored_employees.insert(e.id, A::Employee::create(&mut *tx, department, e.name, e.title).await?);
+		}
+
+		let mut restored_organizations = HashMap::with_capacity(archive.organizations.len());
+		for o in archive.organizations
+		{
+			let location = restored(&restored_locations, "location", o.location.id)?;
+			restored_organizations.insert(o.id, A::Organization::create(&mut *tx, location, o.name).await?);
+		}
+
+		let mut restored_jobs = HashMap::with_capacity(archive.jobs.len());
+		for j in archive.jobs
+		{
+			let client = restored(&restored_organizations, "organization", j.client.id)?;
+			let departments = j
+				.departments
+				.iter()
+				.map(|d| restored(&restored_departments, "department", d.id))
+				.collect::<Result<_, _>>()?;
+
+			let job = A::Job::create(
+				&mut tx,
+				client,
+				j.date_close,
+				j.date_open,
+				departments,
+				j.increment,
+				j.invoice,
+				j.notes,
+				j.objectives,
+			)
+			.await?;
+
+			restored_jobs.insert(j.id, job);
+		}
+
+		for b in archive.budgets
+		{
+			let job = restored(&restored_jobs, "job", b.job_id())?;
+			let budget = Budget::new(b.allowance().clone(), job.id, b.thresholds().clone());
+			A::Budget::set(&mut *tx, &budget).await?;
+		}
+
+		for r in archive.rates
+		{
+			let employee = restored(&restored_employees, "employee", r.employee_id())?;
+			let scope = match r.scope()
+			{
+				RateScope::Job(id) => RateScope::Job(restored(&restored_jobs, "job", id)?.id),
+				RateScope::Organization(id) =>
+				{
+					RateScope::Organization(restored(&restored_organizations, "organization", id)?.id)
+				},
+			};
+
+			A::Rate::set(&mut *tx, &Rate::new(employee.id, *r.hourly_rate(), scope)).await?;
+		}
+
+		let mut restored_timesheets = HashMap::with_capacity(archive.timesheets.len());
+		for t in archive.timesheets
+		{
+			let employee = restored(&restored_employees, "employee", t.employee.id)?;
+			let job = restored(&restored_jobs, "job", t.job.id)?;
+			let expenses = t.expenses.into_iter().map(|x| (x.category, x.cost, x.description)).collect();
+			let timesheet =
+				A::Timesheet::create(&mut tx, employee, expenses, job, t.time_begin, t.time_end, t.work_notes).await?;
+
+			restored_timesheets.insert(t.id, timesheet.id);
+		}
+
+		let mut restored_roles = HashMap::with_capacity(archive.roles.len());
+		for r in archive.roles
+		{
+			restored_roles.insert(r.id(), A::Role::create(&mut *tx, r.name, r.password_ttl()).await?);
+		}
+
+		let mut restored_user_ids = HashMap::with_capacity(archive.users.len());
+		let mut restored_users = Vec::with_capacity(archive.users.len());
+		for (u, hash) in archive.users
+		{
+			let employee = u.employee.map(|e| restored(&restored_employees, "employee", e.id)).transpose()?;
+			let role = restored(&restored_roles, "role", u.role.id())?;
+
+			// the hash is restored afterwards, since creating a user hashes the password it is given
+			let mut user = A::User::create(&mut *tx, employee, String::new(), role, u.username).await?;
+			user.password = hash;
+			user.password_set = u.password_set;
+			restored_user_ids.insert(u.id, user.id);
+			restored_users.push(user);
+		}
+
+		A::User::update(&mut tx, restored_users.iter()).await?;
+		for i in archive.identities
+		{
+			let user_id = restored(&restored_user_ids, "user", i.user_id())?;
+			A::Identity::link(&mut *tx, &Identity::new(i.issuer().to_owned(), i.subject().to_owned(), user_id)).await?;
+		}
+
+		for r in archive.reviews
+		{
+			let timesheet_id = restored(&restored_timesheets, "timesheet", r.timesheet_id())?;
+
+			// the reviewer may have been deleted since they reviewed the timesheet
+			let reviewer_id = r.reviewer_id().and_then(|id| restored_user_ids.get(&id).copied());
+			let comment = r.comment().map(ToOwned::to_owned);
+			A::Review::transition(&mut *tx, timesheet_id, &[ReviewStatus::Draft], r.status(), comment, reviewer_id)
+				.await?;
+		}
+
+		// the permissions are granted before the transaction is committed, so that neither is kept without the other
+		let mut permissions = state.permissions().write().await;
+		let granted = grant(&mut permissions, archive.grouping_policy, archive.policy).await?;
+		if let Err(e) = tx.commit().await
+		{
+			if let Err(e) = revoke(&mut permissions, granted).await
+			{
+				tracing::error!("Could not remove the permissions of the backup after failing to restore it: {e}");
+			}
+
+			return Err(e.into());
+		}
+
+		Ok(())
+	}
+}
+
+/// The rules which were [`grant`]ed, as the `grouping_policy` and the `policy`.
+type Granted = (Vec<Vec<String>>, Vec<Vec<String>>);
+
+/// Add the rules of the `grouping_policy` and `policy` which the `permissions` do not have yet, and save them.
+///
+/// If that fails, the `permissions` are left as they were. Otherwise, the rules which were added are returned, so
+/// that they can be [`revoke`]d.
+async fn grant(
+	permissions: &mut Enforcer,
+	grouping_policy: Vec<Vec<String>>,
+	policy: Vec<Vec<String>>,
+) -> casbin::Result<Granted>
+{
+	let grouping_policy =
+		grouping_policy.into_iter().filter(|g| !permissions.has_grouping_policy(g.clone())).collect::<Vec<_>>();
+	let policy = policy.into_iter().filter(|p| !permissions.has_policy(p.clone())).collect::<Vec<_>>();
+
+	if !grouping_policy.is_empty()
+	{
+		permissions.add_grouping_policies(grouping_policy.clone()).await?;
+	}
+
+	let mut granted = (grouping_policy, Vec::new());
+	let result = match policy.is_empty()
+	{
+		true => Ok(true),
+		false => permissions.add_policies(policy.clone()).await,
+	};
+
+	if let Err(e) = result
+	{
+		revoke(permissions, granted).await.ok();
+		return Err(e);
+	}
+
+	granted.1 = policy;
+	if let Err(e) = permissions.save_policy().await
+	{
+		revoke(permissions, granted).await.ok();
+		return Err(e);
+	}
+
+	Ok(granted)
+}
+
+/// Remove the rules which were [`grant`]ed from the `permissions`, and save them.
+async fn revoke(permissions: &mut Enforcer, (grouping_policy, policy): Granted) -> casbin::Result<()>
+{
+	if !grouping_policy.is_empty()
+	{
+		permissions.remove_grouping_policies(grouping_policy).await?;
+	}
+
+	if !policy.is_empty()
+	{
+		permissions.remove_policies(policy).await?;
+	}
+
+	permissions.save_policy().await
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use core::time::Duration;
+
+	use casbin::{CoreApi, Enforcer, MgmtApi};
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use sqlx::{Executor, PgPool, Postgres};
+	use tokio::fs;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{
+			ContactAdapter,
+			DepartmentAdapter,
+			EmployeeAdapter,
+			JobAdapter,
+			LocationAdapter,
+			OrganizationAdapter,
+			TimesheetAdapter,
+		},
+		Retrievable,
+	};
+	use winvoice_adapter_postgres::{
+		schema::{
+			util::{connect, rand_department_name},
+			PgContact,
+			PgDepartment,
+			PgEmployee,
+			PgJob,
+			PgLocation,
+			PgOrganization,
+			PgTimesheet,
+		},
+		PgSchema,
+	};
+	use winvoice_schema::{chrono::Utc, ContactKind, Invoice, Money};
+
+	use super::{Archive, Restore, VERSION};
+	use crate::{
+		dyn_result::DynResult,
+		lock,
+		schema::{
+			postgres::{PgBudget, PgIdentity, PgRate, PgReview, PgUser},
+			Allowance,
+			Budget,
+			BudgetAdapter,
+			Identity,
+			IdentityAdapter,
+			Rate,
+			RateAdapter,
+			RateScope,
+			ReviewAdapter,
+			ReviewStatus,
+		},
+		server::{Bootstrap, Server, ServerState},
+		utils,
+	};
+
+	/// A [`ServerState`] with the `policy`, whose connections only see the tables of the new `schema`. The tables are
+	/// initialized as they would be for a new server.
+	async fn isolated(schema: &str, policy: &str) -> DynResult<ServerState<Postgres>>
+	{
+		let pool = connect();
+		pool.execute(format!("DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema};").as_str()).await?;
+
+		let pool = PgPool::connect_with(pool.connect_options().clone().options([("search_path", schema)])).await?;
+		Server::<PgSchema>::init(&pool, &Bootstrap::default()).await?;
+
+		let (model_path, policy_path) = utils::init_model_and_policy_files(
+			&format!("args::restore::{schema}"),
+			utils::Model::Rbac.to_string(),
+			policy,
+		)
+		.await?;
+
+		let model_path: &'static str = model_path.to_string_lossy().into_owned().leak();
+		let policy_path: &'static str = policy_path.to_string_lossy().into_owned().leak();
+		let enforcer = Enforcer::new(model_path, policy_path).await.map(lock::new)?;
+		Ok(ServerState::new(enforcer, pool))
+	}
+
+	/// Sort the `values`, so that they can be compared regardless of the order they were retrieved in.
+	fn sorted<T>(mut values: Vec<T>) -> Vec<T>
+	where
+		T: Ord,
+	{
+		values.sort();
+		values
+	}
+
+	#[tokio::test]
+	#[traced_test]
+	async fn round_trip() -> DynResult<()>
+	{
+		let source = isolated("restore_source", "p, manager, location, retrieve\n").await?;
+		let target = isolated("restore_target", "").await?;
+
+		{
+			let pool = source.pool();
+			let department = PgDepartment::create(pool, rand_department_name()).await?;
+			let employee = PgEmployee::create(pool, department.clone(), words::sentence(2), words::word()).await?;
+			let outer = PgLocation::create(pool, None, words::sentence(3), None).await?;
+			let location = PgLocation::create(pool, None, words::sentence(3), Some(outer)).await?;
+			PgContact::create(pool, ContactKind::Address(location.clone()), words::sentence(2)).await?;
+			let organization = PgOrganization::create(pool, location, words::sentence(2)).await?;
+
+			let mut tx = pool.begin().await?;
+			let job = PgJob::create(
+				&mut tx,
+				organization.clone(),
+				None,
+				Utc::now(),
+				[department].into_iter().collect(),
+				Duration::from_secs(60),
+				Invoice { date: None, hourly_rate: Money::new(20_00, 2, Default::default()) },
+				words::sentence(5),
+				words::sentence(5),
+			)
+			.await?;
+
+			let expenses = vec![(words::word(), Money::new(5_00, 2, Default::default()), words::sentence(3))];
+			let timesheet =
+				PgTimesheet::create(&mut tx, employee.clone(), expenses, job.clone(), Utc::now(), None, String::new())
+					.await?;
+
+			let allowance = Allowance::Money(Money::new(1_000_00, 2, Default::default()));
+			PgBudget::set(&mut *tx, &Budget::new(allowance, job.id, [50, 100].into_iter().collect())).await?;
+			let hourly_rate = Money::new(30_00, 2, Default::default());
+			PgRate::set(&mut *tx, &Rate::new(employee.id, hourly_rate, RateScope::Organization(organization.id)))
+				.await?;
+
+			let submit = [ReviewStatus::Draft];
+			PgReview::transition(&mut *tx, timesheet.id, &submit, ReviewStatus::Submitted, None, None).await?;
+
+			// the first user, which the server created
+			let user = PgUser::retrieve(pool, Default::default()).await?.pop().unwrap();
+			PgIdentity::link(&mut *tx, &Identity::new(words::sentence(2), words::word(), user.id())).await?;
+			tx.commit().await?;
+		}
+
+		let archive = Archive::retrieve::<PgSchema>(&source).await?;
+		assert_eq!(archive.version, VERSION);
+
+		let file = utils::temp_dir("args::restore::round_trip").await?.join("backup.json");
+		fs::write(&file, serde_json::to_vec(&archive)?).await?;
+		Restore { file }.run::<PgSchema>(&target).await?;
+
+		let restored = Archive::retrieve::<PgSchema>(&target).await?;
+
+		// the restored entities have new ids, so only the rest of them can be compared
+		macro_rules! assert_restored {
+			($field:ident, |$x:ident| $value:expr) => {
+				assert_eq!(
+					sorted(restored.$field.iter().map(|$x| $value).collect()),
+					sorted(archive.$field.iter().map(|$x| $value).collect()),
+				);
+			};
+		}
+
+		assert_restored!(contacts, |c| c.label.clone());
+		assert_restored!(departments, |d| d.name.clone());
+		assert_restored!(employees, |e| (e.name.clone(), e.title.clone()));
+		assert_restored!(jobs, |j| (j.objectives.clone(), j.client.name.clone()));
+		assert_restored!(locations, |l| (l.name.clone(), l.outer.as_ref().map(|o| o.name.clone())));
+		assert_restored!(organizations, |o| (o.name.clone(), o.location.name.clone()));
+		assert_restored!(roles, |r| r.name().to_owned());
+		assert_restored!(timesheets, |t| {
+			let expenses = t.expenses.iter().map(|x| (x.category.clone(), x.cost.amount)).collect::<Vec<_>>();
+			(t.employee.name.clone(), expenses)
+		});
+		assert_restored!(users, |u| (u.0.username().to_owned(), u.1.clone()));
+		assert_restored!(budgets, |b| b.thresholds().clone());
+		assert_restored!(rates, |r| r.hourly_rate().amount);
+		assert_restored!(reviews, |r| r.status());
+		assert_restored!(identities, |i| (i.issuer().to_owned(), i.subject().to_owned()));
+
+		{
+			let permissions = target.permissions().read().await;
+			assert!(archive.policy.iter().all(|p| permissions.has_policy(p.clone())));
+		}
+
+		let pool = connect();
+		pool.execute("DROP SCHEMA restore_source CASCADE; DROP SCHEMA restore_target CASCADE;").await?;
+		Ok(())
+	}
+}
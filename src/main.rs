@@ -9,22 +9,13 @@
 //!
 //! > **Note**
 //! >
-//! > A template user will be created upon first running the user. It has the following fields (some information omitted
-//! > for simplicity):
+//! > The first user is created upon first running the server, when there are no users. Its username is
+//! > `--admin-username` (`admin` by default), and its role is named `admin`. The passwords of that role last for
+//! > `--admin-password-ttl`, or forever when it is unspecified.
 //! >
-//! > ```json
-//! > {
-//! > "username": "admin",
-//! > "password": "password",
-//! > "role": {
-//! > "name": "admin"
-//! > "password_ttl": null, # password lasts forever
-//! > },
-//! > }
-//! > ```
-//! >
-//! > It is recommended to change the password and the Password TTL of the admin role in order to increase the security
-//! > of your installation.
+//! > Its password is `--admin-password` (or the contents of `--admin-password-file`). When neither is specified, a
+//! > password is generated and printed. Either way, the password must be changed with the `/password` endpoint before
+//! > logging in.
 //!
 //! ## Installation
 //!
@@ -9,6 +9,10 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "snake_case")]
 pub enum Action
 {
+	/// Permission to approve or reject [`Object`](super::Object)s which were submitted for review. Only has meaning for
+	/// timesheets.
+	Approve,
+
 	/// Permission to create [`Object`](super::Object)s.
 	Create,
 
@@ -18,6 +22,9 @@ pub enum Action
 	/// Permission to retrieve [`Object`](super::Object)s.
 	Retrieve,
 
+	/// Permission to submit [`Object`](super::Object)s for review. Only has meaning for timesheets.
+	Submit,
+
 	/// Permission to update [`Object`](super::Object)s.
 	Update,
 }
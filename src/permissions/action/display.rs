@@ -10,9 +10,11 @@ impl Display for Action
 	{
 		match self
 		{
+			Self::Approve => "approve",
 			Self::Create => "create",
 			Self::Delete => "delete",
 			Self::Retrieve => "retrieve",
+			Self::Submit => "submit",
 			Self::Update => "update",
 		}
 		.fmt(f)
@@ -27,7 +27,9 @@ pub enum Object
 	/// Permission to operate on [`winvoice_schema::Timesheet`]s which that particular
 	/// [`User`](crate::schema::User) has created.
 	///
-	/// The [`Create`](super::Action::Create) permission means nothing on this object type.
+	/// The [`Create`](super::Action::Create) permission only allows starting a timesheet via
+	/// [`TIMESHEET_START`](crate::api::routes::TIMESHEET_START), and the [`Approve`](super::Action::Approve)
+	/// permission means nothing, since users may not review their own timesheets.
 	CreatedTimesheet,
 
 	/// Permission to operate on [`winvoice_schema::Department`]s. Assumes
@@ -55,6 +57,11 @@ pub enum Object
 	#[serde(rename = "expense_in_department")]
 	ExpensesInDepartment,
 
+	/// Permission to operate on the [`winvoice_schema::Timesheet`]s and [`winvoice_schema::Expense`]s of
+	/// [`winvoice_schema::Job`]s whose invoice has been issued. Without it, those timesheets and expenses are locked
+	/// against the corresponding [`Action`](super::Action), even if another permission would otherwise allow it.
+	InvoicedJob,
+
 	/// Permission to operate on [`winvoice_schema::Job`]s. Assumes
 	/// [`JobInDepartment`](Self::JobInDepartment`].
 	Job,
@@ -69,9 +76,21 @@ pub enum Object
 	/// Permission to operate on [`winvoice_schema::Organization`]s
 	Organization,
 
+	/// Permission to operate on the [`Rate`](crate::schema::Rate)s which
+	/// [`winvoice_schema::Employee`]s bill at, in place of the rate of a [`winvoice_schema::Job`].
+	///
+	/// The [`Update`](super::Action::Update) permission means nothing on this object type.
+	Rate,
+
 	/// Permission to operate on [`Role`](crate::schema::Role)s
 	Role,
 
+	/// Permission to save match conditions as [`SavedQuery`](crate::schema::SavedQuery)s. A saved query can only be
+	/// used by the [`User`](crate::schema::User) who saved it.
+	///
+	/// Only the [`Create`](super::Action::Create) permission means something on this object type.
+	SavedQuery,
+
 	/// Permission to operate on [`winvoice_schema::Timesheet`]s. Assumes
 	/// [`CreatedTimesheet`](Self::CreatedTimesheet).
 	Timesheet,
@@ -89,6 +108,12 @@ pub enum Object
 
 	/// Permission to act on one's own user.
 	UserSelf,
+
+	/// Permission to operate on [`Webhook`](crate::schema::Webhook)s, and to see the log of their
+	/// [`Delivery`](crate::schema::Delivery)s.
+	///
+	/// The [`Update`](super::Action::Update) permission means nothing on this object type.
+	Webhook,
 }
 
 impl Object
@@ -20,16 +20,20 @@ impl Display for Object
 			Self::EmployeeSelf => "their employee record",
 			Self::Expenses => "expenses",
 			Self::ExpensesInDepartment => "expenses in their department",
+			Self::InvoicedJob => "the timesheets and expenses of invoiced jobs",
 			Self::Job => "jobs",
 			Self::JobInDepartment => "jobs in their department",
 			Self::Location => "locations",
 			Self::Organization => "organization",
+			Self::Rate => "the rates of employees",
 			Self::Role => "roles",
+			Self::SavedQuery => "saved queries",
 			Self::Timesheet => "timesheets",
 			Self::TimesheetInDepartment => "timesheets in their department",
 			Self::User => "users",
 			Self::UserInDepartment => "users in their department",
 			Self::UserSelf => "themselves",
+			Self::Webhook => "webhooks",
 		}
 		.fmt(f)
 	}
@@ -2,13 +2,72 @@
 
 use winvoice_adapter::{schema::Adapter as BaseAdapter, Deletable};
 
-use super::{RoleAdapter, UserAdapter};
+use super::{
+	AttachmentAdapter,
+	BudgetAdapter,
+	EventAdapter,
+	IdempotencyAdapter,
+	IdentityAdapter,
+	InvitationAdapter,
+	LocationTreeAdapter,
+	PasswordResetAdapter,
+	RateAdapter,
+	ReviewAdapter,
+	RoleAdapter,
+	SavedQueryAdapter,
+	SearchAdapter,
+	TimerAdapter,
+	UserAdapter,
+	WebhookAdapter,
+};
 
 pub trait Adapter: BaseAdapter
 {
+	/// The adapter for [`Attachment`](super::Attachment)s
+	type Attachment: AttachmentAdapter<Db = Self::Db>;
+
+	/// The adapter for the [`Budget`](super::Budget)s of jobs
+	type Budget: BudgetAdapter<Db = Self::Db>;
+
+	/// The adapter for [`Event`](crate::api::Event)s
+	type Event: EventAdapter<Db = Self::Db>;
+
+	/// The adapter for idempotency keys
+	type Idempotency: IdempotencyAdapter<Db = Self::Db>;
+
+	/// The adapter for the [`Identity`](super::Identity)s of [`User`](super::User)s at identity providers
+	type Identity: IdentityAdapter<Db = Self::Db>;
+
+	/// The adapter for [`Invitation`](super::Invitation)s
+	type Invitation: InvitationAdapter<Db = Self::Db>;
+
+	/// The adapter for walking the tree of [`Location`](winvoice_schema::Location)s
+	type LocationTree: LocationTreeAdapter<Db = Self::Db>;
+
+	/// The adapter for the tokens which reset the passwords of [`User`](super::User)s
+	type PasswordReset: PasswordResetAdapter<Db = Self::Db>;
+
+	/// The adapter for the [`Rate`](super::Rate)s of employees
+	type Rate: RateAdapter<Db = Self::Db>;
+
+	/// The adapter for the [`Review`](super::Review)s of timesheets
+	type Review: ReviewAdapter<Db = Self::Db>;
+
 	/// The adapter for [`Role`](super::Role)s
 	type Role: Deletable<Db = Self::Db> + RoleAdapter;
 
+	/// The adapter for [`SavedQuery`](super::SavedQuery)s
+	type SavedQuery: SavedQueryAdapter<Db = Self::Db>;
+
+	/// The adapter for searching the text of entities
+	type Search: SearchAdapter<Db = Self::Db>;
+
+	/// The adapter for starting [`Timesheet`](winvoice_schema::Timesheet)s as timers
+	type Timer: TimerAdapter<Db = Self::Db>;
+
 	/// The adapter for [`User`](super::User)s
 	type User: Deletable<Db = Self::Db> + UserAdapter;
+
+	/// The adapter for [`Webhook`](super::Webhook)s
+	type Webhook: WebhookAdapter<Db = Self::Db>;
 }
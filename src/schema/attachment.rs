@@ -0,0 +1,82 @@
+//! Contains information about an [`Attachment`] of an [`Expense`](winvoice_schema::Expense).
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+/// Corresponds to the `attachments` table in the database. The contents of the file are stored separately.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Attachment
+{
+	/// The MIME type of the file.
+	content_type: String,
+
+	/// When the [`Attachment`] was uploaded.
+	created: DateTime<Utc>,
+
+	/// The [`Id`] of the [`Expense`](winvoice_schema::Expense) which the file is attached to.
+	expense_id: Id,
+
+	/// The unique identity of the [`Attachment`].
+	id: Id,
+
+	/// The name of the file, as it was uploaded.
+	name: String,
+
+	/// The size of the file, in bytes.
+	size: i64,
+}
+
+impl Attachment
+{
+	/// Create a new [`Attachment`].
+	pub const fn new(
+		content_type: String,
+		created: DateTime<Utc>,
+		expense_id: Id,
+		id: Id,
+		name: String,
+		size: i64,
+	) -> Self
+	{
+		Self { content_type, created, expense_id, id, name, size }
+	}
+
+	/// The MIME type of the file.
+	pub fn content_type(&self) -> &str
+	{
+		self.content_type.as_ref()
+	}
+
+	/// When the [`Attachment`] was uploaded.
+	pub const fn created(&self) -> DateTime<Utc>
+	{
+		self.created
+	}
+
+	/// The [`Id`] of the [`Expense`](winvoice_schema::Expense) which the file is attached to.
+	pub const fn expense_id(&self) -> Id
+	{
+		self.expense_id
+	}
+
+	/// The unique identity of the [`Attachment`].
+	pub const fn id(&self) -> Id
+	{
+		self.id
+	}
+
+	/// The name of the file, as it was uploaded.
+	pub fn name(&self) -> &str
+	{
+		self.name.as_ref()
+	}
+
+	/// The size of the file, in bytes.
+	pub const fn size(&self) -> i64
+	{
+		self.size
+	}
+}
@@ -0,0 +1,48 @@
+//! Contains the adapter which stores information about [`Attachment`]s.
+
+use sqlx::{Database, Pool, Result};
+use winvoice_schema::Id;
+
+use super::Attachment;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Attachment`]s.
+///
+/// Only the information about each [`Attachment`] is stored this way. The contents of the file are stored separately.
+#[async_trait::async_trait]
+pub trait AttachmentAdapter
+{
+	/// The [`Database`] which the [`Attachment`]s are stored in.
+	type Db: Database;
+
+	/// Create a new [`Attachment`] of the [`Expense`](winvoice_schema::Expense) with the `expense_id`.
+	async fn create(
+		pool: &Pool<Self::Db>,
+		content_type: String,
+		expense_id: Id,
+		name: String,
+		size: i64,
+	) -> Result<Attachment>;
+
+	/// Delete the [`Attachment`]s with the `ids`, all at once.
+	///
+	/// The contents of an [`Attachment`] which is deleted — whether by this, or along with its expense — must be
+	/// deleted separately. Until they are [forgotten](Self::forget), the attachment is one of the
+	/// [`deleted`](Self::deleted).
+	async fn delete(pool: &Pool<Self::Db>, ids: &[Id]) -> Result<()>;
+
+	/// The [`Id`]s of at most `limit` [`Attachment`]s which have been deleted, but whose contents may not have been.
+	async fn deleted(pool: &Pool<Self::Db>, limit: i64) -> Result<Vec<Id>>;
+
+	/// Stop reporting the [`Attachment`]s with the `ids` as [`deleted`](Self::deleted), because their contents have
+	/// been deleted too.
+	async fn forget(pool: &Pool<Self::Db>, ids: &[Id]) -> Result<()>;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Attachment`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// The [`Attachment`] with the `id`, if there is one.
+	async fn retrieve(pool: &Pool<Self::Db>, id: Id) -> Result<Option<Attachment>>;
+
+	/// The [`Attachment`]s of the [`Expense`](winvoice_schema::Expense) with the `expense_id`, the oldest first.
+	async fn retrieve_for(pool: &Pool<Self::Db>, expense_id: Id) -> Result<Vec<Attachment>>;
+}
@@ -0,0 +1,144 @@
+//! Contains the [`Budget`] of a [`Job`](winvoice_schema::Job), and how much of it has been used.
+
+use core::time::Duration;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{Id, Money, Timesheet};
+
+/// How much may be spent on a [`Job`](winvoice_schema::Job).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Allowance
+{
+	/// The total of the [`Timesheet`]s and their [`Expense`](winvoice_schema::Expense)s, in the currency of the
+	/// [`Money`].
+	Money(Money),
+
+	/// The time worked on the [`Timesheet`]s, e.g. some number of hours.
+	Time(#[serde(with = "humantime_serde")] Duration),
+}
+
+/// Corresponds to the `job_budgets` table in the database.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Budget
+{
+	/// See [`Allowance`].
+	allowance: Allowance,
+
+	/// The [`Id`] of the [`Job`](winvoice_schema::Job) which the budget is for.
+	job_id: Id,
+
+	/// The percentages of the [`Allowance`] which, once a new timesheet pushes the job past them, cause a warning.
+	thresholds: BTreeSet<u8>,
+}
+
+impl Budget
+{
+	/// Create a new [`Budget`].
+	pub const fn new(allowance: Allowance, job_id: Id, thresholds: BTreeSet<u8>) -> Self
+	{
+		Self { allowance, job_id, thresholds }
+	}
+
+	/// See [`Allowance`].
+	pub const fn allowance(&self) -> &Allowance
+	{
+		&self.allowance
+	}
+
+	/// The [`Id`] of the [`Job`](winvoice_schema::Job) which the budget is for.
+	pub const fn job_id(&self) -> Id
+	{
+		self.job_id
+	}
+
+	/// The percentages of the [`Allowance`] which, once a new timesheet pushes the job past them, cause a warning.
+	pub const fn thresholds(&self) -> &BTreeSet<u8>
+	{
+		&self.thresholds
+	}
+}
+
+/// How much of a [`Budget`] has been consumed by the [`Timesheet`]s of its job.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BudgetUsage
+{
+	/// The [`Budget`] which is being consumed.
+	budget: Budget,
+
+	/// How much of the [`Budget::allowance`] has been consumed.
+	consumed: Allowance,
+
+	/// How much of the [`Budget::allowance`] is left. Once a budget of [`Money`] is overrun, this is negative.
+	remaining: Allowance,
+}
+
+impl BudgetUsage
+{
+	/// Calculate how much of the `budget` has been consumed by the `timesheets` of its job.
+	///
+	/// For a budget of [`Money`], the `timesheets` must already be in its currency. For a budget of time, only the
+	/// timesheets which have ended are counted.
+	pub fn new(budget: Budget, timesheets: &[Timesheet]) -> Self
+	{
+		let (consumed, remaining) = match &budget.allowance
+		{
+			Allowance::Money(limit) =>
+			{
+				let mut consumed = Money::new(0, 2, limit.currency);
+				timesheets.iter().for_each(|t| consumed.amount += t.total().amount);
+
+				let remaining = Money { amount: limit.amount - consumed.amount, currency: limit.currency };
+				(Allowance::Money(consumed), Allowance::Money(remaining))
+			},
+
+			Allowance::Time(limit) =>
+			{
+				let consumed = timesheets
+					.iter()
+					.filter_map(|t| t.time_end.and_then(|end| (end - t.time_begin).to_std().ok()))
+					.sum::<Duration>();
+
+				(Allowance::Time(consumed), Allowance::Time(limit.saturating_sub(consumed)))
+			},
+		};
+
+		Self { budget, consumed, remaining }
+	}
+
+	/// The [`Budget`] which is being consumed.
+	pub const fn budget(&self) -> &Budget
+	{
+		&self.budget
+	}
+
+	/// How much of the [`Budget::allowance`] has been consumed.
+	pub const fn consumed(&self) -> &Allowance
+	{
+		&self.consumed
+	}
+
+	/// How much of the [`Budget::allowance`] has been consumed, as a percentage.
+	pub fn percent(&self) -> f64
+	{
+		/// The `money` as a float, which is precise enough to compare against the [`Budget::thresholds`].
+		fn float(money: &Money) -> f64
+		{
+			money.amount.to_string().parse().unwrap_or_default()
+		}
+
+		match (&self.budget.allowance, &self.consumed)
+		{
+			(Allowance::Money(limit), Allowance::Money(consumed)) => float(consumed) / float(limit) * 100.0,
+			(Allowance::Time(limit), Allowance::Time(consumed)) => consumed.as_secs_f64() / limit.as_secs_f64() * 100.0,
+			_ => unreachable!("what was consumed is always the same kind of allowance as the budget"),
+		}
+	}
+
+	/// How much of the [`Budget::allowance`] is left. Once a budget of [`Money`] is overrun, this is negative.
+	pub const fn remaining(&self) -> &Allowance
+	{
+		&self.remaining
+	}
+}
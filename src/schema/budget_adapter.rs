@@ -0,0 +1,28 @@
+//! Contains the adapter which stores the [`Budget`]s of [`Job`](winvoice_schema::Job)s.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::Id;
+
+use super::Budget;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Budget`]s.
+#[async_trait::async_trait]
+pub trait BudgetAdapter
+{
+	/// The [`Database`] which the [`Budget`]s are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Budget`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Remove the [`Budget`]s of the jobs with the `job_ids`, if they have any.
+	async fn remove(pool: &Pool<Self::Db>, job_ids: &[Id]) -> Result<()>;
+
+	/// The [`Budget`]s of the jobs with the `job_ids`. Jobs without a budget are skipped.
+	async fn retrieve(pool: &Pool<Self::Db>, job_ids: &[Id]) -> Result<Vec<Budget>>;
+
+	/// Set the `budget` of its job, replacing any which it had before.
+	async fn set<'connection, Conn>(connection: Conn, budget: &Budget) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
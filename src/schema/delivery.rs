@@ -0,0 +1,115 @@
+//! Contains information about a [`Delivery`] of an [`Event`] to a [`Webhook`](super::Webhook).
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+use crate::api::Event;
+
+/// Corresponds to the `webhook_deliveries` table in the database.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Delivery
+{
+	/// How many times the [`Event`] has been sent.
+	attempts: i32,
+
+	/// When the [`Delivery`] was queued.
+	created: DateTime<Utc>,
+
+	/// When the [`Webhook`](super::Webhook) accepted the [`Event`], if it has.
+	delivered: Option<DateTime<Utc>>,
+
+	/// Why the last attempt failed, if it did.
+	error: Option<String>,
+
+	/// The [`Event`] being delivered.
+	event: Event,
+
+	/// The unique identity of the [`Delivery`].
+	id: i64,
+
+	/// When the [`Event`] will be sent again. [`None`] if it was delivered, or if there are no attempts left.
+	next_attempt: Option<DateTime<Utc>>,
+
+	/// The HTTP status code which the [`Webhook`](super::Webhook) responded with last, if it did.
+	status: Option<u16>,
+
+	/// The [`Id`] of the [`Webhook`](super::Webhook) which the [`Event`] is being delivered to.
+	webhook_id: Id,
+}
+
+impl Delivery
+{
+	/// Create a new [`Delivery`].
+	#[allow(clippy::too_many_arguments)]
+	pub const fn new(
+		attempts: i32,
+		created: DateTime<Utc>,
+		delivered: Option<DateTime<Utc>>,
+		error: Option<String>,
+		event: Event,
+		id: i64,
+		next_attempt: Option<DateTime<Utc>>,
+		status: Option<u16>,
+		webhook_id: Id,
+	) -> Self
+	{
+		Self { attempts, created, delivered, error, event, id, next_attempt, status, webhook_id }
+	}
+
+	/// How many times the [`Event`] has been sent.
+	pub const fn attempts(&self) -> i32
+	{
+		self.attempts
+	}
+
+	/// When the [`Delivery`] was queued.
+	pub const fn created(&self) -> DateTime<Utc>
+	{
+		self.created
+	}
+
+	/// When the [`Webhook`](super::Webhook) accepted the [`Event`], if it has.
+	pub const fn delivered(&self) -> Option<DateTime<Utc>>
+	{
+		self.delivered
+	}
+
+	/// Why the last attempt failed, if it did.
+	pub fn error(&self) -> Option<&str>
+	{
+		self.error.as_deref()
+	}
+
+	/// The [`Event`] being delivered.
+	pub const fn event(&self) -> &Event
+	{
+		&self.event
+	}
+
+	/// The unique identity of the [`Delivery`].
+	pub const fn id(&self) -> i64
+	{
+		self.id
+	}
+
+	/// When the [`Event`] will be sent again. [`None`] if it was delivered, or if there are no attempts left.
+	pub const fn next_attempt(&self) -> Option<DateTime<Utc>>
+	{
+		self.next_attempt
+	}
+
+	/// The HTTP status code which the [`Webhook`](super::Webhook) responded with last, if it did.
+	pub const fn status(&self) -> Option<u16>
+	{
+		self.status
+	}
+
+	/// The [`Id`] of the [`Webhook`](super::Webhook) which the [`Event`] is being delivered to.
+	pub const fn webhook_id(&self) -> Id
+	{
+		self.webhook_id
+	}
+}
@@ -0,0 +1,27 @@
+//! Contains the adapter which distributes [`Event`]s between every replica of the
+//! [`winvoice_server`](crate) which shares a [`Database`].
+
+use sqlx::{Database, Pool, Result};
+use tokio::sync::broadcast::Sender;
+
+use crate::api::Event;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Event`]s.
+#[async_trait::async_trait]
+pub trait EventAdapter
+{
+	/// The [`Database`] which is used to distribute the [`Event`]s.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to [`publish`](Self::publish) [`Event`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Receive every [`Event`] which is [`publish`](Self::publish)ed by any replica, and pass it to the `sender`.
+	///
+	/// Only returns if an error occurs.
+	async fn listen(pool: &Pool<Self::Db>, sender: &Sender<Event>) -> Result<()>;
+
+	/// Make the `event` available to every replica which is [listening](Self::listen), once the transaction of the
+	/// `connection` is committed.
+	async fn publish(connection: &mut <Self::Db as Database>::Connection, event: &Event) -> Result<()>;
+}
@@ -0,0 +1,23 @@
+//! Contains a [`Hit`] from a search.
+
+use winvoice_schema::Id;
+
+/// Something which matched a search.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Hit
+{
+	/// The [`Contact`](winvoice_schema::Contact) with the label.
+	Contact(String),
+
+	/// The [`Expense`](winvoice_schema::Expense) with the [`Id`].
+	Expense(Id),
+
+	/// The [`Job`](winvoice_schema::Job) with the [`Id`].
+	Job(Id),
+
+	/// The [`Organization`](winvoice_schema::Organization) with the [`Id`].
+	Organization(Id),
+
+	/// The [`Timesheet`](winvoice_schema::Timesheet) with the [`Id`].
+	Timesheet(Id),
+}
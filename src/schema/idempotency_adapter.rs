@@ -0,0 +1,73 @@
+//! Contains the adapter which stores the responses to requests made with an
+//! [idempotency key](crate::api::IDEMPOTENCY_HEADER).
+
+use core::time::Duration;
+
+use sqlx::{Database, Pool, Result};
+use winvoice_schema::Id;
+
+/// The outcome of trying to [`reserve`](IdempotencyAdapter::reserve) an idempotency key.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Reservation
+{
+	/// A request with the key has already been responded to.
+	Completed
+	{
+		/// The body of the response.
+		body: Vec<u8>,
+
+		/// The HTTP status code of the response.
+		status: u16,
+	},
+
+	/// A request with the key was sent with a different body.
+	Mismatch,
+
+	/// A request with the key is still running.
+	Pending,
+
+	/// The key was not in use, and now belongs to the request which reserved it.
+	Reserved,
+}
+
+/// Implementors of this trait may act as an [adapter](super) for idempotency keys.
+///
+/// A key is identified by the key itself, the [`User`](super::User) who sent it, and the route it was sent to.
+#[async_trait::async_trait]
+pub trait IdempotencyAdapter
+{
+	/// The [`Database`] which the keys are stored in.
+	type Db: Database;
+
+	/// Store the response to the request which [reserved](Self::reserve) the `key`.
+	async fn complete(
+		pool: &Pool<Self::Db>,
+		user: Id,
+		route: &str,
+		key: &str,
+		status: u16,
+		body: &[u8],
+	) -> Result<()>;
+
+	/// Initialize whatever is needed on the [`Database`] to store idempotency keys.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Forget the `key`, so that the request can be tried again.
+	async fn release(pool: &Pool<Self::Db>, user: Id, route: &str, key: &str) -> Result<()>;
+
+	/// Reserve the `key` for a request whose body has the `fingerprint`, unless it was already reserved less than
+	/// `ttl` ago.
+	///
+	/// The reservation lasts for the `lease`. A request which has not been [completed](Self::complete) by then is
+	/// assumed to have been lost, and the `key` can be reserved again by a request with the same `fingerprint`.
+	#[allow(clippy::too_many_arguments)]
+	async fn reserve(
+		pool: &Pool<Self::Db>,
+		user: Id,
+		route: &str,
+		key: &str,
+		fingerprint: &[u8],
+		lease: Duration,
+		ttl: Duration,
+	) -> Result<Reservation>;
+}
@@ -0,0 +1,49 @@
+//! Contains the [`Identity`] of a [`User`](super::User) at an external identity provider.
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::Id;
+
+/// Corresponds to the `user_identities` table in the database.
+///
+/// A user who logs in through an identity provider (e.g. [OpenID Connect](crate::server::Oidc) or an
+/// [LDAP directory](crate::server::Ldap)) is found by the identity which it vouched for, rather than by their username,
+/// so that the provider cannot log in as a [`User`](super::User) which it does not manage.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Identity
+{
+	/// The identity provider which vouched for the user.
+	issuer: String,
+
+	/// What the identity provider calls the user, which does not change for as long as the user exists there.
+	subject: String,
+
+	/// The [`Id`] of the [`User`](super::User) who has the identity.
+	user_id: Id,
+}
+
+impl Identity
+{
+	/// Create a new [`Identity`].
+	pub const fn new(issuer: String, subject: String, user_id: Id) -> Self
+	{
+		Self { issuer, subject, user_id }
+	}
+
+	/// The identity provider which vouched for the user.
+	pub fn issuer(&self) -> &str
+	{
+		&self.issuer
+	}
+
+	/// What the identity provider calls the user, which does not change for as long as the user exists there.
+	pub fn subject(&self) -> &str
+	{
+		&self.subject
+	}
+
+	/// The [`Id`] of the [`User`](super::User) who has the identity.
+	pub const fn user_id(&self) -> Id
+	{
+		self.user_id
+	}
+}
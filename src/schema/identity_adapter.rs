@@ -0,0 +1,28 @@
+//! Contains the adapter which stores the [`Identity`]s of [`User`](super::User)s.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::Id;
+
+use super::Identity;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Identity`]s.
+#[async_trait::async_trait]
+pub trait IdentityAdapter
+{
+	/// The [`Database`] which the [`Identity`]s are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Identity`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// The [`Identity`] which the `issuer` calls `subject`, if it has been linked to a user.
+	async fn find(pool: &Pool<Self::Db>, issuer: &str, subject: &str) -> Result<Option<Identity>>;
+
+	/// Link the `identity` to its user. Fails if it is already linked to a user.
+	async fn link<'connection, Conn>(connection: Conn, identity: &Identity) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+
+	/// The [`Identity`]s of the users with the `user_ids`.
+	async fn retrieve(pool: &Pool<Self::Db>, user_ids: &[Id]) -> Result<Vec<Identity>>;
+}
@@ -0,0 +1,87 @@
+//! Contains the [`Invitation`] of somebody to become a [`User`](super::User).
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+/// Corresponds to the `invitations` table in the database.
+///
+/// Whoever has the token of an [`Invitation`] can redeem it once, before it expires, to become a
+/// [`User`](super::User) with the chosen password.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Invitation
+{
+	/// The [`Id`] of the [`Employee`](winvoice_schema::Employee) which the [`User`](super::User) will be, if any.
+	employee_id: Option<Id>,
+
+	/// When the [`Invitation`] can no longer be redeemed.
+	expires: DateTime<Utc>,
+
+	/// The unique identity of the [`Invitation`].
+	id: Id,
+
+	/// The link which redeems the [`Invitation`], relative to the server.
+	///
+	/// Only the response to creating the [`Invitation`] has it, because only a hash of the token in it is stored.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	link: Option<String>,
+
+	/// The [`Id`] of the [`Role`](super::Role) which the [`User`](super::User) will have.
+	role_id: Id,
+
+	/// The username which the [`User`](super::User) will have.
+	username: String,
+}
+
+impl Invitation
+{
+	/// Create a new [`Invitation`].
+	pub const fn new(employee_id: Option<Id>, expires: DateTime<Utc>, id: Id, role_id: Id, username: String) -> Self
+	{
+		Self { employee_id, expires, id, link: None, role_id, username }
+	}
+
+	/// The [`Id`] of the [`Employee`](winvoice_schema::Employee) which the [`User`](super::User) will be, if any.
+	pub const fn employee_id(&self) -> Option<Id>
+	{
+		self.employee_id
+	}
+
+	/// When the [`Invitation`] can no longer be redeemed.
+	pub const fn expires(&self) -> DateTime<Utc>
+	{
+		self.expires
+	}
+
+	/// The unique identity of the [`Invitation`].
+	pub const fn id(&self) -> Id
+	{
+		self.id
+	}
+
+	/// The link which redeems the [`Invitation`], if it is known.
+	pub fn link(&self) -> Option<&str>
+	{
+		self.link.as_deref()
+	}
+
+	/// The [`Id`] of the [`Role`](super::Role) which the [`User`](super::User) will have.
+	pub const fn role_id(&self) -> Id
+	{
+		self.role_id
+	}
+
+	/// The username which the [`User`](super::User) will have.
+	pub fn username(&self) -> &str
+	{
+		self.username.as_ref()
+	}
+
+	/// Set the `link` which redeems the [`Invitation`].
+	pub fn with_link(self, link: String) -> Self
+	{
+		Self { link: Some(link), ..self }
+	}
+}
@@ -0,0 +1,44 @@
+//! Contains the adapter which stores [`Invitation`]s.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+use super::Invitation;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Invitation`]s.
+///
+/// The token of an [`Invitation`] must not be stored, so that whoever can read the [`Database`] cannot redeem it.
+#[async_trait::async_trait]
+pub trait InvitationAdapter
+{
+	/// The [`Database`] which the [`Invitation`]s are stored in.
+	type Db: Database;
+
+	/// Create a new [`Invitation`] for somebody to become a user with the `username`, the role with the `role_id`,
+	/// and the employee with the `employee_id` (if any) until it `expires`. Returns the token which redeems it.
+	async fn create(
+		pool: &Pool<Self::Db>,
+		employee_id: Option<Id>,
+		expires: DateTime<Utc>,
+		role_id: Id,
+		username: String,
+	) -> Result<(Invitation, String)>;
+
+	/// Delete the [`Invitation`]s with the `ids`.
+	async fn delete(pool: &Pool<Self::Db>, ids: &[Id]) -> Result<()>;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Invitation`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Delete the [`Invitation`] which the `token` redeems, and return it. Returns [`None`] if there is none which has
+	/// not expired.
+	async fn redeem<'connection, Conn>(connection: Conn, token: &str) -> Result<Option<Invitation>>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+
+	/// Every [`Invitation`], including those which have expired.
+	async fn retrieve(pool: &Pool<Self::Db>) -> Result<Vec<Invitation>>;
+}
@@ -0,0 +1,30 @@
+//! Contains the adapter which walks the tree of [`Location`](winvoice_schema::Location)s, which is formed by their
+//! [`outer`](winvoice_schema::Location::outer) locations.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::Id;
+
+/// Implementors of this trait may act as an [adapter](super) for walking the tree of
+/// [`Location`](winvoice_schema::Location)s.
+#[async_trait::async_trait]
+pub trait LocationTreeAdapter
+{
+	/// The [`Database`] which the [`Location`](winvoice_schema::Location)s are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to walk the tree of
+	/// [`Location`](winvoice_schema::Location)s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// The [`Id`]s of every location which is inside of one of the locations with the `ids`, however deeply. The
+	/// `ids` themselves are not included.
+	async fn descendants<'connection, Conn>(connection: Conn, ids: &[Id]) -> Result<Vec<Id>>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+
+	/// Keep every other transaction from locking the tree until the transaction of the `connection` ends, so that
+	/// the locations which it moves cannot be moved into a cycle by another transaction at the same time.
+	async fn lock<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
@@ -2,18 +2,85 @@
 
 #[cfg(feature = "bin")]
 mod adapter;
+mod attachment;
+#[cfg(feature = "bin")]
+mod attachment_adapter;
+mod budget;
+#[cfg(feature = "bin")]
+mod budget_adapter;
 pub mod columns;
+mod delivery;
 #[cfg(feature = "postgres")]
 pub mod postgres;
+mod rate;
+#[cfg(feature = "bin")]
+mod rate_adapter;
+mod review;
+#[cfg(feature = "bin")]
+mod review_adapter;
 mod role;
 #[cfg(feature = "bin")]
+mod event_adapter;
+#[cfg(feature = "bin")]
+mod hit;
+#[cfg(feature = "bin")]
+mod idempotency_adapter;
+mod identity;
+#[cfg(feature = "bin")]
+mod identity_adapter;
+mod invitation;
+#[cfg(feature = "bin")]
+mod invitation_adapter;
+#[cfg(feature = "bin")]
+mod location_tree_adapter;
+#[cfg(feature = "bin")]
+mod password_reset_adapter;
+#[cfg(feature = "bin")]
 mod role_adapter;
+mod saved_query;
+#[cfg(feature = "bin")]
+mod saved_query_adapter;
+#[cfg(feature = "bin")]
+mod search_adapter;
+#[cfg(feature = "bin")]
+mod timer_adapter;
 mod user;
 #[cfg(feature = "bin")]
 mod user_adapter;
+mod webhook;
+#[cfg(feature = "bin")]
+mod webhook_adapter;
 mod write_where_clause;
 
+pub use attachment::Attachment;
+pub use budget::{Allowance, Budget, BudgetUsage};
+pub use delivery::Delivery;
+pub use identity::Identity;
+pub use invitation::Invitation;
+pub use rate::{Rate, RateScope};
+pub use review::{Review, ReviewStatus};
 pub use role::Role;
+pub use saved_query::SavedQuery;
 pub use user::User;
+pub use webhook::Webhook;
 #[cfg(feature = "bin")]
-pub use {adapter::Adapter, role_adapter::RoleAdapter, user_adapter::UserAdapter};
+pub use {
+	adapter::Adapter,
+	attachment_adapter::AttachmentAdapter,
+	budget_adapter::BudgetAdapter,
+	event_adapter::EventAdapter,
+	hit::Hit,
+	idempotency_adapter::{IdempotencyAdapter, Reservation},
+	identity_adapter::IdentityAdapter,
+	invitation_adapter::InvitationAdapter,
+	location_tree_adapter::LocationTreeAdapter,
+	password_reset_adapter::PasswordResetAdapter,
+	rate_adapter::RateAdapter,
+	review_adapter::ReviewAdapter,
+	role_adapter::RoleAdapter,
+	saved_query_adapter::SavedQueryAdapter,
+	search_adapter::SearchAdapter,
+	timer_adapter::TimerAdapter,
+	user_adapter::UserAdapter,
+	webhook_adapter::WebhookAdapter,
+};
@@ -0,0 +1,31 @@
+//! Contains the adapter which stores the tokens that reset the passwords of [`User`](super::User)s.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+/// Implementors of this trait may act as an [adapter](super) for the tokens which reset the passwords of
+/// [`User`](super::User)s.
+///
+/// A token must not be stored, so that whoever can read the [`Database`] cannot redeem it.
+#[async_trait::async_trait]
+pub trait PasswordResetAdapter
+{
+	/// The [`Database`] which the tokens are stored in.
+	type Db: Database;
+
+	/// Create a token which resets the password of the user with the `user_id` until it `expires`, in place of any
+	/// which the user had before.
+	async fn create(pool: &Pool<Self::Db>, user_id: Id, expires: DateTime<Utc>) -> Result<String>;
+
+	/// Initialize whatever is needed on the [`Database`] to store the tokens.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Delete the `token`, and return the [`Id`] of the user whose password it resets. Returns [`None`] if there is no
+	/// such token which has not expired.
+	async fn redeem<'connection, Conn>(connection: Conn, token: &str) -> Result<Option<Id>>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
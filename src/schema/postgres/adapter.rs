@@ -2,11 +2,42 @@
 
 use winvoice_adapter_postgres::PgSchema;
 
-use super::{PgRole, PgUser};
+use super::{
+	PgAttachment,
+	PgBudget,
+	PgEvent,
+	PgIdempotency,
+	PgIdentity,
+	PgInvitation,
+	PgLocationTree,
+	PgPasswordReset,
+	PgRate,
+	PgReview,
+	PgRole,
+	PgSavedQuery,
+	PgSearch,
+	PgTimer,
+	PgUser,
+	PgWebhook,
+};
 use crate::schema::Adapter;
 
 impl Adapter for PgSchema
 {
+	type Attachment = PgAttachment;
+	type Budget = PgBudget;
+	type Event = PgEvent;
+	type Idempotency = PgIdempotency;
+	type Identity = PgIdentity;
+	type Invitation = PgInvitation;
+	type LocationTree = PgLocationTree;
+	type PasswordReset = PgPasswordReset;
+	type Rate = PgRate;
+	type Review = PgReview;
 	type Role = PgRole;
+	type SavedQuery = PgSavedQuery;
+	type Search = PgSearch;
+	type Timer = PgTimer;
 	type User = PgUser;
+	type Webhook = PgWebhook;
 }
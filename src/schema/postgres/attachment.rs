@@ -0,0 +1,94 @@
+//! Contains an [`AttachmentAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{postgres::PgRow, Executor, PgPool, Result, Row};
+use winvoice_schema::Id;
+
+use crate::schema::{Attachment, AttachmentAdapter};
+
+/// Stores information about [`Attachment`]s in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgAttachment;
+
+impl PgAttachment
+{
+	/// Get an [`Attachment`] out of a `row` which has every column of `attachments`.
+	fn attachment(row: &PgRow) -> Result<Attachment>
+	{
+		Ok(Attachment::new(
+			row.try_get("content_type")?,
+			row.try_get("created")?,
+			row.try_get("expense_id")?,
+			row.try_get("id")?,
+			row.try_get("name")?,
+			row.try_get("size")?,
+		))
+	}
+}
+
+#[async_trait::async_trait]
+impl AttachmentAdapter for PgAttachment
+{
+	type Db = sqlx::Postgres;
+
+	async fn create(pool: &PgPool, content_type: String, expense_id: Id, name: String, size: i64) -> Result<Attachment>
+	{
+		let row = sqlx::query(
+			"INSERT INTO attachments (id, content_type, expense_id, name, size) VALUES ($1, $2, $3, $4, $5) \
+			 RETURNING *;",
+		)
+		.bind(Id::new_v4())
+		.bind(content_type)
+		.bind(expense_id)
+		.bind(name)
+		.bind(size)
+		.fetch_one(pool)
+		.await?;
+
+		Self::attachment(&row)
+	}
+
+	async fn delete(pool: &PgPool, ids: &[Id]) -> Result<()>
+	{
+		sqlx::query("DELETE FROM attachments WHERE id = ANY($1);").bind(ids).execute(pool).await?;
+		Ok(())
+	}
+
+	async fn deleted(pool: &PgPool, limit: i64) -> Result<Vec<Id>>
+	{
+		sqlx::query_scalar("SELECT id FROM deleted_attachments LIMIT $1;").bind(limit).fetch_all(pool).await
+	}
+
+	async fn forget(pool: &PgPool, ids: &[Id]) -> Result<()>
+	{
+		sqlx::query("DELETE FROM deleted_attachments WHERE id = ANY($1);").bind(ids).execute(pool).await?;
+		Ok(())
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("attachment/44-attachments.sql")).await?;
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool, id: Id) -> Result<Option<Attachment>>
+	{
+		sqlx::query("SELECT * FROM attachments WHERE id = $1;")
+			.bind(id)
+			.fetch_optional(pool)
+			.await?
+			.as_ref()
+			.map(Self::attachment)
+			.transpose()
+	}
+
+	async fn retrieve_for(pool: &PgPool, expense_id: Id) -> Result<Vec<Attachment>>
+	{
+		sqlx::query("SELECT * FROM attachments WHERE expense_id = $1 ORDER BY created;")
+			.bind(expense_id)
+			.fetch_all(pool)
+			.await?
+			.iter()
+			.map(Self::attachment)
+			.collect()
+	}
+}
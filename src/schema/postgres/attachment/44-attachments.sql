@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS attachments
+(
+	id uuid PRIMARY KEY,
+	content_type text NOT NULL,
+	created timestamptz NOT NULL DEFAULT now(),
+	expense_id uuid NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+	name text NOT NULL,
+	size bigint NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS attachments_expense_id ON attachments(expense_id);
+
+-- the contents of an attachment are stored outside of the database, so every attachment which is deleted (even along
+-- with its expense) is remembered until its contents have been deleted as well
+CREATE TABLE IF NOT EXISTS deleted_attachments
+(
+	id uuid PRIMARY KEY
+);
+
+CREATE OR REPLACE FUNCTION attachments_deleted() RETURNS trigger AS $$
+BEGIN
+	INSERT INTO deleted_attachments (id) SELECT id FROM deleted ON CONFLICT DO NOTHING;
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER attachments_deleted AFTER DELETE ON attachments REFERENCING OLD TABLE AS deleted
+	FOR EACH STATEMENT EXECUTE FUNCTION attachments_deleted();
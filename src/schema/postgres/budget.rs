@@ -0,0 +1,159 @@
+//! Contains a [`BudgetAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{
+	postgres::{types::PgInterval, PgRow},
+	Error,
+	Executor,
+	PgPool,
+	Postgres,
+	Result,
+	Row,
+};
+use winvoice_adapter_postgres::schema::util::duration_from;
+use winvoice_schema::{Id, Money};
+
+use crate::schema::{Allowance, Budget, BudgetAdapter};
+
+/// Stores the [`Budget`]s of jobs in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgBudget;
+
+impl PgBudget
+{
+	/// Get a [`Budget`] out of a `row` which has every column of `job_budgets`.
+	fn budget(row: &PgRow) -> Result<Budget>
+	{
+		let money = row.try_get::<Option<&str>, _>("money")?;
+		let time = row.try_get::<Option<PgInterval>, _>("time")?;
+		let allowance = match (money, time)
+		{
+			(Some(m), _) => m.parse::<Money>().map(Allowance::Money).map_err(|e| Error::Decode(e.into()))?,
+			(None, Some(t)) => duration_from(t).map(Allowance::Time)?,
+			(None, None) => return Err(Error::Decode("a budget has neither money nor time".into())),
+		};
+
+		let thresholds = row
+			.try_get::<Vec<i16>, _>("thresholds")?
+			.into_iter()
+			.map(|t| u8::try_from(t).map_err(|e| Error::Decode(e.into())))
+			.collect::<Result<_>>()?;
+
+		Ok(Budget::new(allowance, row.try_get("job_id")?, thresholds))
+	}
+}
+
+#[async_trait::async_trait]
+impl BudgetAdapter for PgBudget
+{
+	type Db = Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("budget/46-job_budgets.sql")).await?;
+		Ok(())
+	}
+
+	async fn remove(pool: &PgPool, job_ids: &[Id]) -> Result<()>
+	{
+		sqlx::query("DELETE FROM job_budgets WHERE job_id = ANY($1);").bind(job_ids).execute(pool).await?;
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool, job_ids: &[Id]) -> Result<Vec<Budget>>
+	{
+		sqlx::query("SELECT * FROM job_budgets WHERE job_id = ANY($1);")
+			.bind(job_ids)
+			.fetch_all(pool)
+			.await?
+			.iter()
+			.map(Self::budget)
+			.collect()
+	}
+
+	async fn set<'connection, Conn>(connection: Conn, budget: &Budget) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let (money, time) = match budget.allowance()
+		{
+			Allowance::Money(m) => (Some(m.to_string()), None),
+			Allowance::Time(t) => (None, Some(*t)),
+		};
+
+		sqlx::query(
+			"INSERT INTO job_budgets (job_id, money, thresholds, time) VALUES ($1, $2, $3, $4) ON CONFLICT (job_id) DO \
+			 UPDATE SET money = excluded.money, thresholds = excluded.thresholds, time = excluded.time;",
+		)
+		.bind(budget.job_id())
+		.bind(money)
+		.bind(budget.thresholds().iter().copied().map(i16::from).collect::<Vec<_>>())
+		.bind(time)
+		.execute(connection)
+		.await?;
+
+		Ok(())
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use core::time::Duration;
+
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{JobAdapter, LocationAdapter, OrganizationAdapter},
+		Deletable,
+	};
+	use winvoice_adapter_postgres::schema::{util::connect, PgJob, PgLocation, PgOrganization};
+	use winvoice_schema::{chrono::Utc, Invoice, Money};
+
+	use super::{Allowance, Budget, BudgetAdapter, PgBudget};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn set() -> DynResult<()>
+	{
+		let pool = connect();
+		PgBudget::init(&pool).await?;
+
+		let location = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let organization = PgOrganization::create(&pool, location.clone(), words::sentence(2)).await?;
+
+		let mut tx = pool.begin().await?;
+		let job = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			None,
+			Utc::now(),
+			Default::default(),
+			Duration::from_secs(60),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Default::default()) },
+			words::sentence(5),
+			words::sentence(5),
+		)
+		.await?;
+		tx.commit().await?;
+
+		assert!(PgBudget::retrieve(&pool, &[job.id]).await?.is_empty());
+
+		let hours = Budget::new(Allowance::Time(Duration::from_secs(40 * 60 * 60)), job.id, [80, 100].into());
+		PgBudget::set(&pool, &hours).await?;
+		assert_eq!(PgBudget::retrieve(&pool, &[job.id]).await?, [hours]);
+
+		let money = Budget::new(Allowance::Money(Money::new(5000_00, 2, Default::default())), job.id, [50].into());
+		PgBudget::set(&pool, &money).await?;
+		assert_eq!(PgBudget::retrieve(&pool, &[job.id]).await?, [money]);
+
+		PgBudget::remove(&pool, &[job.id]).await?;
+		assert!(PgBudget::retrieve(&pool, &[job.id]).await?.is_empty());
+
+		PgJob::delete(&pool, [&job].into_iter()).await?;
+		PgOrganization::delete(&pool, [&organization].into_iter()).await?;
+		PgLocation::delete(&pool, [&location].into_iter()).await?;
+		Ok(())
+	}
+}
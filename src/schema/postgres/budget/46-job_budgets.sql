@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS job_budgets
+(
+	job_id uuid PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+	money text,
+	thresholds smallint[] NOT NULL,
+	time interval,
+
+	CHECK ((money IS NULL) <> (time IS NULL))
+);
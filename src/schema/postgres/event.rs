@@ -0,0 +1,126 @@
+//! Contains an [`EventAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{postgres::PgListener, types::Json, Executor, PgConnection, PgPool, Result};
+use tokio::sync::broadcast::Sender;
+
+use crate::{api::Event, schema::EventAdapter};
+
+/// The channel which [`Event`]s are announced on.
+const CHANNEL: &str = "winvoice_events";
+
+/// Distributes [`Event`]s using the `LISTEN`/`NOTIFY` commands of [`Postgres`](sqlx::Postgres).
+///
+/// Because the payload of a `NOTIFY` is limited in size, the [`Event`] itself is stored in the `events` table, and only
+/// its `id` is sent as the payload. Events are kept until they have been
+/// [queued](crate::schema::WebhookAdapter::enqueue) for delivery to webhooks, and long enough after that for every
+/// listener to retrieve them.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgEvent;
+
+#[async_trait::async_trait]
+impl EventAdapter for PgEvent
+{
+	type Db = sqlx::Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("event/40-events.sql")).await?;
+		Ok(())
+	}
+
+	async fn listen(pool: &PgPool, sender: &Sender<Event>) -> Result<()>
+	{
+		let mut listener = PgListener::connect_with(pool).await?;
+		listener.listen(CHANNEL).await?;
+
+		loop
+		{
+			let notification = listener.recv().await?;
+			let Ok(id) = notification.payload().parse::<i64>()
+			else
+			{
+				tracing::warn!("Received a malformed notification on {CHANNEL}: {notification:?}");
+				continue;
+			};
+
+			let event = sqlx::query_scalar::<_, Json<Event>>("SELECT event FROM events WHERE id = $1")
+				.bind(id)
+				.fetch_optional(pool)
+				.await?;
+
+			if let Some(Json(e)) = event
+			{
+				// `Err` only means that there are no subscribers on this replica right now
+				sender.send(e).ok();
+			}
+		}
+	}
+
+	async fn publish(connection: &mut PgConnection, event: &Event) -> Result<()>
+	{
+		let id = sqlx::query_scalar::<_, i64>("INSERT INTO events (event) VALUES ($1) RETURNING id")
+			.bind(Json(event))
+			.fetch_one(&mut *connection)
+			.await?;
+
+		// notifications are only sent once the transaction is committed
+		sqlx::query("SELECT pg_notify($1, $2)").bind(CHANNEL).bind(id.to_string()).execute(connection).await?;
+		Ok(())
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use core::time::Duration;
+
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use tokio::sync::broadcast;
+	use tracing_test::traced_test;
+	use winvoice_adapter_postgres::schema::util::connect;
+	use winvoice_schema::{Department, Id};
+
+	use super::{EventAdapter, PgEvent};
+	use crate::{
+		api::{Event, EventKind},
+		dyn_result::DynResult,
+	};
+
+	#[tokio::test]
+	#[traced_test]
+	async fn publish() -> DynResult<()>
+	{
+		let pool = connect();
+		PgEvent::init(&pool).await?;
+
+		let (sender, mut receiver) = broadcast::channel(1);
+		let listener = {
+			let pool = pool.clone();
+			tokio::spawn(async move { PgEvent::listen(&pool, &sender).await })
+		};
+
+		// give the listener time to `LISTEN` before anything is published
+		tokio::time::sleep(Duration::from_millis(500)).await;
+
+		let event = || {
+			Event::new(EventKind::Create, vec![Department { id: Id::new_v4(), name: words::sentence(3) }.into()])
+		};
+
+		// an event is not published unless the changes it describes are
+		let mut tx = pool.begin().await?;
+		PgEvent::publish(&mut tx, &event()).await?;
+		tx.rollback().await?;
+
+		let event = event();
+		let mut tx = pool.begin().await?;
+		PgEvent::publish(&mut tx, &event).await?;
+		tx.commit().await?;
+
+		let received = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await??;
+		assert_eq!(received, event);
+
+		listener.abort();
+		Ok(())
+	}
+}
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS events
+(
+	id bigserial NOT NULL PRIMARY KEY,
+	created timestamptz NOT NULL DEFAULT now(),
+	event json NOT NULL,
+	queued bool NOT NULL DEFAULT false
+);
+
+CREATE INDEX IF NOT EXISTS events_unqueued ON events(id) WHERE NOT queued;
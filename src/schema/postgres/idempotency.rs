@@ -0,0 +1,118 @@
+//! Contains an [`IdempotencyAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use core::time::Duration;
+
+use sqlx::{Error, Executor, PgPool, Result, Row};
+use winvoice_schema::Id;
+
+use crate::schema::{IdempotencyAdapter, Reservation};
+
+/// Stores idempotency keys in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgIdempotency;
+
+#[async_trait::async_trait]
+impl IdempotencyAdapter for PgIdempotency
+{
+	type Db = sqlx::Postgres;
+
+	async fn complete(pool: &PgPool, user: Id, route: &str, key: &str, status: u16, body: &[u8]) -> Result<()>
+	{
+		sqlx::query(
+			"UPDATE idempotency_keys SET body = $4, status = $5 WHERE user_id = $1 AND route = $2 AND key = $3;",
+		)
+		.bind(user)
+		.bind(route)
+		.bind(key)
+		.bind(body)
+		.bind(i32::from(status))
+		.execute(pool)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("idempotency/42-idempotency-keys.sql")).await?;
+		Ok(())
+	}
+
+	async fn release(pool: &PgPool, user: Id, route: &str, key: &str) -> Result<()>
+	{
+		sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1 AND route = $2 AND key = $3;")
+			.bind(user)
+			.bind(route)
+			.bind(key)
+			.execute(pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn reserve(
+		pool: &PgPool,
+		user: Id,
+		route: &str,
+		key: &str,
+		fingerprint: &[u8],
+		lease: Duration,
+		ttl: Duration,
+	) -> Result<Reservation>
+	{
+		sqlx::query("DELETE FROM idempotency_keys WHERE created < now() - $1 * interval '1 second';")
+			.bind(ttl.as_secs_f64())
+			.execute(pool)
+			.await?;
+
+		// an expired lease means the request which held it was lost, so the key is taken over
+		let reserved = sqlx::query(
+			"INSERT INTO idempotency_keys AS k (key, route, user_id, fingerprint, leased_until) VALUES ($1, $2, $3, \
+			 $4, now() + $5 * interval '1 second') ON CONFLICT (user_id, route, key) DO UPDATE SET created = now(), \
+			 leased_until = EXCLUDED.leased_until WHERE k.status IS NULL AND k.leased_until < now() AND \
+			 k.fingerprint = EXCLUDED.fingerprint;",
+		)
+		.bind(key)
+		.bind(route)
+		.bind(user)
+		.bind(fingerprint)
+		.bind(lease.as_secs_f64())
+		.execute(pool)
+		.await?;
+
+		if reserved.rows_affected() > 0
+		{
+			return Ok(Reservation::Reserved);
+		}
+
+		let row = sqlx::query(
+			"SELECT body, fingerprint, status FROM idempotency_keys WHERE user_id = $1 AND route = $2 AND key = $3;",
+		)
+		.bind(user)
+		.bind(route)
+		.bind(key)
+		.fetch_optional(pool)
+		.await?;
+
+		let Some(r) = row
+		else
+		{
+			// the request which reserved the key was just released
+			return Ok(Reservation::Pending);
+		};
+
+		if r.try_get::<Vec<u8>, _>("fingerprint")? != fingerprint
+		{
+			return Ok(Reservation::Mismatch);
+		}
+
+		Ok(match (r.try_get::<Option<Vec<u8>>, _>("body")?, r.try_get::<Option<i32>, _>("status")?)
+		{
+			(Some(body), Some(status)) =>
+			{
+				Reservation::Completed { body, status: u16::try_from(status).map_err(|e| Error::Decode(e.into()))? }
+			},
+			_ => Reservation::Pending,
+		})
+	}
+}
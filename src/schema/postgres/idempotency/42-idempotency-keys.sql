@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys
+(
+	key text NOT NULL,
+	route text NOT NULL,
+	user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+	body bytea,
+	created timestamptz NOT NULL DEFAULT now(),
+	fingerprint bytea NOT NULL,
+	leased_until timestamptz NOT NULL,
+	status int,
+
+	PRIMARY KEY (user_id, route, key)
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_created ON idempotency_keys(created);
@@ -0,0 +1,120 @@
+//! Contains an [`IdentityAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{postgres::PgRow, Executor, PgPool, Postgres, Result, Row};
+use winvoice_schema::Id;
+
+use crate::schema::{Identity, IdentityAdapter};
+
+/// Stores the [`Identity`]s of users in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgIdentity;
+
+impl PgIdentity
+{
+	/// Get an [`Identity`] out of a `row` which has every column of `user_identities`.
+	fn identity(row: &PgRow) -> Result<Identity>
+	{
+		Ok(Identity::new(row.try_get("issuer")?, row.try_get("subject")?, row.try_get("user_id")?))
+	}
+}
+
+#[async_trait::async_trait]
+impl IdentityAdapter for PgIdentity
+{
+	type Db = Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("identity/50-user_identities.sql")).await?;
+		Ok(())
+	}
+
+	async fn find(pool: &PgPool, issuer: &str, subject: &str) -> Result<Option<Identity>>
+	{
+		sqlx::query("SELECT * FROM user_identities WHERE issuer = $1 AND subject = $2;")
+			.bind(issuer)
+			.bind(subject)
+			.fetch_optional(pool)
+			.await?
+			.as_ref()
+			.map(Self::identity)
+			.transpose()
+	}
+
+	async fn link<'connection, Conn>(connection: Conn, identity: &Identity) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		sqlx::query("INSERT INTO user_identities (issuer, subject, user_id) VALUES ($1, $2, $3);")
+			.bind(identity.issuer())
+			.bind(identity.subject())
+			.bind(identity.user_id())
+			.execute(connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool, user_ids: &[Id]) -> Result<Vec<Identity>>
+	{
+		sqlx::query("SELECT * FROM user_identities WHERE user_id = ANY($1);")
+			.bind(user_ids)
+			.fetch_all(pool)
+			.await?
+			.iter()
+			.map(Self::identity)
+			.collect()
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::{internet, password, words};
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::Deletable;
+	use winvoice_adapter_postgres::schema::util::connect;
+
+	use super::{Identity, IdentityAdapter, PgIdentity};
+	use crate::{
+		dyn_result::DynResult,
+		schema::{
+			postgres::{PgRole, PgUser},
+			RoleAdapter,
+			UserAdapter,
+		},
+	};
+
+	#[tokio::test]
+	#[traced_test]
+	async fn link() -> DynResult<()>
+	{
+		let pool = connect();
+		PgIdentity::init(&pool).await?;
+
+		let role = PgRole::create(&pool, words::sentence(5), None).await?;
+		let (user, other) = futures::try_join!(
+			PgUser::create(&pool, None, password::generate(true, true, true, 8), role.clone(), internet::username()),
+			PgUser::create(&pool, None, password::generate(true, true, true, 8), role.clone(), internet::username()),
+		)?;
+
+		let issuer = words::sentence(2);
+		let identity = Identity::new(issuer.clone(), words::sentence(3), user.id());
+		assert!(PgIdentity::find(&pool, identity.issuer(), identity.subject()).await?.is_none());
+
+		PgIdentity::link(&pool, &identity).await?;
+		assert_eq!(PgIdentity::find(&pool, identity.issuer(), identity.subject()).await?, Some(identity.clone()));
+		assert_eq!(PgIdentity::retrieve(&pool, &[user.id(), other.id()]).await?, [identity.clone()]);
+
+		// an identity can only belong to one user
+		let stolen = Identity::new(issuer, identity.subject().to_owned(), other.id());
+		assert!(PgIdentity::link(&pool, &stolen).await.is_err());
+
+		PgUser::delete(&pool, [&user, &other].into_iter()).await?;
+		assert!(PgIdentity::find(&pool, identity.issuer(), identity.subject()).await?.is_none());
+
+		PgRole::delete(&pool, [&role].into_iter()).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS user_identities
+(
+	issuer text NOT NULL,
+	subject text NOT NULL,
+	user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+	PRIMARY KEY (issuer, subject)
+);
@@ -0,0 +1,139 @@
+//! Contains an [`InvitationAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{postgres::PgRow, Executor, PgPool, Postgres, Result, Row};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+use super::token;
+use crate::schema::{Invitation, InvitationAdapter};
+
+/// Stores [`Invitation`]s in [`Postgres`](sqlx::Postgres), along with the hash of their tokens.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgInvitation;
+
+impl PgInvitation
+{
+	/// Get an [`Invitation`] out of a `row` which has every column of `invitations`.
+	fn invitation(row: &PgRow) -> Result<Invitation>
+	{
+		Ok(Invitation::new(
+			row.try_get("employee_id")?,
+			row.try_get("expires")?,
+			row.try_get("id")?,
+			row.try_get("role_id")?,
+			row.try_get("username")?,
+		))
+	}
+}
+
+#[async_trait::async_trait]
+impl InvitationAdapter for PgInvitation
+{
+	type Db = Postgres;
+
+	async fn create(
+		pool: &PgPool,
+		employee_id: Option<Id>,
+		expires: DateTime<Utc>,
+		role_id: Id,
+		username: String,
+	) -> Result<(Invitation, String)>
+	{
+		let token = token::generate();
+		let row = sqlx::query(
+			"INSERT INTO invitations (id, employee_id, expires, role_id, token_hash, username) VALUES ($1, $2, $3, $4, \
+			 $5, $6) RETURNING *;",
+		)
+		.bind(Id::new_v4())
+		.bind(employee_id)
+		.bind(expires)
+		.bind(role_id)
+		.bind(token::hash(&token))
+		.bind(username)
+		.fetch_one(pool)
+		.await?;
+
+		Self::invitation(&row).map(|i| (i, token))
+	}
+
+	async fn delete(pool: &PgPool, ids: &[Id]) -> Result<()>
+	{
+		sqlx::query("DELETE FROM invitations WHERE id = ANY($1);").bind(ids).execute(pool).await?;
+		Ok(())
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("invitation/48-invitations.sql")).await?;
+		Ok(())
+	}
+
+	async fn redeem<'connection, Conn>(connection: Conn, token: &str) -> Result<Option<Invitation>>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		sqlx::query("DELETE FROM invitations WHERE token_hash = $1 AND expires > now() RETURNING *;")
+			.bind(token::hash(token))
+			.fetch_optional(connection)
+			.await?
+			.as_ref()
+			.map(Self::invitation)
+			.transpose()
+	}
+
+	async fn retrieve(pool: &PgPool) -> Result<Vec<Invitation>>
+	{
+		sqlx::query("SELECT * FROM invitations;").fetch_all(pool).await?.iter().map(Self::invitation).collect()
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::{internet, words};
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::Deletable;
+	use winvoice_adapter_postgres::schema::util::connect;
+	use winvoice_schema::chrono::{Duration, Utc};
+
+	use super::{InvitationAdapter, PgInvitation};
+	use crate::{
+		dyn_result::DynResult,
+		schema::{postgres::PgRole, RoleAdapter},
+	};
+
+	#[tokio::test]
+	#[traced_test]
+	async fn redeem() -> DynResult<()>
+	{
+		let pool = connect();
+		PgInvitation::init(&pool).await?;
+
+		let role = PgRole::create(&pool, words::sentence(5), None).await?;
+		let (invitation, token) =
+			PgInvitation::create(&pool, None, Utc::now() + Duration::hours(1), role.id(), internet::username()).await?;
+		let (expired, expired_token) =
+			PgInvitation::create(&pool, None, Utc::now() - Duration::hours(1), role.id(), internet::username()).await?;
+
+		let retrieved = PgInvitation::retrieve(&pool).await?;
+		assert!(retrieved.contains(&invitation));
+		assert!(retrieved.contains(&expired));
+
+		assert!(PgInvitation::redeem(&pool, &expired_token).await?.is_none());
+		assert!(PgInvitation::redeem(&pool, "not a token").await?.is_none());
+		assert_eq!(PgInvitation::redeem(&pool, &token).await?, Some(invitation.clone()));
+
+		// an invitation can only be redeemed once
+		assert!(PgInvitation::redeem(&pool, &token).await?.is_none());
+
+		PgInvitation::delete(&pool, &[expired.id()]).await?;
+		let retrieved = PgInvitation::retrieve(&pool).await?;
+		assert!(!retrieved.iter().any(|i| i.id() == invitation.id() || i.id() == expired.id()));
+
+		PgRole::delete(&pool, [&role].into_iter()).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS invitations
+(
+	id uuid PRIMARY KEY,
+	employee_id uuid REFERENCES employees(id) ON DELETE CASCADE,
+	expires timestamptz NOT NULL,
+	role_id uuid NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+	token_hash text NOT NULL UNIQUE,
+	username text NOT NULL
+);
@@ -0,0 +1,118 @@
+//! Contains a [`LocationTreeAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{Executor, PgPool, Postgres, Result};
+use winvoice_adapter::{fmt::TableToSql, schema::columns::LocationColumns};
+use winvoice_schema::Id;
+
+use crate::schema::LocationTreeAdapter;
+
+/// Walks the tree of [`Location`](winvoice_schema::Location)s in [`Postgres`](sqlx::Postgres) with a recursive
+/// common table expression.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgLocationTree;
+
+#[async_trait::async_trait]
+impl LocationTreeAdapter for PgLocationTree
+{
+	type Db = Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		let (outer_id, table) = (LocationColumns::default().outer_id, LocationColumns::TABLE_NAME);
+		pool.execute(format!("CREATE INDEX IF NOT EXISTS {table}_{outer_id} ON {table} ({outer_id});").as_str()).await?;
+		Ok(())
+	}
+
+	async fn descendants<'connection, Conn>(connection: Conn, ids: &[Id]) -> Result<Vec<Id>>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let columns = LocationColumns::default();
+
+		// `UNION` rather than `UNION ALL`, so that the walk ends even if the tree already has a cycle
+		sqlx::query_scalar(&format!(
+			"WITH RECURSIVE descendants ({0}) AS (SELECT {0} FROM {2} WHERE {1} = ANY($1) UNION SELECT L.{0} FROM {2} \
+			 L JOIN descendants D ON L.{1} = D.{0}) SELECT {0} FROM descendants;",
+			columns.id,
+			columns.outer_id,
+			LocationColumns::TABLE_NAME,
+		))
+		.bind(ids)
+		.fetch_all(connection)
+		.await
+	}
+
+	async fn lock<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		// the OID of the table is the key of the lock, since it is unique to the tree
+		let table = LocationColumns::TABLE_NAME;
+		connection.execute(format!("SELECT pg_advisory_xact_lock('{table}'::regclass::oid::bigint);").as_str()).await?;
+		Ok(())
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use core::time::Duration;
+	use std::collections::HashSet;
+
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{schema::LocationAdapter, Deletable};
+	use winvoice_adapter_postgres::schema::{util::connect, PgLocation};
+
+	use super::{LocationTreeAdapter, PgLocationTree};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn descendants() -> DynResult<()>
+	{
+		let pool = connect();
+		PgLocationTree::init(&pool).await?;
+
+		let outer = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let middle = PgLocation::create(&pool, None, words::sentence(3), Some(outer.clone())).await?;
+		let inner = PgLocation::create(&pool, None, words::sentence(3), Some(middle.clone())).await?;
+
+		assert_eq!(
+			PgLocationTree::descendants(&pool, &[outer.id]).await?.into_iter().collect::<HashSet<_>>(),
+			[middle.id, inner.id].into_iter().collect::<HashSet<_>>(),
+		);
+		assert_eq!(PgLocationTree::descendants(&pool, &[middle.id]).await?, [inner.id]);
+		assert!(PgLocationTree::descendants(&pool, &[inner.id]).await?.is_empty());
+
+		PgLocation::delete(&pool, [&inner, &middle, &outer].into_iter()).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[traced_test]
+	async fn lock() -> DynResult<()>
+	{
+		let pool = connect();
+		let mut tx = pool.begin().await?;
+		PgLocationTree::lock(&mut *tx).await?;
+
+		// another transaction must wait for the first to end
+		let waiting = tokio::spawn({
+			let pool = pool.clone();
+			async move {
+				let mut other = pool.begin().await?;
+				PgLocationTree::lock(&mut *other).await?;
+				other.rollback().await
+			}
+		});
+
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		assert!(!waiting.is_finished());
+
+		tx.commit().await?;
+		tokio::time::timeout(Duration::from_secs(5), waiting).await???;
+		Ok(())
+	}
+}
@@ -1,8 +1,37 @@
 //! Contains [`schema`](super) extensions for [`Postgres`](sqlx::Postgres)
 
 mod adapter;
+mod attachment;
+mod budget;
+mod event;
+mod idempotency;
+mod identity;
+mod invitation;
+mod location_tree;
+mod password_reset;
+mod rate;
+mod review;
 mod role;
+mod saved_query;
+mod search;
+mod timer;
+mod token;
 mod user;
+mod webhook;
 
+pub use attachment::PgAttachment;
+pub use budget::PgBudget;
+pub use event::PgEvent;
+pub use idempotency::PgIdempotency;
+pub use identity::PgIdentity;
+pub use invitation::PgInvitation;
+pub use location_tree::PgLocationTree;
+pub use password_reset::PgPasswordReset;
+pub use rate::PgRate;
+pub use review::PgReview;
 pub use role::PgRole;
+pub use saved_query::PgSavedQuery;
+pub use search::PgSearch;
+pub use timer::PgTimer;
 pub use user::PgUser;
+pub use webhook::PgWebhook;
@@ -0,0 +1,106 @@
+//! Contains a [`PasswordResetAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{Executor, PgPool, Postgres, Result};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+use super::token;
+use crate::schema::PasswordResetAdapter;
+
+/// Stores the hash of the tokens which reset the passwords of [`User`](crate::schema::User)s in
+/// [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgPasswordReset;
+
+#[async_trait::async_trait]
+impl PasswordResetAdapter for PgPasswordReset
+{
+	type Db = Postgres;
+
+	async fn create(pool: &PgPool, user_id: Id, expires: DateTime<Utc>) -> Result<String>
+	{
+		sqlx::query("DELETE FROM password_resets WHERE expires <= now();").execute(pool).await?;
+
+		let token = token::generate();
+		sqlx::query(
+			"INSERT INTO password_resets (token_hash, expires, user_id) VALUES ($1, $2, $3) ON CONFLICT (user_id) DO \
+			 UPDATE SET token_hash = excluded.token_hash, expires = excluded.expires;",
+		)
+		.bind(token::hash(&token))
+		.bind(expires)
+		.bind(user_id)
+		.execute(pool)
+		.await?;
+
+		Ok(token)
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("password_reset/49-password_resets.sql")).await?;
+		Ok(())
+	}
+
+	async fn redeem<'connection, Conn>(connection: Conn, token: &str) -> Result<Option<Id>>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		sqlx::query_scalar("DELETE FROM password_resets WHERE token_hash = $1 AND expires > now() RETURNING user_id;")
+			.bind(token::hash(token))
+			.fetch_optional(connection)
+			.await
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::{internet, password, words};
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::Deletable;
+	use winvoice_adapter_postgres::schema::util::connect;
+	use winvoice_schema::chrono::{Duration, Utc};
+
+	use super::{PasswordResetAdapter, PgPasswordReset};
+	use crate::{
+		dyn_result::DynResult,
+		schema::{
+			postgres::{PgRole, PgUser},
+			RoleAdapter,
+			UserAdapter,
+		},
+	};
+
+	#[tokio::test]
+	#[traced_test]
+	async fn redeem() -> DynResult<()>
+	{
+		let pool = connect();
+		PgPasswordReset::init(&pool).await?;
+
+		let role = PgRole::create(&pool, words::sentence(5), None).await?;
+		let user =
+			PgUser::create(&pool, None, password::generate(true, true, true, 8), role.clone(), internet::username())
+				.await?;
+
+		let expired = PgPasswordReset::create(&pool, user.id(), Utc::now() - Duration::hours(1)).await?;
+		assert!(PgPasswordReset::redeem(&pool, &expired).await?.is_none());
+
+		// only the latest token of a user can be redeemed
+		let replaced = PgPasswordReset::create(&pool, user.id(), Utc::now() + Duration::hours(1)).await?;
+		let token = PgPasswordReset::create(&pool, user.id(), Utc::now() + Duration::hours(1)).await?;
+		assert!(PgPasswordReset::redeem(&pool, &replaced).await?.is_none());
+		assert!(PgPasswordReset::redeem(&pool, "not a token").await?.is_none());
+		assert_eq!(PgPasswordReset::redeem(&pool, &token).await?, Some(user.id()));
+
+		// a token can only be redeemed once
+		assert!(PgPasswordReset::redeem(&pool, &token).await?.is_none());
+
+		PgUser::delete(&pool, [&user].into_iter()).await?;
+		PgRole::delete(&pool, [&role].into_iter()).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS password_resets
+(
+	token_hash text PRIMARY KEY,
+	expires timestamptz NOT NULL,
+	user_id uuid NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,193 @@
+//! Contains a [`RateAdapter`] for the [`Postgres`] database.
+
+use sqlx::{postgres::PgRow, Error, Executor, PgPool, Postgres, Result, Row};
+use winvoice_schema::{Id, Money};
+
+use crate::schema::{Rate, RateAdapter, RateScope};
+
+/// Stores the [`Rate`]s of employees in [`Postgres`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgRate;
+
+impl PgRate
+{
+	/// Get a [`Rate`] out of a `row` which has every column of `employee_rates`.
+	fn rate(row: &PgRow) -> Result<Rate>
+	{
+		let scope = match (row.try_get::<Option<Id>, _>("job_id")?, row.try_get::<Option<Id>, _>("organization_id")?)
+		{
+			(Some(id), _) => RateScope::Job(id),
+			(None, Some(id)) => RateScope::Organization(id),
+			(None, None) => return Err(Error::Decode("a rate has neither a job nor an organization".into())),
+		};
+
+		let hourly_rate =
+			row.try_get::<&str, _>("hourly_rate")?.parse::<Money>().map_err(|e| Error::Decode(e.into()))?;
+
+		Ok(Rate::new(row.try_get("employee_id")?, hourly_rate, scope))
+	}
+}
+
+#[async_trait::async_trait]
+impl RateAdapter for PgRate
+{
+	type Db = Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("rate/47-employee_rates.sql")).await?;
+		Ok(())
+	}
+
+	async fn remove(pool: &PgPool, rates: &[Rate]) -> Result<()>
+	{
+		let (mut job_employees, mut jobs, mut organization_employees, mut organizations) =
+			(Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+		rates.iter().for_each(|r| match r.scope()
+		{
+			RateScope::Job(id) =>
+			{
+				job_employees.push(r.employee_id());
+				jobs.push(id);
+			},
+			RateScope::Organization(id) =>
+			{
+				organization_employees.push(r.employee_id());
+				organizations.push(id);
+			},
+		});
+
+		sqlx::query(
+			"DELETE FROM employee_rates WHERE (employee_id, job_id) IN (SELECT * FROM UNNEST($1::uuid[], \
+			 $2::uuid[])) OR (employee_id, organization_id) IN (SELECT * FROM UNNEST($3::uuid[], $4::uuid[]));",
+		)
+		.bind(job_employees)
+		.bind(jobs)
+		.bind(organization_employees)
+		.bind(organizations)
+		.execute(pool)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool) -> Result<Vec<Rate>>
+	{
+		sqlx::query("SELECT * FROM employee_rates;").fetch_all(pool).await?.iter().map(Self::rate).collect()
+	}
+
+	async fn set<'connection, Conn>(connection: Conn, rate: &Rate) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let query = match rate.scope()
+		{
+			RateScope::Job(_) =>
+			{
+				"INSERT INTO employee_rates (employee_id, hourly_rate, job_id) VALUES ($1, $2, $3) ON CONFLICT \
+				 (employee_id, job_id) WHERE job_id IS NOT NULL DO UPDATE SET hourly_rate = excluded.hourly_rate;"
+			},
+			RateScope::Organization(_) =>
+			{
+				"INSERT INTO employee_rates (employee_id, hourly_rate, organization_id) VALUES ($1, $2, $3) ON \
+				 CONFLICT (employee_id, organization_id) WHERE organization_id IS NOT NULL DO UPDATE SET hourly_rate \
+				 = excluded.hourly_rate;"
+			},
+		};
+
+		let (RateScope::Job(id) | RateScope::Organization(id)) = rate.scope();
+		sqlx::query(query)
+			.bind(rate.employee_id())
+			.bind(rate.hourly_rate().to_string())
+			.bind(id)
+			.execute(connection)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use core::time::Duration;
+
+	use mockd::{job, name, words};
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{DepartmentAdapter, EmployeeAdapter, JobAdapter, LocationAdapter, OrganizationAdapter},
+		Deletable,
+	};
+	use winvoice_adapter_postgres::schema::{
+		util::{connect, rand_department_name},
+		PgDepartment,
+		PgEmployee,
+		PgJob,
+		PgLocation,
+		PgOrganization,
+	};
+	use winvoice_schema::{chrono::Utc, Invoice, Money};
+
+	use super::{PgRate, Rate, RateAdapter, RateScope};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn set() -> DynResult<()>
+	{
+		let pool = connect();
+		PgRate::init(&pool).await?;
+
+		let location = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let organization = PgOrganization::create(&pool, location.clone(), words::sentence(2)).await?;
+
+		let mut tx = pool.begin().await?;
+		let department = PgDepartment::create(&mut *tx, rand_department_name()).await?;
+		let employee = PgEmployee::create(&mut *tx, department.clone(), name::full(), job::title()).await?;
+		let job = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			None,
+			Utc::now(),
+			[department.clone()].into(),
+			Duration::from_secs(60),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Default::default()) },
+			words::sentence(5),
+			words::sentence(5),
+		)
+		.await?;
+		tx.commit().await?;
+
+		let for_job = Rate::new(employee.id, Money::new(35_00, 2, Default::default()), RateScope::Job(job.id));
+		let for_organization =
+			Rate::new(employee.id, Money::new(30_00, 2, Default::default()), RateScope::Organization(organization.id));
+
+		PgRate::set(&pool, &for_job).await?;
+		PgRate::set(&pool, &for_organization).await?;
+
+		let raised = Rate::new(employee.id, Money::new(40_00, 2, Default::default()), RateScope::Job(job.id));
+		PgRate::set(&pool, &raised).await?;
+
+		let mut rates = PgRate::retrieve(&pool).await?;
+		rates.retain(|r| r.employee_id() == employee.id);
+		rates.sort_by_key(Rate::scope);
+		assert_eq!(rates, [raised, for_organization.clone()]);
+
+		PgRate::remove(&pool, &[for_job]).await?;
+		let mut rates = PgRate::retrieve(&pool).await?;
+		rates.retain(|r| r.employee_id() == employee.id);
+		assert_eq!(rates, [for_organization]);
+
+		PgJob::delete(&pool, [&job].into_iter()).await?;
+		PgEmployee::delete(&pool, [&employee].into_iter()).await?;
+		futures::try_join!(
+			PgDepartment::delete(&pool, [&department].into_iter()),
+			PgOrganization::delete(&pool, [&organization].into_iter()),
+		)?;
+
+		PgLocation::delete(&pool, [&location].into_iter()).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS employee_rates
+(
+	employee_id uuid NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+	hourly_rate text NOT NULL,
+	job_id uuid REFERENCES jobs(id) ON DELETE CASCADE,
+	organization_id uuid REFERENCES organizations(id) ON DELETE CASCADE,
+
+	CHECK ((job_id IS NULL) <> (organization_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS employee_rates_job_id ON employee_rates(employee_id, job_id)
+	WHERE job_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS employee_rates_organization_id ON employee_rates(employee_id, organization_id)
+	WHERE organization_id IS NOT NULL;
@@ -0,0 +1,218 @@
+//! Contains a [`ReviewAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{postgres::PgRow, Executor, PgPool, Postgres, Result, Row};
+use winvoice_schema::Id;
+
+use crate::schema::{Review, ReviewAdapter, ReviewStatus};
+
+/// Stores the [`Review`]s of timesheets in [`Postgres`](sqlx::Postgres).
+///
+/// Timesheets which have never been submitted have no row.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgReview;
+
+impl PgReview
+{
+	/// The value of the `status` column for a [`ReviewStatus`].
+	const fn name(status: ReviewStatus) -> &'static str
+	{
+		match status
+		{
+			ReviewStatus::Approved => "approved",
+			ReviewStatus::Draft => "draft",
+			ReviewStatus::Rejected => "rejected",
+			ReviewStatus::Submitted => "submitted",
+		}
+	}
+
+	/// Get a [`Review`] out of a `row` which has every column of `timesheet_reviews`.
+	fn review(row: &PgRow) -> Result<Review>
+	{
+		let status = match row.try_get::<&str, _>("status")?
+		{
+			"approved" => ReviewStatus::Approved,
+			"draft" => ReviewStatus::Draft,
+			"rejected" => ReviewStatus::Rejected,
+			"submitted" => ReviewStatus::Submitted,
+			s => return Err(sqlx::Error::Decode(format!("{s} is not a review status").into())),
+		};
+
+		Ok(Review::new(
+			row.try_get("comment")?,
+			row.try_get("reviewer_id")?,
+			status,
+			row.try_get("timesheet_id")?,
+			row.try_get("updated")?,
+		))
+	}
+}
+
+#[async_trait::async_trait]
+impl ReviewAdapter for PgReview
+{
+	type Db = Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("review/45-timesheet_reviews.sql")).await?;
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool, timesheet_ids: &[Id]) -> Result<Vec<Review>>
+	{
+		let mut stored = sqlx::query("SELECT * FROM timesheet_reviews WHERE timesheet_id = ANY($1);")
+			.bind(timesheet_ids)
+			.fetch_all(pool)
+			.await?
+			.iter()
+			.map(Self::review)
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(timesheet_ids
+			.iter()
+			.map(|id| match stored.iter().position(|r| r.timesheet_id() == *id)
+			{
+				Some(i) => stored.swap_remove(i),
+				None => Review::draft(*id),
+			})
+			.collect())
+	}
+
+	async fn transition<'connection, Conn>(
+		connection: Conn,
+		timesheet_id: Id,
+		from: &[ReviewStatus],
+		status: ReviewStatus,
+		comment: Option<String>,
+		reviewer_id: Option<Id>,
+	) -> Result<Option<Review>>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let query = match from.contains(&ReviewStatus::Draft)
+		{
+			// a draft has no row, so one must be inserted
+			true => sqlx::query(
+				"INSERT INTO timesheet_reviews AS r (timesheet_id, comment, reviewer_id, status) VALUES ($1, $2, $3, \
+				 $4) ON CONFLICT (timesheet_id) DO UPDATE SET comment = excluded.comment, reviewer_id = \
+				 excluded.reviewer_id, status = excluded.status, updated = now() WHERE r.status = ANY($5) RETURNING *;",
+			),
+			false => sqlx::query(
+				"UPDATE timesheet_reviews SET comment = $2, reviewer_id = $3, status = $4, updated = now() WHERE \
+				 timesheet_id = $1 AND status = ANY($5) RETURNING *;",
+			),
+		};
+
+		query
+			.bind(timesheet_id)
+			.bind(comment)
+			.bind(reviewer_id)
+			.bind(Self::name(status))
+			.bind(from.iter().map(|s| Self::name(*s)).collect::<Vec<_>>())
+			.fetch_optional(connection)
+			.await?
+			.as_ref()
+			.map(Self::review)
+			.transpose()
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{
+			DepartmentAdapter,
+			EmployeeAdapter,
+			JobAdapter,
+			LocationAdapter,
+			OrganizationAdapter,
+			TimesheetAdapter,
+		},
+		Deletable,
+	};
+	use winvoice_adapter_postgres::schema::{
+		util::{connect, rand_department_name},
+		PgDepartment,
+		PgEmployee,
+		PgJob,
+		PgLocation,
+		PgOrganization,
+		PgTimesheet,
+	};
+	use winvoice_schema::{chrono::Utc, Invoice, Money};
+
+	use super::{PgReview, ReviewAdapter, ReviewStatus};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn transition() -> DynResult<()>
+	{
+		let pool = connect();
+		PgReview::init(&pool).await?;
+
+		let department = PgDepartment::create(&pool, rand_department_name()).await?;
+		let employee = PgEmployee::create(&pool, department.clone(), words::sentence(2), words::word()).await?;
+		let location = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let organization = PgOrganization::create(&pool, location.clone(), words::sentence(2)).await?;
+
+		let mut tx = pool.begin().await?;
+		let job = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			None,
+			Utc::now(),
+			[department.clone()].into_iter().collect(),
+			core::time::Duration::from_secs(60),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Default::default()) },
+			words::sentence(5),
+			words::sentence(5),
+		)
+		.await?;
+
+		let timesheet =
+			PgTimesheet::create(&mut tx, employee.clone(), Vec::new(), job.clone(), Utc::now(), None, String::new())
+				.await?;
+		tx.commit().await?;
+
+		let drafts = PgReview::retrieve(&pool, &[timesheet.id]).await?;
+		assert_eq!(drafts.iter().map(|r| r.status()).collect::<Vec<_>>(), [ReviewStatus::Draft]);
+
+		// a draft cannot be approved
+		let approve = [ReviewStatus::Submitted];
+		let approved = PgReview::transition(&pool, timesheet.id, &approve, ReviewStatus::Approved, None, None).await?;
+		assert!(approved.is_none());
+
+		let submit = [ReviewStatus::Draft, ReviewStatus::Rejected];
+		let submitted = PgReview::transition(&pool, timesheet.id, &submit, ReviewStatus::Submitted, None, None).await?;
+		assert_eq!(submitted.map(|r| r.status()), Some(ReviewStatus::Submitted));
+
+		// it cannot be submitted twice
+		let resubmitted =
+			PgReview::transition(&pool, timesheet.id, &submit, ReviewStatus::Submitted, None, None).await?;
+		assert!(resubmitted.is_none());
+
+		let comment = Some(words::sentence(4));
+		let approved =
+			PgReview::transition(&pool, timesheet.id, &approve, ReviewStatus::Approved, comment.clone(), None).await?;
+		assert_eq!(approved.as_ref().map(|r| r.status()), Some(ReviewStatus::Approved));
+		assert_eq!(approved.as_ref().and_then(|r| r.comment()), comment.as_deref());
+		assert_eq!(PgReview::retrieve(&pool, &[timesheet.id]).await?, approved.into_iter().collect::<Vec<_>>());
+
+		PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+		futures::try_join!(
+			PgJob::delete(&pool, [&job].into_iter()),
+			PgEmployee::delete(&pool, [&employee].into_iter()),
+		)?;
+		futures::try_join!(
+			PgOrganization::delete(&pool, [&organization].into_iter()),
+			PgDepartment::delete(&pool, [&department].into_iter()),
+		)?;
+		PgLocation::delete(&pool, [&location].into_iter()).await?;
+		Ok(())
+	}
+}
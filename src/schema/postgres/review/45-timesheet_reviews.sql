@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS timesheet_reviews
+(
+	timesheet_id uuid PRIMARY KEY REFERENCES timesheets(id) ON DELETE CASCADE,
+	comment text,
+	reviewer_id uuid REFERENCES users(id) ON DELETE SET NULL,
+	status text NOT NULL,
+	updated timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,51 @@
+//! Contains a [`SavedQueryAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use serde_json::Value;
+use sqlx::{types::Json, Executor, PgPool, Result, Row};
+use winvoice_schema::Id;
+
+use crate::schema::{SavedQuery, SavedQueryAdapter};
+
+/// Stores [`SavedQuery`]s in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgSavedQuery;
+
+#[async_trait::async_trait]
+impl SavedQueryAdapter for PgSavedQuery
+{
+	type Db = sqlx::Postgres;
+
+	async fn create(pool: &PgPool, condition: Value, route: String, user: Id) -> Result<SavedQuery>
+	{
+		let id = Id::new_v4();
+		sqlx::query("INSERT INTO saved_queries (id, condition, route, user_id) VALUES ($1, $2, $3, $4);")
+			.bind(id)
+			.bind(Json(&condition))
+			.bind(&route)
+			.bind(user)
+			.execute(pool)
+			.await?;
+
+		Ok(SavedQuery::new(condition, id, route))
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("saved_query/43-saved-queries.sql")).await?;
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool, id: Id, user: Id) -> Result<Option<SavedQuery>>
+	{
+		sqlx::query("SELECT * FROM saved_queries WHERE id = $1 AND user_id = $2;")
+			.bind(id)
+			.bind(user)
+			.fetch_optional(pool)
+			.await?
+			.map(|row| {
+				let condition = row.try_get::<Json<Value>, _>("condition")?.0;
+				Ok(SavedQuery::new(condition, row.try_get("id")?, row.try_get("route")?))
+			})
+			.transpose()
+	}
+}
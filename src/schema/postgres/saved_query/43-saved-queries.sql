@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS saved_queries
+(
+	id uuid PRIMARY KEY,
+	condition jsonb NOT NULL,
+	route text NOT NULL,
+	user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE
+);
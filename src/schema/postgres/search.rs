@@ -0,0 +1,185 @@
+//! Contains a [`SearchAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{Executor, PgPool, Result, Row};
+use winvoice_adapter::{
+	fmt::TableToSql,
+	schema::columns::{ContactColumns, ExpenseColumns, JobColumns, OrganizationColumns, TimesheetColumns},
+};
+use winvoice_schema::Id;
+
+use crate::schema::{Hit, SearchAdapter};
+
+/// The text search configuration which both the documents and the search terms are parsed with.
+const CONFIG: &str = "'english'";
+
+/// The search terms, as a `tsquery`.
+const QUERY: &str = "websearch_to_tsquery('english', $1)";
+
+/// A table whose text can be searched.
+struct Searchable
+{
+	/// The column which identifies a row of the table.
+	key: &'static str,
+
+	/// The kind of [`Hit`] which a row of the table is.
+	kind: &'static str,
+
+	/// The name of the table.
+	table: &'static str,
+
+	/// The columns of the table which are searched.
+	text: Vec<&'static str>,
+}
+
+impl Searchable
+{
+	/// Every [`Searchable`] table.
+	fn all() -> [Self; 5]
+	{
+		let contact = ContactColumns::default();
+		let expense = ExpenseColumns::default();
+		let job = JobColumns::default();
+		let organization = OrganizationColumns::default();
+		let timesheet = TimesheetColumns::default();
+
+		[
+			Self {
+				key: contact.label,
+				kind: "contact",
+				table: ContactColumns::TABLE_NAME,
+				text: vec![contact.label, contact.email, contact.other, contact.phone],
+			},
+			Self {
+				key: expense.id,
+				kind: "expense",
+				table: ExpenseColumns::TABLE_NAME,
+				text: vec![expense.description],
+			},
+			Self { key: job.id, kind: "job", table: JobColumns::TABLE_NAME, text: vec![job.objectives, job.notes] },
+			Self {
+				key: organization.id,
+				kind: "organization",
+				table: OrganizationColumns::TABLE_NAME,
+				text: vec![organization.name],
+			},
+			Self {
+				key: timesheet.id,
+				kind: "timesheet",
+				table: TimesheetColumns::TABLE_NAME,
+				text: vec![timesheet.work_notes],
+			},
+		]
+	}
+
+	/// The `tsvector` of a row of the table.
+	///
+	/// This expression must be the same in the index and the query, or else the index will not be used.
+	fn document(&self) -> String
+	{
+		let text = self.text.iter().map(|c| format!("coalesce({c}, '')")).collect::<Vec<_>>();
+		format!("to_tsvector({CONFIG}, {})", text.join(" || ' ' || "))
+	}
+
+	/// Turn the `key` of a row into a [`Hit`].
+	fn hit(kind: &str, key: String) -> Result<Hit>
+	{
+		let id = || key.parse::<Id>().map_err(|e| sqlx::Error::Decode(e.into()));
+		Ok(match kind
+		{
+			"contact" => Hit::Contact(key),
+			"expense" => Hit::Expense(id()?),
+			"job" => Hit::Job(id()?),
+			"organization" => Hit::Organization(id()?),
+			"timesheet" => Hit::Timesheet(id()?),
+			_ => return Err(sqlx::Error::Decode(format!("{kind} is not a kind of search result").into())),
+		})
+	}
+}
+
+/// Searches entities using the full-text search of [`Postgres`](sqlx::Postgres).
+///
+/// The text of each table is indexed with `GIN`, and results are ranked with `ts_rank`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgSearch;
+
+#[async_trait::async_trait]
+impl SearchAdapter for PgSearch
+{
+	type Db = sqlx::Postgres;
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		let statements = Searchable::all()
+			.iter()
+			.map(|s| format!("CREATE INDEX IF NOT EXISTS {0}_search ON {0} USING GIN ({1});", s.table, s.document()))
+			.collect::<Vec<_>>();
+
+		pool.execute(statements.join("\n").as_str()).await?;
+		Ok(())
+	}
+
+	async fn search(pool: &PgPool, terms: &str, limit: i64, offset: i64) -> Result<Vec<Hit>>
+	{
+		let selects = Searchable::all()
+			.iter()
+			.map(|s| {
+				let document = s.document();
+				format!(
+					"SELECT '{}' AS kind, CAST({} AS text) AS key, ts_rank({document}, {QUERY}) AS rank FROM {} WHERE \
+					 {document} @@ {QUERY}",
+					s.kind,
+					s.key,
+					s.table,
+				)
+			})
+			.collect::<Vec<_>>();
+
+		// ties are broken so that the order is the same for every `offset`
+		sqlx::query(&format!("{} ORDER BY rank DESC, kind, key LIMIT $2 OFFSET $3;", selects.join(" UNION ALL ")))
+			.bind(terms)
+			.bind(limit)
+			.bind(offset)
+			.fetch_all(pool)
+			.await?
+			.into_iter()
+			.map(|row| Searchable::hit(row.try_get("kind")?, row.try_get("key")?))
+			.collect()
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::words;
+	use pretty_assertions::assert_eq;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{LocationAdapter, OrganizationAdapter},
+		Deletable,
+	};
+	use winvoice_adapter_postgres::schema::{util::connect, PgLocation, PgOrganization};
+
+	use super::{Hit, PgSearch, SearchAdapter};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn search() -> DynResult<()>
+	{
+		let pool = connect();
+		PgSearch::init(&pool).await?;
+
+		// a word which is unlikely to appear in any other test's data
+		let word = format!("zq{}", words::word().to_lowercase());
+		let location = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let organization = PgOrganization::create(&pool, location.clone(), format!("{} {word}", words::word())).await?;
+
+		assert_eq!(PgSearch::search(&pool, &word, 10, 0).await?, [Hit::Organization(organization.id)]);
+		assert!(PgSearch::search(&pool, &word, 10, 1).await?.is_empty());
+		assert!(PgSearch::search(&pool, &format!("{word} -{word}"), 10, 0).await?.is_empty());
+
+		PgOrganization::delete(&pool, [&organization].into_iter()).await?;
+		PgLocation::delete(&pool, [&location].into_iter()).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,106 @@
+//! Contains a [`TimerAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use sqlx::{Executor, Postgres, Result};
+use winvoice_adapter::{
+	fmt::TableToSql,
+	schema::columns::{EmployeeColumns, TimesheetColumns},
+};
+use winvoice_schema::Id;
+
+use crate::schema::TimerAdapter;
+
+/// Starts timers in [`Postgres`](sqlx::Postgres) by locking the row of the employee which the timer is for.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgTimer;
+
+#[async_trait::async_trait]
+impl TimerAdapter for PgTimer
+{
+	type Db = Postgres;
+
+	async fn lock_running<'connection, Conn>(connection: Conn, employee_id: Id) -> Result<bool>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let (employee, timesheet) = (EmployeeColumns::default(), TimesheetColumns::default());
+		sqlx::query_scalar::<_, bool>(&format!(
+			"SELECT EXISTS (SELECT 1 FROM {0} T WHERE T.{1} = E.{2} AND T.{3} IS NULL) FROM {4} E WHERE E.{2} = $1 \
+			 FOR UPDATE OF E;",
+			TimesheetColumns::TABLE_NAME,
+			timesheet.employee_id,
+			employee.id,
+			timesheet.time_end,
+			EmployeeColumns::TABLE_NAME,
+		))
+		.bind(employee_id)
+		.fetch_optional(connection)
+		.await
+		.map(|running| running.unwrap_or(false))
+	}
+}
+
+#[cfg(all(feature = "test-postgres", test))]
+mod tests
+{
+	use mockd::words;
+	use tracing_test::traced_test;
+	use winvoice_adapter::{
+		schema::{DepartmentAdapter, EmployeeAdapter, JobAdapter, LocationAdapter, OrganizationAdapter, TimesheetAdapter},
+		Deletable,
+	};
+	use winvoice_adapter_postgres::schema::{
+		util::connect,
+		PgDepartment,
+		PgEmployee,
+		PgJob,
+		PgLocation,
+		PgOrganization,
+		PgTimesheet,
+	};
+	use winvoice_schema::{chrono::Utc, Invoice, Money};
+
+	use super::{PgTimer, TimerAdapter};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	#[traced_test]
+	async fn lock_running() -> DynResult<()>
+	{
+		let pool = connect();
+
+		let department = PgDepartment::create(&pool, words::sentence(3)).await?;
+		let employee = PgEmployee::create(&pool, department.clone(), words::sentence(2), words::sentence(2)).await?;
+		let location = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+		let organization = PgOrganization::create(&pool, location.clone(), words::sentence(2)).await?;
+
+		let mut tx = pool.begin().await?;
+		let job = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			None,
+			Utc::now(),
+			[department.clone()].into_iter().collect(),
+			core::time::Duration::from_secs(60),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Default::default()) },
+			words::sentence(5),
+			words::sentence(5),
+		)
+		.await?;
+
+		assert!(!PgTimer::lock_running(&mut *tx, employee.id).await?);
+		let timesheet =
+			PgTimesheet::create(&mut tx, employee.clone(), Vec::new(), job.clone(), Utc::now(), None, String::new())
+				.await?;
+
+		assert!(PgTimer::lock_running(&mut *tx, employee.id).await?);
+		tx.commit().await?;
+
+		PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+		PgJob::delete(&pool, [&job].into_iter()).await?;
+		PgOrganization::delete(&pool, [&organization].into_iter()).await?;
+		PgLocation::delete(&pool, [&location].into_iter()).await?;
+		PgEmployee::delete(&pool, [&employee].into_iter()).await?;
+		PgDepartment::delete(&pool, [&department].into_iter()).await?;
+		Ok(())
+	}
+}
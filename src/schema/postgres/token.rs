@@ -0,0 +1,15 @@
+//! Contains functions for the secret tokens which are given out to redeem something, of which only a hash is stored.
+
+use sha2::{Digest, Sha256};
+
+/// Generate a new token.
+pub fn generate() -> String
+{
+	hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// The hash of the `token` which is stored in place of it.
+pub fn hash(token: &str) -> String
+{
+	hex::encode(Sha256::digest(token.as_bytes()))
+}
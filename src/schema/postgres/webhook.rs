@@ -0,0 +1,193 @@
+//! Contains a [`WebhookAdapter`] for the [`Postgres`](sqlx::Postgres) database.
+
+use core::time::Duration;
+
+use sqlx::{postgres::PgRow, types::Json, Error, Executor, PgPool, Result, Row};
+use winvoice_schema::Id;
+
+use crate::{
+	api::{Event, EventKind},
+	schema::{Delivery, Webhook, WebhookAdapter},
+};
+
+/// The columns of `webhook_deliveries` which make up a [`Delivery`], prefixed by `D.`.
+const DELIVERY_COLUMNS: &str =
+	"D.attempts, D.created, D.delivered, D.error, D.event, D.id, D.next_attempt, D.status, D.webhook_id";
+
+/// Stores [`Webhook`]s and their queue of [`Delivery`]s in [`Postgres`](sqlx::Postgres).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgWebhook;
+
+impl PgWebhook
+{
+	/// Get a [`Delivery`] out of a `row` which has the [`DELIVERY_COLUMNS`].
+	fn delivery(row: &PgRow) -> Result<Delivery>
+	{
+		let status = row
+			.try_get::<Option<i32>, _>("status")?
+			.map(u16::try_from)
+			.transpose()
+			.map_err(|e| Error::Decode(e.into()))?;
+
+		Ok(Delivery::new(
+			row.try_get("attempts")?,
+			row.try_get("created")?,
+			row.try_get("delivered")?,
+			row.try_get("error")?,
+			row.try_get::<Json<Event>, _>("event")?.0,
+			row.try_get("id")?,
+			row.try_get("next_attempt")?,
+			status,
+			row.try_get("webhook_id")?,
+		))
+	}
+
+	/// Get a [`Webhook`] out of a `row` which has every column of `webhooks`.
+	fn webhook(row: &PgRow) -> Result<Webhook>
+	{
+		Ok(Webhook::new(
+			row.try_get::<Json<Vec<EventKind>>, _>("events")?.0,
+			row.try_get("id")?,
+			row.try_get("secret")?,
+			row.try_get("url")?,
+		))
+	}
+}
+
+#[async_trait::async_trait]
+impl WebhookAdapter for PgWebhook
+{
+	type Db = sqlx::Postgres;
+
+	async fn accept(pool: &PgPool, id: i64, status: u16) -> Result<()>
+	{
+		sqlx::query(
+			"UPDATE webhook_deliveries
+			SET delivered = now(), error = NULL, next_attempt = NULL, status = $2
+			WHERE id = $1;",
+		)
+		.bind(id)
+		.bind(i32::from(status))
+		.execute(pool)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn claim(pool: &PgPool, limit: i64, lease: Duration) -> Result<Vec<(Delivery, Webhook)>>
+	{
+		sqlx::query(&format!(
+			"UPDATE webhook_deliveries D
+			SET attempts = D.attempts + 1, next_attempt = now() + $2 * interval '1 second'
+			FROM webhooks W
+			WHERE D.webhook_id = W.id AND D.id IN (
+				SELECT id FROM webhook_deliveries
+				WHERE next_attempt <= now()
+				ORDER BY next_attempt
+				LIMIT $1
+				FOR UPDATE SKIP LOCKED
+			)
+			RETURNING {DELIVERY_COLUMNS}, W.events, W.secret, W.url;"
+		))
+		.bind(limit)
+		.bind(lease.as_secs_f64())
+		.fetch_all(pool)
+		.await?
+		.iter()
+		.map(|row| {
+			let webhook = Webhook::new(
+				row.try_get::<Json<Vec<EventKind>>, _>("events")?.0,
+				row.try_get("webhook_id")?,
+				row.try_get("secret")?,
+				row.try_get("url")?,
+			);
+
+			Self::delivery(row).map(|d| (d, webhook))
+		})
+		.collect()
+	}
+
+	async fn create(pool: &PgPool, events: Vec<EventKind>, secret: String, url: String) -> Result<Webhook>
+	{
+		let id = Id::new_v4();
+		sqlx::query("INSERT INTO webhooks (id, events, secret, url) VALUES ($1, $2, $3, $4);")
+			.bind(id)
+			.bind(Json(&events))
+			.bind(&secret)
+			.bind(&url)
+			.execute(pool)
+			.await?;
+
+		Ok(Webhook::new(events, id, secret, url))
+	}
+
+	async fn delete(pool: &PgPool, ids: &[Id]) -> Result<()>
+	{
+		sqlx::query("DELETE FROM webhooks WHERE id = ANY($1);").bind(ids).execute(pool).await?;
+		Ok(())
+	}
+
+	async fn deliveries(pool: &PgPool, id: Id) -> Result<Vec<Delivery>>
+	{
+		sqlx::query(&format!(
+			"SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries D WHERE D.webhook_id = $1 ORDER BY D.id DESC;"
+		))
+		.bind(id)
+		.fetch_all(pool)
+		.await?
+		.iter()
+		.map(Self::delivery)
+		.collect()
+	}
+
+	async fn enqueue(pool: &PgPool) -> Result<()>
+	{
+		let mut tx = pool.begin().await?;
+
+		// events which happened before a webhook was created are not delivered to it
+		sqlx::query(
+			"WITH E AS (UPDATE events SET queued = true WHERE NOT queued RETURNING created, event, id)
+			INSERT INTO webhook_deliveries (event, event_id, webhook_id)
+			SELECT E.event::jsonb, E.id, W.id FROM E
+			JOIN webhooks W ON W.created <= E.created AND W.events @> jsonb_build_array(E.event->'kind')
+			ON CONFLICT (event_id, webhook_id) DO NOTHING;",
+		)
+		.execute(&mut tx)
+		.await?;
+
+		// listeners have had long enough to retrieve the events which were queued
+		sqlx::query("DELETE FROM events WHERE queued AND created < now() - interval '1 minute';")
+			.execute(&mut tx)
+			.await?;
+
+		tx.commit().await
+	}
+
+	async fn init(pool: &PgPool) -> Result<()>
+	{
+		pool.execute(include_str!("webhook/41-webhooks.sql")).await?;
+		Ok(())
+	}
+
+	async fn reject(pool: &PgPool, id: i64, status: Option<u16>, error: String, retry: Option<Duration>) -> Result<()>
+	{
+		sqlx::query(
+			"UPDATE webhook_deliveries
+			SET error = $2, next_attempt = now() + $3 * interval '1 second', status = $4
+			WHERE id = $1;",
+		)
+		.bind(id)
+		.bind(error)
+		.bind(retry.map(|r| r.as_secs_f64()))
+		.bind(status.map(i32::from))
+		.execute(pool)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn retrieve(pool: &PgPool) -> Result<Vec<Webhook>>
+	{
+		sqlx::query("SELECT * FROM webhooks;").fetch_all(pool).await?.iter().map(Self::webhook).collect()
+	}
+}
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS webhooks
+(
+	id uuid PRIMARY KEY,
+	created timestamptz NOT NULL DEFAULT now(),
+	events jsonb NOT NULL,
+	secret text NOT NULL,
+	url text NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries
+(
+	id bigserial NOT NULL PRIMARY KEY,
+	attempts int NOT NULL DEFAULT 0,
+	created timestamptz NOT NULL DEFAULT now(),
+	delivered timestamptz,
+	error text,
+	event jsonb NOT NULL,
+	event_id bigint NOT NULL,
+	next_attempt timestamptz DEFAULT now(),
+	status int,
+	webhook_id uuid NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+	UNIQUE (event_id, webhook_id)
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_next_attempt ON webhook_deliveries(next_attempt)
+	WHERE next_attempt IS NOT NULL;
@@ -0,0 +1,69 @@
+//! Contains the [`Rate`]s which [`Employee`](winvoice_schema::Employee)s bill at, in place of the
+//! [`Invoice::hourly_rate`](winvoice_schema::Invoice::hourly_rate) of a job.
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{Id, Money, Timesheet};
+
+/// What a [`Rate`] applies to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateScope
+{
+	/// The [`Job`](winvoice_schema::Job) with the [`Id`].
+	Job(Id),
+
+	/// Every [`Job`](winvoice_schema::Job) for the [`Organization`](winvoice_schema::Organization) with the [`Id`].
+	Organization(Id),
+}
+
+/// Corresponds to the `employee_rates` table in the database.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Rate
+{
+	/// The [`Id`] of the [`Employee`](winvoice_schema::Employee) who bills at the [`Rate`].
+	employee_id: Id,
+
+	/// How much the employee bills per hour.
+	hourly_rate: Money,
+
+	/// See [`RateScope`].
+	scope: RateScope,
+}
+
+impl Rate
+{
+	/// Create a new [`Rate`].
+	pub const fn new(employee_id: Id, hourly_rate: Money, scope: RateScope) -> Self
+	{
+		Self { employee_id, hourly_rate, scope }
+	}
+
+	/// The [`Rate`] which applies to the `timesheet`, if any of the `rates` do.
+	///
+	/// A rate for the job of the timesheet takes precedence over a rate for the client of that job.
+	pub fn applicable<'rates>(rates: &'rates [Self], timesheet: &Timesheet) -> Option<&'rates Self>
+	{
+		let employee_rates = || rates.iter().filter(|r| r.employee_id == timesheet.employee.id);
+		employee_rates()
+			.find(|r| r.scope == RateScope::Job(timesheet.job.id))
+			.or_else(|| employee_rates().find(|r| r.scope == RateScope::Organization(timesheet.job.client.id)))
+	}
+
+	/// The [`Id`] of the [`Employee`](winvoice_schema::Employee) who bills at the [`Rate`].
+	pub const fn employee_id(&self) -> Id
+	{
+		self.employee_id
+	}
+
+	/// How much the employee bills per hour.
+	pub const fn hourly_rate(&self) -> &Money
+	{
+		&self.hourly_rate
+	}
+
+	/// See [`RateScope`].
+	pub const fn scope(&self) -> RateScope
+	{
+		self.scope
+	}
+}
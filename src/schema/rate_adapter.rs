@@ -0,0 +1,27 @@
+//! Contains the adapter which stores the [`Rate`]s of [`Employee`](winvoice_schema::Employee)s.
+
+use sqlx::{Database, Executor, Pool, Result};
+
+use super::Rate;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Rate`]s.
+#[async_trait::async_trait]
+pub trait RateAdapter
+{
+	/// The [`Database`] which the [`Rate`]s are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Rate`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Remove the [`Rate`]s with the same employee and [scope](super::RateScope) as any of the `rates`.
+	async fn remove(pool: &Pool<Self::Db>, rates: &[Rate]) -> Result<()>;
+
+	/// Every [`Rate`].
+	async fn retrieve(pool: &Pool<Self::Db>) -> Result<Vec<Rate>>;
+
+	/// Set the `rate` of its employee in its [scope](super::RateScope), replacing any which was there before.
+	async fn set<'connection, Conn>(connection: Conn, rate: &Rate) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
@@ -0,0 +1,109 @@
+//! Contains the [`Review`] of a [`Timesheet`](winvoice_schema::Timesheet).
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+
+/// Where a [`Timesheet`](winvoice_schema::Timesheet) is in the process of being reviewed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus
+{
+	/// The timesheet has been approved, and can no longer be changed by the [`User`](super::User) who created it.
+	Approved,
+
+	/// The timesheet has not been submitted for review.
+	#[default]
+	Draft,
+
+	/// The timesheet was rejected, and may be changed and submitted again.
+	Rejected,
+
+	/// The timesheet is waiting to be approved or rejected, and can no longer be changed by the
+	/// [`User`](super::User) who created it.
+	Submitted,
+}
+
+impl ReviewStatus
+{
+	/// Whether the timesheet is locked against changes by users who cannot approve it.
+	pub const fn is_locked(self) -> bool
+	{
+		matches!(self, Self::Approved | Self::Submitted)
+	}
+}
+
+/// Corresponds to the `timesheet_reviews` table in the database.
+///
+/// A timesheet which has never been submitted is a [`ReviewStatus::Draft`] with no [`Review::updated`] date.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Review
+{
+	/// What the reviewer said about the timesheet when approving or rejecting it, if anything.
+	comment: Option<String>,
+
+	/// The [`Id`] of the [`User`](super::User) who approved or rejected the timesheet.
+	reviewer_id: Option<Id>,
+
+	/// See [`ReviewStatus`].
+	status: ReviewStatus,
+
+	/// The [`Id`] of the [`Timesheet`](winvoice_schema::Timesheet) being reviewed.
+	timesheet_id: Id,
+
+	/// When the [`status`](Self::status) last changed.
+	updated: Option<DateTime<Utc>>,
+}
+
+impl Review
+{
+	/// Create a new [`Review`].
+	pub const fn new(
+		comment: Option<String>,
+		reviewer_id: Option<Id>,
+		status: ReviewStatus,
+		timesheet_id: Id,
+		updated: Option<DateTime<Utc>>,
+	) -> Self
+	{
+		Self { comment, reviewer_id, status, timesheet_id, updated }
+	}
+
+	/// The [`Review`] of a timesheet which has never been submitted.
+	pub const fn draft(timesheet_id: Id) -> Self
+	{
+		Self::new(None, None, ReviewStatus::Draft, timesheet_id, None)
+	}
+
+	/// What the reviewer said about the timesheet when approving or rejecting it, if anything.
+	pub fn comment(&self) -> Option<&str>
+	{
+		self.comment.as_deref()
+	}
+
+	/// The [`Id`] of the [`User`](super::User) who approved or rejected the timesheet.
+	pub const fn reviewer_id(&self) -> Option<Id>
+	{
+		self.reviewer_id
+	}
+
+	/// See [`ReviewStatus`].
+	pub const fn status(&self) -> ReviewStatus
+	{
+		self.status
+	}
+
+	/// The [`Id`] of the [`Timesheet`](winvoice_schema::Timesheet) being reviewed.
+	pub const fn timesheet_id(&self) -> Id
+	{
+		self.timesheet_id
+	}
+
+	/// When the [`status`](Self::status) last changed.
+	pub const fn updated(&self) -> Option<DateTime<Utc>>
+	{
+		self.updated
+	}
+}
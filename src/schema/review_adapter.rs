@@ -0,0 +1,34 @@
+//! Contains the adapter which stores the [`Review`]s of [`Timesheet`](winvoice_schema::Timesheet)s.
+
+use sqlx::{Database, Executor, Pool, Result};
+use winvoice_schema::Id;
+
+use super::{Review, ReviewStatus};
+
+/// Implementors of this trait may act as an [adapter](super) for [`Review`]s.
+#[async_trait::async_trait]
+pub trait ReviewAdapter
+{
+	/// The [`Database`] which the [`Review`]s are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Review`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// The [`Review`]s of the timesheets with the `timesheet_ids`. Timesheets which have never been submitted are
+	/// [drafts](Review::draft).
+	async fn retrieve(pool: &Pool<Self::Db>, timesheet_ids: &[Id]) -> Result<Vec<Review>>;
+
+	/// Change the [`ReviewStatus`] of the timesheet with the `timesheet_id` to `status`, but only if it is currently
+	/// one of the statuses in `from`. Returns [`None`] if it was not.
+	async fn transition<'connection, Conn>(
+		connection: Conn,
+		timesheet_id: Id,
+		from: &[ReviewStatus],
+		status: ReviewStatus,
+		comment: Option<String>,
+		reviewer_id: Option<Id>,
+	) -> Result<Option<Review>>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
@@ -0,0 +1,48 @@
+//! Contains information about a [`SavedQuery`], which can be used to retrieve entities with a `GET` request.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use winvoice_schema::Id;
+
+/// Corresponds to the `saved_queries` table in the database.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SavedQuery
+{
+	/// The match condition, as it would appear in the [`Post`](crate::api::request::Post) request to the
+	/// [`route`](Self::route).
+	condition: Value,
+
+	/// The unique identity of the [`SavedQuery`].
+	id: Id,
+
+	/// The route which the [`SavedQuery`] retrieves entities from.
+	route: String,
+}
+
+impl SavedQuery
+{
+	/// Create a new [`SavedQuery`].
+	pub const fn new(condition: Value, id: Id, route: String) -> Self
+	{
+		Self { condition, id, route }
+	}
+
+	/// The match condition, as it would appear in the [`Post`](crate::api::request::Post) request to the
+	/// [`route`](Self::route).
+	pub const fn condition(&self) -> &Value
+	{
+		&self.condition
+	}
+
+	/// The unique identity of the [`SavedQuery`].
+	pub const fn id(&self) -> Id
+	{
+		self.id
+	}
+
+	/// The route which the [`SavedQuery`] retrieves entities from.
+	pub fn route(&self) -> &str
+	{
+		self.route.as_ref()
+	}
+}
@@ -0,0 +1,24 @@
+//! Contains the adapter which stores [`SavedQuery`]s.
+
+use serde_json::Value;
+use sqlx::{Database, Pool, Result};
+use winvoice_schema::Id;
+
+use super::SavedQuery;
+
+/// Implementors of this trait may act as an [adapter](super) for [`SavedQuery`]s.
+#[async_trait::async_trait]
+pub trait SavedQueryAdapter
+{
+	/// The [`Database`] which the [`SavedQuery`]s are stored in.
+	type Db: Database;
+
+	/// Save the `condition` for retrieving entities from the `route`, on behalf of the `user`.
+	async fn create(pool: &Pool<Self::Db>, condition: Value, route: String, user: Id) -> Result<SavedQuery>;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`SavedQuery`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// The [`SavedQuery`] with the `id`, if there is one which was saved by the `user`.
+	async fn retrieve(pool: &Pool<Self::Db>, id: Id, user: Id) -> Result<Option<SavedQuery>>;
+}
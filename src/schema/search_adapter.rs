@@ -0,0 +1,20 @@
+//! Contains the adapter which searches the text of entities.
+
+use sqlx::{Database, Pool, Result};
+
+use super::Hit;
+
+/// Implementors of this trait may act as an [adapter](super) for searching the text of entities.
+#[async_trait::async_trait]
+pub trait SearchAdapter
+{
+	/// The [`Database`] which the entities are stored in.
+	type Db: Database;
+
+	/// Initialize whatever is needed on the [`Database`] to search entities.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Find at most `limit` entities whose text matches the `terms`, the most relevant first, after skipping the
+	/// first `offset` of them.
+	async fn search(pool: &Pool<Self::Db>, terms: &str, limit: i64, offset: i64) -> Result<Vec<Hit>>;
+}
@@ -0,0 +1,22 @@
+//! Contains the adapter which starts [`Timesheet`](winvoice_schema::Timesheet)s as timers.
+
+use sqlx::{Database, Executor, Result};
+use winvoice_schema::Id;
+
+/// Implementors of this trait may act as an [adapter](super) for starting
+/// [`Timesheet`](winvoice_schema::Timesheet)s as timers.
+#[async_trait::async_trait]
+pub trait TimerAdapter
+{
+	/// The [`Database`] which the [`Timesheet`](winvoice_schema::Timesheet)s are stored in.
+	type Db: Database;
+
+	/// Lock the [`Employee`](winvoice_schema::Employee) with the `employee_id` until the transaction of the
+	/// `connection` ends, and then check whether they have a running timesheet.
+	///
+	/// Two timers which are started for the same employee at once will therefore take turns, so the second will see
+	/// the timesheet of the first.
+	async fn lock_running<'connection, Conn>(connection: Conn, employee_id: Id) -> Result<bool>
+	where
+		Conn: Executor<'connection, Database = Self::Db>;
+}
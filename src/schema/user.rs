@@ -126,8 +126,16 @@ impl User
 	}
 
 	/// Post the [`DateTime`] that the `password` expires. Used to enforce password rotation.
+	///
+	/// A `password` which was set at the [`DateTime::UNIX_EPOCH`] was not chosen by the [`User`], and has already
+	/// expired.
 	pub fn password_expires(&self) -> Option<Result<DateTime<Utc>, OutOfRangeError>>
 	{
+		if self.password_set == DateTime::<Utc>::UNIX_EPOCH
+		{
+			return Some(Ok(self.password_set));
+		}
+
 		self.role.password_ttl().map(|ttl| Duration::from_std(ttl).map(|d| self.password_set + d))
 	}
 
@@ -0,0 +1,59 @@
+//! Contains information about a [`Webhook`] which [`Event`](crate::api::Event)s are delivered to.
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::Id;
+
+use crate::api::EventKind;
+
+/// Corresponds to the `webhooks` table in the database.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Webhook
+{
+	/// The kinds of [`Event`](crate::api::Event) which are delivered to the [`Webhook`].
+	events: Vec<EventKind>,
+
+	/// The unique identity of the [`Webhook`].
+	id: Id,
+
+	/// The key which every delivery is signed with, so that the receiver can verify where it came from.
+	///
+	/// It is never sent back to clients once the [`Webhook`] is created.
+	#[serde(default, skip_serializing)]
+	secret: String,
+
+	/// Where [`Event`](crate::api::Event)s are `POST`ed to.
+	url: String,
+}
+
+impl Webhook
+{
+	/// Create a new [`Webhook`].
+	pub const fn new(events: Vec<EventKind>, id: Id, secret: String, url: String) -> Self
+	{
+		Self { events, id, secret, url }
+	}
+
+	/// The kinds of [`Event`](crate::api::Event) which are delivered to the [`Webhook`].
+	pub fn events(&self) -> &[EventKind]
+	{
+		self.events.as_ref()
+	}
+
+	/// The unique identity of the [`Webhook`].
+	pub const fn id(&self) -> Id
+	{
+		self.id
+	}
+
+	/// The key which every delivery is signed with, so that the receiver can verify where it came from.
+	pub fn secret(&self) -> &str
+	{
+		self.secret.as_ref()
+	}
+
+	/// Where [`Event`](crate::api::Event)s are `POST`ed to.
+	pub fn url(&self) -> &str
+	{
+		self.url.as_ref()
+	}
+}
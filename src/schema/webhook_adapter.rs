@@ -0,0 +1,62 @@
+//! Contains the adapter which stores [`Webhook`]s, and queues the [`Delivery`]s of [`Event`](crate::api::Event)s to
+//! them.
+
+use core::time::Duration;
+
+use sqlx::{Database, Pool, Result};
+use winvoice_schema::Id;
+
+use super::{Delivery, Webhook};
+use crate::api::EventKind;
+
+/// Implementors of this trait may act as an [adapter](super) for [`Webhook`]s.
+///
+/// The queue of [`Delivery`]s is shared by every replica of the [`winvoice_server`](crate) which uses the same
+/// [`Database`], so a [`Delivery`] may only be [claimed](Self::claim) by one of them at a time.
+#[async_trait::async_trait]
+pub trait WebhookAdapter
+{
+	/// The [`Database`] which the [`Webhook`]s are stored in.
+	type Db: Database;
+
+	/// Record that the [`Delivery`] with the `id` was accepted with the HTTP `status`.
+	async fn accept(pool: &Pool<Self::Db>, id: i64, status: u16) -> Result<()>;
+
+	/// Take up to `limit` [`Delivery`]s which are due, along with the [`Webhook`] they are for.
+	///
+	/// Each claimed [`Delivery`] counts as an attempt, and is postponed by the `lease` so that it is tried again if
+	/// this replica never [accept](Self::accept)s or [reject](Self::reject)s it.
+	async fn claim(pool: &Pool<Self::Db>, limit: i64, lease: Duration) -> Result<Vec<(Delivery, Webhook)>>;
+
+	/// Create a new [`Webhook`] which the `events` will be delivered to.
+	async fn create(pool: &Pool<Self::Db>, events: Vec<EventKind>, secret: String, url: String) -> Result<Webhook>;
+
+	/// Delete the [`Webhook`]s with the `ids`, along with their [`Delivery`]s.
+	async fn delete(pool: &Pool<Self::Db>, ids: &[Id]) -> Result<()>;
+
+	/// The [`Delivery`]s to the [`Webhook`] with the `id`, most recent first.
+	async fn deliveries(pool: &Pool<Self::Db>, id: Id) -> Result<Vec<Delivery>>;
+
+	/// Queue a [`Delivery`] of each [`Event`](crate::api::Event) which was [published](super::EventAdapter::publish)
+	/// to the same [`Database`] to every [`Webhook`] which subscribes to its [`EventKind`].
+	///
+	/// Events which were already queued are skipped, so any number of replicas may do this at once. Events are only
+	/// removed once they have been queued, so none are lost if no replica was queueing them for a while.
+	async fn enqueue(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Initialize whatever is needed on the [`Database`] to store [`Webhook`]s.
+	async fn init(pool: &Pool<Self::Db>) -> Result<()>;
+
+	/// Record that the [`Delivery`] with the `id` failed because of the `error`, with the HTTP `status` if there was
+	/// one. It is tried again after the `retry` delay, or never if that is [`None`].
+	async fn reject(
+		pool: &Pool<Self::Db>,
+		id: i64,
+		status: Option<u16>,
+		error: String,
+		retry: Option<Duration>,
+	) -> Result<()>;
+
+	/// Every [`Webhook`].
+	async fn retrieve(pool: &Pool<Self::Db>) -> Result<Vec<Webhook>>;
+}
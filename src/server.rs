@@ -1,18 +1,30 @@
 //! The `server` module functions to spawn an [`axum_server`] which communicates over TLS.
 
+mod attachments;
 mod auth;
+mod conditional;
 mod db_session_store;
+mod extract;
 mod handler;
+mod idempotency;
+mod ldap;
+mod notifier;
+mod oidc;
+mod password_resets;
 mod response;
 mod state;
+mod storage;
 #[cfg(test)]
 mod test_client_ext;
 #[cfg(test)]
 mod tests;
+mod webhook;
 
 use core::{fmt::Display, marker::PhantomData, time::Duration};
 use std::net::SocketAddr;
 
+pub use attachments::Attachments;
+pub use auth::Bootstrap;
 use auth::{DbUserStore, InitializableWithAuthorization, RequireAuthLayer, UserStore};
 use axum::{
 	error_handling::HandleErrorLayer,
@@ -34,11 +46,17 @@ use axum_login::{
 };
 use axum_server::tls_rustls::RustlsConfig;
 use db_session_store::DbSessionStore;
+pub(crate) use handler::import;
 use handler::Handler;
+pub use ldap::Ldap;
+pub use notifier::{LogFileNotifier, Notifier, SmtpNotifier};
+pub use oidc::Oidc;
+pub use password_resets::PasswordResets;
 pub use response::VersionResponse;
 use semver::VersionReq;
-use sqlx::{Connection, Database, Executor, QueryBuilder};
+use sqlx::{Connection, Database, Executor, Pool, QueryBuilder};
 pub use state::ServerState;
+pub use storage::{LocalStorage, Storage};
 use tower::{timeout, ServiceBuilder};
 use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
 use winvoice_adapter::{fmt::sql, Initializable};
@@ -46,7 +64,24 @@ use winvoice_adapter::{fmt::sql, Initializable};
 use crate::{
 	api::{self, routes},
 	bool_ext::BoolExt,
-	schema::{columns::UserColumns, Adapter, User},
+	schema::{
+		columns::UserColumns,
+		Adapter,
+		AttachmentAdapter,
+		BudgetAdapter,
+		EventAdapter,
+		IdempotencyAdapter,
+		IdentityAdapter,
+		InvitationAdapter,
+		LocationTreeAdapter,
+		PasswordResetAdapter,
+		RateAdapter,
+		ReviewAdapter,
+		SavedQueryAdapter,
+		SearchAdapter,
+		User,
+		WebhookAdapter,
+	},
 	DynResult,
 };
 
@@ -80,20 +115,70 @@ where
 		Self { address, phantom: PhantomData, tls }
 	}
 
+	/// Create the tables (and the first [`User`] as the `bootstrap` describes, if there are none) which the server
+	/// needs in the database of the `pool`.
+	pub async fn init(pool: &Pool<A::Db>, bootstrap: &Bootstrap) -> sqlx::Result<()>
+	{
+		let session_store = DbSessionStore::new(pool.clone());
+		futures::try_join!(
+			A::init_with_auth(pool, bootstrap),
+			session_store.init(),
+			A::Event::init(pool),
+			A::Webhook::init(pool),
+		)?;
+
+		// these refer to the tables created above, so they must be initialized afterwards
+		futures::try_join!(
+			A::Attachment::init(pool),
+			A::Budget::init(pool),
+			A::Idempotency::init(pool),
+			A::Identity::init(pool),
+			A::Invitation::init(pool),
+			A::LocationTree::init(pool),
+			A::PasswordReset::init(pool),
+			A::Rate::init(pool),
+			A::Review::init(pool),
+			A::SavedQuery::init(pool),
+			A::Search::init(pool),
+		)?;
+		Ok(())
+	}
+
 	/// Create an [`Router`] based on the `connect_options`.
 	///
 	/// Operations `timeout`, if specified.
+	#[allow(clippy::too_many_arguments)]
 	pub async fn serve(
 		self,
+		attachments: Attachments,
+		bootstrap: Bootstrap,
 		cookie_domain: Option<String>,
 		cookie_secret: Vec<u8>,
 		cors_allow_origin: Vec<HeaderValue>,
+		idempotency_ttl: Duration,
+		ldap: Option<Ldap>,
+		oidc: Option<Oidc>,
+		password_resets: PasswordResets,
 		state: ServerState<A::Db>,
 		session_ttl: Duration,
 		timeout: Option<Duration>,
 	) -> DynResult<()>
 	{
-		let router = Self::router(cookie_domain, cookie_secret, cors_allow_origin, state, session_ttl, timeout).await?;
+		let router = Self::router(
+			attachments,
+			bootstrap,
+			cookie_domain,
+			cookie_secret,
+			cors_allow_origin,
+			idempotency_ttl,
+			ldap,
+			oidc,
+			password_resets,
+			state,
+			session_ttl,
+			timeout,
+		)
+		.await?;
 		let service = router.into_make_service();
 
 		match self.tls
@@ -106,10 +191,17 @@ where
 	}
 
 	/// Create the [`Router`] that will be used by the [`Server`].
+	#[allow(clippy::too_many_arguments)]
 	async fn router(
+		attachments: Attachments,
+		bootstrap: Bootstrap,
 		cookie_domain: Option<String>,
 		cookie_secret: Vec<u8>,
 		cors_allow_origin: Vec<HeaderValue>,
+		idempotency_ttl: Duration,
+		ldap: Option<Ldap>,
+		oidc: Option<Oidc>,
+		password_resets: PasswordResets,
 		state: ServerState<A::Db>,
 		session_ttl: Duration,
 		timeout: Option<Duration>,
@@ -158,27 +250,71 @@ where
 			Ok(next.run(req).await)
 		}
 
+		Self::init(state.pool(), &bootstrap).await?;
 		let session_store = DbSessionStore::new(state.pool().clone());
-		futures::try_join!(A::init_with_auth(state.pool()), session_store.init())?;
 
+		let state = state.with_event_adapter::<A::Event>();
+		tokio::spawn(attachments.clone().clean::<A::Attachment>(state.pool().clone()));
+		tokio::spawn(webhook::deliver::<A::Webhook>(state.pool().clone()));
+
+		let body_limit = attachments.max_size().max(idempotency::BODY_LIMIT);
 		let handler = Handler::<A>::new();
+		let get = |router| conditional::get::<A::SavedQuery>(&state, router);
 		let mut router = Router::new()
-			.route(routes::CONTACT, handler.contact())
-			.route(routes::DEPARTMENT, handler.department())
-			.route(routes::EMPLOYEE, handler.employee())
-			.route(routes::EXPENSE, handler.expense())
+			.route(routes::BATCH, handler.batch())
+			.route(routes::CONTACT, get(handler.contact()))
+			.route(routes::DEPARTMENT, get(handler.department()))
+			.route(routes::EMPLOYEE, get(handler.employee()))
+			.route(routes::EVENTS, handler.events())
+			.route(routes::EXPENSE, get(handler.expense()))
+			.route(routes::EXPENSE_ATTACHMENT, handler.expense_attachment(attachments))
 			.route(routes::EXPORT, handler.export())
-			.route(routes::JOB, handler.job())
-			.route(routes::LOCATION, handler.location())
+			.route(routes::IMPORT, handler.import())
+			.route(routes::INVITATION, handler.invitation())
+			.route(routes::JOB, get(handler.job()))
+			.route(routes::JOB_BUDGET, handler.job_budget())
+			.route(routes::JOB_CLOSE, handler.job_close())
+			.route(routes::JOB_INVOICE_ISSUE, handler.job_invoice_issue())
+			.route(routes::JOB_INVOICE_PAY, handler.job_invoice_pay())
+			.route(routes::JOB_REOPEN, handler.job_reopen())
+			.route(routes::LOCATION, get(handler.location()))
+			.route(routes::LOCATION_ANCESTORS, handler.location_ancestors())
+			.route(routes::LOCATION_DESCENDANTS, handler.location_descendants())
+			.route(routes::LOCATION_MOVE, handler.location_move())
+			.route(routes::LOCATION_ORGANIZATIONS, handler.location_organizations())
 			.route(routes::LOGOUT, handler.logout())
-			.route(routes::ORGANIZATION, handler.organization())
-			.route(routes::ROLE, handler.role())
-			.route(routes::TIMESHEET, handler.timesheet())
-			.route(routes::USER, handler.user())
+			.route(routes::ORGANIZATION, get(handler.organization()))
+			.route(routes::QUERY, handler.query())
+			.route(routes::RATE, handler.rate())
+			.route(routes::ROLE, get(handler.role()))
+			.route(routes::SEARCH, handler.search())
+			.route(routes::TIMESHEET, get(handler.timesheet()))
+			.route(routes::TIMESHEET_REVIEW, handler.timesheet_review())
+			.route(routes::TIMESHEET_START, handler.timesheet_start())
+			.route(routes::TIMESHEET_STOP, handler.timesheet_stop())
+			.route(routes::TIMESHEET_SUBMIT, handler.timesheet_submit())
+			.route(routes::USER, get(handler.user()))
+			.route(routes::WEBHOOK, handler.webhook())
+			.route(routes::WEBHOOK_DELIVERIES, handler.webhook_deliveries())
 			.route(routes::WHO_AM_I, handler.who_am_i())
+			.route_layer(middleware::from_fn_with_state(
+				(state.pool().clone(), timeout.unwrap_or(idempotency::LEASE), idempotency_ttl, body_limit),
+				idempotency::handle::<A::Idempotency>,
+			))
 			.route_layer(RequireAuthLayer::login())
 			.route(routes::HEALTHY, handler.healthy())
-			.route(routes::LOGIN, handler.login());
+			.route(routes::INVITATION_ACCEPT, handler.invitation_accept())
+			.route(routes::LOGIN, handler.login(ldap))
+			.route(routes::PASSWORD, handler.password())
+			.route(routes::PASSWORD_RESET, handler.password_reset(password_resets))
+			.route(routes::PASSWORD_RESET_REDEEM, handler.password_reset_redeem());
+
+		if let Some(o) = oidc
+		{
+			router = router
+				.route(routes::LOGIN_OIDC, handler.login_oidc(o.clone()))
+				.route(routes::LOGIN_OIDC_CALLBACK, handler.login_oidc_callback(o));
+		}
 
 		if let Some(t) = timeout
 		{
@@ -220,6 +356,8 @@ where
 					.allow_credentials(true)
 					.allow_headers([
 						HeaderName::from_static(api::HEADER),
+						HeaderName::from_static(api::IDEMPOTENCY_HEADER),
+						HeaderName::from_static(api::OVERLAP_HEADER),
 						HeaderName::from_static("sec-fetch-dest"),
 						HeaderName::from_static("sec-fetch-mode"),
 						HeaderName::from_static("sec-fetch-site"),
@@ -235,14 +373,20 @@ where
 						header::COOKIE,
 						header::DNT,
 						header::HOST,
+						header::IF_NONE_MATCH,
 						header::ORIGIN,
 						header::REFERER,
 						header::TE,
 						header::USER_AGENT,
 					])
-					.allow_methods([Method::DELETE, Method::POST, Method::PATCH, Method::PUT])
+					.allow_methods([Method::DELETE, Method::GET, Method::POST, Method::PATCH, Method::PUT])
 					.allow_origin(cors_allow_origin)
-					.allow_private_network(true),
+					.allow_private_network(true)
+					.expose_headers([
+						HeaderName::from_static(api::IDEMPOTENCY_REPLAY_HEADER),
+						header::CONTENT_DISPOSITION,
+						header::ETAG,
+					]),
 			)
 			.layer(TraceLayer::new_for_http())
 			.with_state(state))
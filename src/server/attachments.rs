@@ -0,0 +1,146 @@
+//! Contains the configuration of the files which may be attached to expenses.
+
+use core::{
+	fmt::{self, Debug, Formatter},
+	time::Duration,
+};
+use std::sync::Arc;
+
+use sqlx::Pool;
+use winvoice_schema::Id;
+
+use super::storage::Storage;
+use crate::schema::AttachmentAdapter;
+
+/// How many [`deleted`](AttachmentAdapter::deleted) attachments have their contents removed at once.
+const BATCH_SIZE: i64 = 64;
+
+/// How long to wait before checking for [`deleted`](AttachmentAdapter::deleted) attachments again, after there were
+/// none.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where the files attached to expenses are stored, and which files are accepted.
+#[derive(Clone)]
+pub struct Attachments
+{
+	/// The largest attachment which is accepted, in bytes.
+	max_size: usize,
+
+	/// Where the attachments are stored.
+	storage: Arc<dyn Storage>,
+
+	/// The MIME types of the attachments which are accepted.
+	types: Vec<String>,
+}
+
+impl Attachments
+{
+	/// Create a new [`Attachments`] configuration.
+	pub fn new(max_size: usize, storage: impl Storage + 'static, types: Vec<String>) -> Self
+	{
+		Self { max_size, storage: Arc::new(storage), types }
+	}
+
+	/// Whether an attachment with the MIME `content_type` is accepted.
+	///
+	/// Parameters such as `charset` are ignored, and the comparison is not case-sensitive.
+	pub fn accepts(&self, content_type: &str) -> bool
+	{
+		let essence = content_type.split(';').next().unwrap_or_default().trim();
+		self.types.iter().any(|t| t.eq_ignore_ascii_case(essence))
+	}
+
+	/// Remove the contents of the attachments which `A` has [`deleted`](AttachmentAdapter::deleted), forever.
+	///
+	/// This is how the contents of attachments are removed when their expense is deleted.
+	pub async fn clean<A>(self, pool: Pool<A::Db>)
+	where
+		A: AttachmentAdapter,
+	{
+		loop
+		{
+			let ids = A::deleted(&pool, BATCH_SIZE).await.unwrap_or_else(|e| {
+				tracing::error!("Could not find the attachments which were deleted: {e}");
+				Vec::new()
+			});
+
+			// there may be more which were deleted
+			if !ids.is_empty() && self.remove::<A>(&pool, &ids).await
+			{
+				continue;
+			}
+
+			tokio::time::sleep(POLL_INTERVAL).await;
+		}
+	}
+
+	/// The largest attachment which is accepted, in bytes.
+	pub const fn max_size(&self) -> usize
+	{
+		self.max_size
+	}
+
+	/// Remove the contents of the attachments with the `ids`, which `A` has already deleted. Returns whether all of
+	/// them were removed.
+	pub async fn remove<A>(&self, pool: &Pool<A::Db>, ids: &[Id]) -> bool
+	where
+		A: AttachmentAdapter,
+	{
+		let mut removed = Vec::with_capacity(ids.len());
+		for id in ids
+		{
+			match self.storage.delete(*id).await
+			{
+				Ok(()) => removed.push(*id),
+				Err(e) => tracing::error!("Could not delete the contents of attachment {id}: {e}"),
+			}
+		}
+
+		if let Err(e) = A::forget(pool, &removed).await
+		{
+			tracing::error!("Could not forget the attachments whose contents were deleted: {e}");
+			return false;
+		}
+
+		removed.len() == ids.len()
+	}
+
+	/// Where the attachments are stored.
+	pub fn storage(&self) -> &dyn Storage
+	{
+		self.storage.as_ref()
+	}
+}
+
+impl Debug for Attachments
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+	{
+		f.debug_struct("Attachments").field("max_size", &self.max_size).field("types", &self.types).finish()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::env;
+
+	use super::Attachments;
+	use crate::server::LocalStorage;
+
+	#[test]
+	fn accepts()
+	{
+		let attachments = Attachments::new(
+			1024,
+			LocalStorage::new(env::temp_dir()),
+			vec!["application/pdf".into(), "image/png".into()],
+		);
+
+		assert!(attachments.accepts("application/pdf"));
+		assert!(attachments.accepts("Image/PNG"));
+		assert!(attachments.accepts("application/pdf; charset=binary"));
+		assert!(!attachments.accepts("image/jpeg"));
+		assert!(!attachments.accepts(""));
+	}
+}
@@ -1,8 +1,10 @@
 //! Contains data and algorithms used for authenticating users.
 
+mod bootstrap;
 mod initializable_with_authorization;
 
 use axum_login::{extractors::AuthContext as Context, RequireAuthorizationLayer, SqlxStore};
+pub use bootstrap::Bootstrap;
 pub use initializable_with_authorization::InitializableWithAuthorization;
 use sqlx::Pool;
 use winvoice_schema::Id;
@@ -0,0 +1,58 @@
+//! Contains the [`Bootstrap`] configuration for the first [`User`](crate::schema::User).
+
+use core::time::Duration;
+
+/// The name of the [`Role`](crate::schema::Role) given to the first [`User`](crate::schema::User). It is fixed so that
+/// permissions policies can refer to it before the server is ever run.
+pub const ROLE: &str = "admin";
+
+/// How to create the first [`User`](crate::schema::User), when there are no users in the database.
+///
+/// Their password must be changed the first time they log in (see [`routes::PASSWORD`](crate::api::routes::PASSWORD)).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bootstrap
+{
+	/// The password of the first user. When [`None`], one is generated and printed.
+	password: Option<String>,
+
+	/// The [`password_ttl`](crate::schema::Role::password_ttl) of the [`ROLE`].
+	password_ttl: Option<Duration>,
+
+	/// The username of the first user.
+	username: String,
+}
+
+impl Bootstrap
+{
+	/// Create a new [`Bootstrap`].
+	pub const fn new(password: Option<String>, password_ttl: Option<Duration>, username: String) -> Self
+	{
+		Self { password, password_ttl, username }
+	}
+
+	/// The password of the first user, if one was given.
+	pub fn password(&self) -> Option<&str>
+	{
+		self.password.as_deref()
+	}
+
+	/// The [`password_ttl`](crate::schema::Role::password_ttl) of the [`ROLE`].
+	pub const fn password_ttl(&self) -> Option<Duration>
+	{
+		self.password_ttl
+	}
+
+	/// The username of the first user.
+	pub fn username(&self) -> &str
+	{
+		self.username.as_ref()
+	}
+}
+
+impl Default for Bootstrap
+{
+	fn default() -> Self
+	{
+		Self::new(None, None, ROLE.into())
+	}
+}
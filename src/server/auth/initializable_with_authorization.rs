@@ -3,9 +3,18 @@
 
 use sqlx::{Pool, Result};
 use winvoice_adapter::Initializable;
-
 #[cfg(feature = "postgres")]
-use crate::schema::postgres::{PgRole, PgUser};
+use {
+	super::bootstrap,
+	crate::{
+		schema::postgres::{PgRole, PgUser},
+		utils,
+	},
+	winvoice_adapter::Updatable,
+	winvoice_schema::chrono::{DateTime, Utc},
+};
+
+use super::Bootstrap;
 use crate::schema::{RoleAdapter, UserAdapter};
 
 /// Implementors of this trait are marked as able to both Initialize the base Winvoice
@@ -14,15 +23,16 @@ use crate::schema::{RoleAdapter, UserAdapter};
 #[async_trait::async_trait]
 pub trait InitializableWithAuthorization: Initializable
 {
-	/// Initialize the [`auth`](super) tables on the [`Database`](sqlx::Database)
-	async fn init_with_auth(pool: &Pool<Self::Db>) -> Result<()>;
+	/// Initialize the [`auth`](super) tables on the [`Database`](sqlx::Database), creating the first user as the
+	/// `bootstrap` describes if there are none.
+	async fn init_with_auth(pool: &Pool<Self::Db>, bootstrap: &Bootstrap) -> Result<()>;
 }
 
 #[cfg(feature = "postgres")]
 #[async_trait::async_trait]
 impl InitializableWithAuthorization for winvoice_adapter_postgres::PgSchema
 {
-	async fn init_with_auth(pool: &sqlx::PgPool) -> Result<()>
+	async fn init_with_auth(pool: &sqlx::PgPool, bootstrap: &Bootstrap) -> Result<()>
 	{
 		let mut tx = pool.begin().await?;
 		Self::init(&mut tx).await?;
@@ -31,12 +41,28 @@ impl InitializableWithAuthorization for winvoice_adapter_postgres::PgSchema
 		sqlx::query_file!("src/server/auth/initializable_with_authorization/21-users.sql").execute(&mut tx).await?;
 
 		let has_rows = sqlx::query!("SELECT * FROM users LIMIT 1").fetch_optional(&mut tx).await?;
-		if has_rows.is_none()
+		if has_rows.is_some()
+		{
+			return tx.commit().await;
+		}
+
+		let generated = bootstrap.password().is_none().then(utils::password);
+		let password = generated.as_deref().or(bootstrap.password()).unwrap_or_default();
+
+		let role = PgRole::create(&mut tx, bootstrap::ROLE.into(), bootstrap.password_ttl()).await?;
+		let mut user = PgUser::create(&mut tx, None, password.into(), role, bootstrap.username().into()).await?;
+
+		// nobody chose this password for themselves, so it must be changed before it can be used to log in
+		user.password_set = DateTime::<Utc>::UNIX_EPOCH;
+		PgUser::update(&mut tx, [&user].into_iter()).await?;
+		tx.commit().await?;
+
+		tracing::info!("Created the first user, {}", user.username());
+		if let Some(p) = generated
 		{
-			let role = PgRole::create(&mut tx, "admin".into(), None).await?;
-			PgUser::create(&mut tx, None, "password".into(), role, "admin".into()).await?;
+			println!("Created the first user, {}, with the password {p}. Change it to log in.", user.username());
 		}
 
-		tx.commit().await
+		Ok(())
 	}
 }
@@ -0,0 +1,171 @@
+//! Contains the `GET` method of the endpoints that retrieve entities, which turns the request into the equivalent
+//! `POST` and allows the response to be cached.
+
+use axum::{
+	body::{self, Body, Full, HttpBody},
+	extract::{Query, State},
+	headers::{ETag, HeaderMapExt, IfNoneMatch},
+	http::{header, HeaderValue, Method, Request, StatusCode},
+	response::IntoResponse,
+	routing::MethodRouter,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tower::ServiceExt;
+use winvoice_schema::Id;
+
+use super::{response::Response, ServerState};
+use crate::{
+	api::{request, response::Post, routes, Code, Status},
+	schema::{SavedQueryAdapter, User},
+};
+
+/// The endpoints which retrieve entities with a `POST` request.
+pub const RETRIEVABLE: [&str; 10] = [
+	routes::CONTACT,
+	routes::DEPARTMENT,
+	routes::EMPLOYEE,
+	routes::EXPENSE,
+	routes::JOB,
+	routes::LOCATION,
+	routes::ORGANIZATION,
+	routes::ROLE,
+	routes::TIMESHEET,
+	routes::USER,
+];
+
+/// The query parameters of a `GET` request to one of the [`RETRIEVABLE`] endpoints.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+struct Params
+{
+	/// The match condition, as JSON.
+	condition: Option<String>,
+
+	/// Whether referenced entities should be nested, or side-loaded.
+	#[serde(default)]
+	expand: request::Expand,
+
+	/// The [`Id`] of a [`SavedQuery`](crate::schema::SavedQuery).
+	query: Option<Id>,
+}
+
+/// Respond with the `code` and `message`, in the same shape as the response to a `POST`.
+fn error(code: Code, message: String) -> axum::response::Response
+{
+	Response::from(Post::<()>::from(Status::new(code, message))).into_response()
+}
+
+/// Add a `GET` method to the `router` of a [`RETRIEVABLE`] endpoint, which is [`handle`]d by its `POST` method.
+pub fn get<Q>(state: &ServerState<Q::Db>, router: MethodRouter<ServerState<Q::Db>>) -> MethodRouter<ServerState<Q::Db>>
+where
+	Q: SavedQueryAdapter,
+{
+	let post = router.clone().with_state(state.clone());
+	router.get(move |State(state): State<ServerState<Q::Db>>, request: Request<Body>| {
+		handle::<Q>(state, post.clone(), request)
+	})
+}
+
+/// Run the `request` against the `post` method, with the match condition from its query parameters. Then, tag the
+/// response with an [`ETag`], and respond with [`StatusCode::NOT_MODIFIED`] if the [`IfNoneMatch`] header of the
+/// `request` matches it.
+///
+/// The [`ETag`] is a hash of the response, so it changes whenever anything in it does (including the entities which are
+/// nested inside of others), no matter which replica of the server changed it.
+async fn handle<Q>(
+	state: ServerState<Q::Db>,
+	post: MethodRouter<()>,
+	mut request: Request<Body>,
+) -> axum::response::Response
+where
+	Q: SavedQueryAdapter,
+{
+	let Some(user) = request.extensions().get::<User>().map(User::id)
+	else
+	{
+		return error(Code::Unauthorized, "The request was not made by a user".into());
+	};
+
+	let route = request.uri().path().to_owned();
+	let params = match Query::<Params>::try_from_uri(request.uri())
+	{
+		Ok(Query(p)) => p,
+		Err(e) => return error(Code::EncodingError, format!("The query parameters could not be read: {e}")),
+	};
+
+	let condition = match (params.condition, params.query)
+	{
+		(Some(c), None) => match serde_json::from_str::<Value>(&c)
+		{
+			Ok(v) => v,
+			Err(e) => return error(Code::EncodingError, format!("The condition is not valid JSON: {e}")),
+		},
+
+		(None, Some(id)) => match Q::retrieve(state.pool(), id, user).await
+		{
+			Ok(Some(q)) if q.route() == route => q.condition().clone(),
+			Ok(_) => return error(Code::InvalidReference, format!("There is no saved query {id} for {route}")),
+			Err(e) => return Response::from(Post::<()>::from(Status::from(&e))).into_response(),
+		},
+
+		_ =>
+		{
+			let message = "Exactly one of the `condition` and `query` parameters must be given".to_owned();
+			return error(Code::EncodingError, message);
+		},
+	};
+
+	let body = match serde_json::to_vec(&request::Post::new(condition).with_expand(params.expand))
+	{
+		Ok(b) => b,
+		Err(e) => return error(Code::EncodingError, e.to_string()),
+	};
+
+	let if_none_match = request.headers().typed_get::<IfNoneMatch>();
+	*request.method_mut() = Method::POST;
+	*request.body_mut() = Body::from(body);
+	request.headers_mut().remove(header::CONTENT_LENGTH);
+	request.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+	let response = match post.oneshot(request).await
+	{
+		Ok(r) => r,
+		Err(e) => match e {},
+	};
+
+	if !response.status().is_success()
+	{
+		return response;
+	}
+
+	let (mut parts, mut body) = response.into_parts();
+	let mut bytes = Vec::new();
+	while let Some(chunk) = body.data().await
+	{
+		match chunk
+		{
+			Ok(c) => bytes.extend_from_slice(&c),
+			Err(e) => return error(Code::Other, format!("The response could not be read: {e}")),
+		}
+	}
+
+	let Ok(etag) = format!("\"{}\"", hex::encode(Sha256::digest(&bytes))).parse::<ETag>()
+	else
+	{
+		return axum::response::Response::from_parts(parts, body::boxed(Full::from(bytes)));
+	};
+
+	parts.headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+	parts.headers.typed_insert(etag.clone());
+
+	if if_none_match.map_or(false, |h| !h.precondition_passes(&etag))
+	{
+		parts.status = StatusCode::NOT_MODIFIED;
+		parts.headers.remove(header::CONTENT_LENGTH);
+		parts.headers.remove(header::CONTENT_TYPE);
+		return axum::response::Response::from_parts(parts, body::boxed(Full::default()));
+	}
+
+	axum::response::Response::from_parts(parts, body::boxed(Full::from(bytes)))
+}
@@ -0,0 +1,110 @@
+//! Contains extractors which stand in for those of [`axum`], but reject requests with a [`Status`] rather than plain
+//! text, so that clients can always read the reason a request was rejected.
+
+use axum::{
+	async_trait,
+	extract::{rejection::JsonRejection, FromRequest, FromRequestParts},
+	headers::{Header, HeaderMapExt},
+	http::{request::Parts, Request},
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::response::Response;
+use crate::api::{Code, Status, Violation};
+
+/// Extracts the body of a request as JSON, like [`axum::Json`].
+///
+/// When the body cannot be deserialized into `T`, the request is rejected with a [`Code::ValidationError`], with the
+/// path to the value which could not be deserialized as a [`Violation`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<B, S, T> FromRequest<S, B> for Json<T>
+where
+	axum::Json<Value>: FromRequest<S, B, Rejection = JsonRejection>,
+	B: Send + 'static,
+	S: Send + Sync,
+	T: DeserializeOwned,
+{
+	type Rejection = Response<Status>;
+
+	async fn from_request(request: Request<B>, state: &S) -> Result<Self, Self::Rejection>
+	{
+		let axum::Json(value) = axum::Json::<Value>::from_request(request, state).await.map_err(|e| {
+			let code = match &e
+			{
+				JsonRejection::MissingJsonContentType(_) => Code::InvalidContentType,
+				JsonRejection::BytesRejection(_) |
+				JsonRejection::JsonDataError(_) |
+				JsonRejection::JsonSyntaxError(_) => Code::EncodingError,
+				_ => Code::Other,
+			};
+
+			Response::from(Status::new(code, e.body_text()))
+		})?;
+
+		serde_path_to_error::deserialize(value).map(Self).map_err(|e| {
+			// the path of the body itself is ".", but a `Violation` refers to it with an empty path
+			let path = match e.path().to_string()
+			{
+				p if p == "." => String::new(),
+				p => p,
+			};
+
+			let message = e.inner().to_string();
+			let violation = Violation::new(path, message.clone());
+			Response::from(Status::new(Code::ValidationError, message).with_violations(vec![violation]))
+		})
+	}
+}
+
+/// Extracts the query parameters of a request, like [`axum::extract::Query`].
+///
+/// When they cannot be deserialized into `T`, the request is rejected with a [`Code::EncodingError`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Query<T>
+where
+	S: Send + Sync,
+	T: DeserializeOwned,
+{
+	type Rejection = Response<Status>;
+
+	async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection>
+	{
+		axum::extract::Query::try_from_uri(&parts.uri).map(|axum::extract::Query(t)| Self(t)).map_err(|e| {
+			Response::from(Status::new(Code::EncodingError, format!("The query parameters could not be read: {e}")))
+		})
+	}
+}
+
+/// Extracts a typed [`Header`] of a request, like [`axum::TypedHeader`].
+///
+/// When the header is missing or cannot be decoded, the request is rejected with a [`Code::InvalidHeader`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TypedHeader<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for TypedHeader<T>
+where
+	S: Send + Sync,
+	T: Header,
+{
+	type Rejection = Response<Status>;
+
+	async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection>
+	{
+		let message = match parts.headers.typed_try_get::<T>()
+		{
+			Ok(Some(t)) => return Ok(Self(t)),
+			Ok(None) => format!("The `{}` header is missing", T::name()),
+			Err(e) => format!("The `{}` header could not be read: {e}", T::name()),
+		};
+
+		Err(Response::from(Status::new(Code::InvalidHeader, message)))
+	}
+}
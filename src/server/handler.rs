@@ -1,24 +1,49 @@
+mod attachment;
+mod authorize;
+mod batch;
+mod budget;
+mod expand;
+mod hierarchy;
+pub mod import;
+mod invitation;
+mod invoiced;
+mod ldap;
+mod lifecycle;
+mod lockout;
+mod oidc;
+mod overlap;
 mod reason;
+mod reset;
+mod review;
+mod role;
+mod scope;
+mod search;
+mod validate;
 
 use core::{marker::PhantomData, time::Duration};
 use std::collections::{BTreeSet, HashMap, HashSet};
 
 use argon2::{password_hash::Error as HashError, Argon2, PasswordHash, PasswordVerifier};
 use axum::{
-	extract::State,
+	extract::{RawBody, State},
 	headers::{authorization::Basic, Authorization},
-	http::StatusCode,
+	http::{HeaderMap, StatusCode},
+	response::sse::{self, KeepAlive, Sse},
 	routing::{self, MethodRouter},
 	Extension,
-	Json,
-	TypedHeader,
 };
-use futures::{stream, TryFutureExt, TryStreamExt};
+use axum_login::axum_sessions::SessionHandle;
+use futures::{stream, TryStreamExt};
 use humantime_serde::Serde;
+use lifecycle::Transition;
 use money2::{Exchange, HistoricalExchangeRates};
+use overlap::Interval;
 use reason::Reason;
-use sqlx::{Database, Executor, Pool};
+use serde::Deserialize;
+use sqlx::{Database, Executor, Pool, Transaction};
+use tokio::sync::broadcast::error::RecvError;
 use tracing::Instrument;
+use validate::validate;
 use winvoice_adapter::{
 	schema::{
 		ContactAdapter,
@@ -53,7 +78,9 @@ use winvoice_schema::{
 };
 
 use super::{
+	attachments::Attachments,
 	auth::{AuthContext, DbUserStore, UserStore},
+	extract::{Json, Query, TypedHeader},
 	response::{
 		DeleteResponse,
 		ExportResponse,
@@ -64,39 +91,82 @@ use super::{
 		ResponseResult,
 		WhoAmIResponse,
 	},
+	Ldap,
+	Oidc,
+	PasswordResets,
 	ServerState,
 };
 use crate::{
 	api::{
 		request,
-		response::{Post, Put},
+		response::{Delete, Login, Post, Put},
 		Code,
+		Entity,
+		EventKind,
 		Status,
 	},
 	bool_ext::BoolExt,
 	permissions::{Action, Object},
-	r#match::MatchUser,
-	schema::{Adapter, Role, RoleAdapter, User, UserAdapter},
+	r#match::{MatchRole, MatchUser},
+	schema::{
+		Adapter,
+		Budget,
+		Identity,
+		IdentityAdapter,
+		InvitationAdapter,
+		Rate,
+		RateAdapter,
+		Review,
+		ReviewAdapter,
+		ReviewStatus,
+		Role,
+		RoleAdapter,
+		SavedQueryAdapter,
+		TimerAdapter,
+		User,
+		UserAdapter,
+		WebhookAdapter,
+	},
 	twin_result::TwinResult,
+	utils,
 	ResultExt,
 };
-/// Map `result` of creating some enti`T`y into a [`ResponseResult`].
-fn create<T>(on_success: Code, result: sqlx::Result<T>) -> ResponseResult<Put<T>>
+/// Map `result` of creating some enti`T`y with the `tx` into a [`ResponseResult`], [`publish`](ServerState::publish)
+/// it, and commit the `tx`.
+async fn create<Db, T>(
+	state: &ServerState<Db>,
+	on_success: impl Into<Status>,
+	mut tx: Transaction<'_, Db>,
+	result: sqlx::Result<T>,
+) -> ResponseResult<Put<T>>
+where
+	Db: Database,
+	T: Clone + Into<Entity>,
 {
-	result.map_all(
-		|t| Response::from(Put::new(t.into(), on_success.into())),
-		|e| Response::from(Put::from(Status::from(&e))),
-	)
+	let error = |e: sqlx::Error| Response::from(Put::from(Status::from(&e)));
+	let t = result.map_err(error)?;
+	state.publish(&mut tx, EventKind::Create, [t.clone()]).await.map_err(error)?;
+	tx.commit().await.map_err(error)?;
+	Ok(Response::from(Put::new(t.into(), on_success.into())))
 }
 
-/// [Retrieve](Retrievable::retrieve) using `R`, and map the result into a [`ResponseResult`].
-async fn delete<D>(pool: &Pool<D::Db>, entities: Vec<D::Entity>, on_success: Code) -> TwinResult<DeleteResponse>
+/// [Delete](Deletable::delete) using `D`, [`publish`](ServerState::publish) the deleted `entities`, and map the
+/// result into a [`ResponseResult`].
+async fn delete<D>(
+	state: &ServerState<D::Db>,
+	entities: Vec<D::Entity>,
+	on_success: Code,
+) -> TwinResult<DeleteResponse>
 where
 	D: Deletable,
-	D::Entity: Sync,
+	D::Entity: Into<Entity> + Sync,
 	for<'con> &'con mut <D::Db as Database>::Connection: Executor<'con, Database = D::Db>,
 {
-	D::delete(pool, entities.iter()).await.map_all(|_| DeleteResponse::from(on_success), DeleteResponse::from)
+	let mut tx = state.pool().begin().await.map_err(DeleteResponse::from)?;
+	D::delete(&mut *tx, entities.iter()).await.map_err(DeleteResponse::from)?;
+	state.publish(&mut tx, EventKind::Delete, entities).await.map_err(DeleteResponse::from)?;
+	tx.commit().await.map_err(DeleteResponse::from)?;
+	Ok(DeleteResponse::from(on_success))
 }
 
 /// [Retrieve](Retrievable::retrieve) using `R`, and map the result into a [`ResponseResult`].
@@ -114,15 +184,47 @@ where
 	)
 }
 
-/// [Retrieve](Retrievable::retrieve) using `R`, and map the result into a [`ResponseResult`].
-async fn update<U>(pool: &Pool<U::Db>, entities: Vec<U::Entity>, on_success: Code) -> TwinResult<PatchResponse>
+/// [Update](Updatable::update) using `U`, [`publish`](ServerState::publish) the updated `entities`, and map the
+/// result into a [`ResponseResult`].
+async fn update<U>(
+	state: &ServerState<U::Db>,
+	entities: Vec<U::Entity>,
+	on_success: impl Into<Status>,
+) -> TwinResult<PatchResponse>
 where
 	U: Updatable,
-	U::Entity: Sync,
+	U::Entity: Into<Entity> + Sync,
 {
-	let mut tx = pool.begin().await.map_err(PatchResponse::from)?;
+	let status: Status = on_success.into();
+	let mut tx = state.pool().begin().await.map_err(PatchResponse::from)?;
 	U::update(&mut tx, entities.iter()).await.map_err(PatchResponse::from)?;
-	tx.commit().await.map_all(|_| PatchResponse::from(on_success), PatchResponse::from)
+	state.publish(&mut tx, EventKind::Update, entities).await.map_err(PatchResponse::from)?;
+	tx.commit().await.map_err(PatchResponse::from)?;
+	Ok(PatchResponse::from(status))
+}
+
+/// [Update](Updatable::update) using `U`, [`publish`](ServerState::publish) the updated `entities` as the `kind` of
+/// [`Event`](crate::api::Event), and respond with them.
+async fn update_returning<U>(
+	state: &ServerState<U::Db>,
+	kind: EventKind,
+	entities: Vec<U::Entity>,
+	on_success: impl Into<Status>,
+) -> ResponseResult<Post<U::Entity>>
+where
+	U: Updatable,
+	U::Entity: Clone + Into<Entity> + Sync,
+{
+	let status: Status = on_success.into();
+	let mut tx = state.pool().begin().await.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+	U::update(&mut tx, entities.iter()).await.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+	state
+		.publish(&mut tx, kind, entities.iter().cloned())
+		.await
+		.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+	tx.commit().await.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+	Ok(Response::from(Post::new(entities, status)))
 }
 
 /// Return a [`ResponseResult`] for when a [`User`] tries to POST something, but they *effectively*
@@ -138,41 +240,220 @@ where
 	))))
 }
 
+/// The query parameters of a request which redeems a token, such as the link of an
+/// [`Invitation`](crate::schema::Invitation).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct Token
+{
+	/// The token which is being redeemed.
+	token: String,
+}
+
+/// Retrieve the [`User`] whose `credentials` were given, or respond that they are invalid.
+async fn authenticate<A>(state: &ServerState<A::Db>, credentials: &Basic) -> Result<User, LoginResponse>
+where
+	A: Adapter,
+{
+	let user = match A::User::retrieve(state.pool(), MatchUser {
+		username: credentials.username().to_owned().into(),
+		..Default::default()
+	})
+	.await
+	.map(|mut v| v.pop())
+	{
+		Ok(Some(u)) => u,
+		Ok(None) => return Err(LoginResponse::invalid_credentials(None)),
+		Err(e) => return Err(LoginResponse::from(e)),
+	};
+
+	PasswordHash::new(user.password()).map_or_else(
+		|e| {
+			tracing::error!("Failed to decode user {}'s password hash stored in database", user.username());
+			Err(LoginResponse::new(
+				StatusCode::INTERNAL_SERVER_ERROR,
+				Status::new(Code::EncodingError, e.to_string()),
+				None,
+			))
+		},
+		|hash| {
+			Argon2::default().verify_password(credentials.password().as_bytes(), &hash).map_err(|e| {
+				tracing::info!("Invalid login attempt for user {}", user.username());
+				LoginResponse::from(e)
+			})
+		},
+	)?;
+
+	Ok(user)
+}
+
+/// Log in the `user`, and respond with them.
+async fn start_session<Db>(auth: &mut AuthContext<Db>, user: User) -> Result<LoginResponse, LoginResponse>
+where
+	Db: Database,
+	DbUserStore<Db>: UserStore,
+{
+	match auth.login(&user).await
+	{
+		Ok(_) => Ok(LoginResponse::from(user)),
+		Err(e) =>
+		{
+			const CODE: Code = Code::LoginError;
+			tracing::error!("Failed to to log in user {}: {e}", user.username());
+			Err(LoginResponse::from(Status::new(CODE, e.to_string())))
+		},
+	}
+}
+
+/// Retrieve the [`User`] who has the identity which the `issuer` (e.g. an identity provider) vouched for as the
+/// `subject`, rather than their password.
+///
+/// The user is given the [`Role`] named by the first of their `groups` which is the name of a role, if that is not
+/// already their role and it would not [lock out](lockout::guard) the users who manage users or roles. If nobody has
+/// the identity, a user with the `username` is created (with a password that nobody knows) and given it when
+/// `provision`ing is allowed and one of their groups names a role.
+///
+/// An identity is never given to an existing user here, so that the `issuer` cannot log in as a user which it does not
+/// manage (e.g. the first user). Users [link](oidc::link) their identities themselves.
+async fn external_user<A>(
+	state: &ServerState<A::Db>,
+	issuer: &str,
+	subject: &str,
+	username: &str,
+	groups: &[String],
+	provision: bool,
+) -> Result<User, LoginResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let role = {
+		let roles = A::Role::retrieve(state.pool(), MatchRole::default()).await?;
+		groups.iter().find_map(|g| roles.iter().find(|r| r.name() == g)).cloned()
+	};
+
+	let existing = match A::Identity::find(state.pool(), issuer, subject).await?
+	{
+		Some(i) => A::User::retrieve(state.pool(), MatchUser { id: i.user_id().into(), ..Default::default() })
+			.await?
+			.pop(),
+		None => None,
+	};
+
+	let taken = match existing
+	{
+		Some(_) => false,
+		None =>
+		{
+			let condition = MatchUser { username: username.to_owned().into(), ..Default::default() };
+			!A::User::retrieve(state.pool(), condition).await?.is_empty()
+		},
+	};
+
+	if taken
+	{
+		tracing::info!("User {username} attempted to log in with an identity at {issuer} which is not theirs");
+		let message = format!("User {username} has not linked their identity at {issuer}");
+		return Err(LoginResponse::invalid_credentials(Some(message)));
+	}
+
+	match (existing, role)
+	{
+		(Some(u), Some(r)) if u.role().id() != r.id() =>
+		{
+			let user = User { role: r, ..u };
+			lockout::guard::<A, Login>(state, &[], &[user.clone()]).await?;
+
+			let mut tx = state.pool().begin().await?;
+			A::User::update(&mut tx, [&user].into_iter()).await?;
+			state.publish(&mut tx, EventKind::Update, [user.clone()]).await?;
+			tx.commit().await?;
+
+			tracing::info!("User {} was given the role {} by their groups", user.username(), user.role().name());
+			Ok(user)
+		},
+		(Some(u), _) => Ok(u),
+		(None, Some(r)) if provision =>
+		{
+			let mut tx = state.pool().begin().await?;
+			let user = A::User::create(&mut *tx, None, utils::password(), r, username.to_owned()).await?;
+			A::Identity::link(&mut *tx, &Identity::new(issuer.to_owned(), subject.to_owned(), user.id())).await?;
+			state.publish(&mut tx, EventKind::Create, [user.clone()]).await?;
+			tx.commit().await?;
+
+			tracing::info!("User {username} was created by logging in through {issuer}");
+			Ok(user)
+		},
+		(None, _) =>
+		{
+			let message = format!("There is no user {username}, and one cannot be created for them");
+			Err(LoginResponse::invalid_credentials(Some(message)))
+		},
+	}
+}
+
 /// Create routes which are able to be implemented generically.
+///
+/// The `DELETE` method may be replaced by passing its `MethodRouter` first, and then the `PATCH` method by passing its
+/// handler after that.
 macro_rules! route {
 	($Entity:ident, $Args:ty, $($param:ident$( = $map:expr)?),+) => {
-		routing::delete(
+		route!(
+			routing::delete(
 				|Extension(user): Extension<User>,
 				 State(state): State<ServerState<A::Db>>,
 				 Json(request): Json<request::Delete<<A::$Entity as Deletable>::Entity>>| async move {
 					state.enforce_permission(&user, Object::$Entity, Action::Delete).await?;
-					delete::<A::$Entity>(state.pool(), request.into_entities(), Code::Success).await
+					delete::<A::$Entity>(&state, request.into_entities(), Code::Success).await
 				},
-			)
+			),
+			$Entity,
+			$Args,
+			$($param$( = $map)?),+
+		)
+	};
+
+	($delete:expr, $Entity:ident, $Args:ty, $($param:ident$( = $map:expr)?),+) => {
+		route!(
+			$delete,
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Patch<<A::$Entity as Deletable>::Entity>>| async move {
+				validate::<Delete>(&request)?;
+				state.enforce_permission(&user, Object::$Entity, Action::Update).await?;
+				update::<A::$Entity>(&state, request.into_entities(), Code::Success).await
+			},
+			$Entity,
+			$Args,
+			$($param$( = $map)?),+
+		)
+	};
+
+	($delete:expr, $patch:expr, $Entity:ident, $Args:ty, $($param:ident$( = $map:expr)?),+) => {
+		$delete
 			.post(
 				|Extension(user): Extension<User>,
 				 State(state): State<ServerState<A::Db>>,
 				 Json(request): Json<request::Post<<A::$Entity as Retrievable>::Match>>| async move {
 					state.enforce_permission(&user, Object::$Entity, Action::Retrieve).await?;
-					retrieve::<A::$Entity>(state.pool(), request.into_condition(), Code::Success).await
-				},
-			)
-			.patch(
-				|Extension(user): Extension<User>,
-				 State(state): State<ServerState<A::Db>>,
-				 Json(request): Json<request::Patch<<A::$Entity as Deletable>::Entity>>| async move {
-					state.enforce_permission(&user, Object::$Entity, Action::Update).await?;
-					update::<A::$Entity>(state.pool(), request.into_entities(), Code::Success).await
+					let expand = request.expand();
+					expand::respond(
+						expand,
+						retrieve::<A::$Entity>(state.pool(), request.into_condition(), Code::Success).await,
+					)
 				},
 			)
+			.patch($patch)
 			.put(
 				|Extension(user): Extension<User>,
 				 State(state): State<ServerState<A::Db>>,
 				 Json(request): Json<request::Put<$Args>>| async move {
+					validate(&request)?;
 					state.enforce_permission(&user, Object::$Entity, Action::Create).await?;
 					let ( $($param),+ ) = request.into_args();
 					$($(let $param = $map;)*)+
-					create(Code::Success, A::$Entity::create(state.pool(), $($param),+).await)
+					let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+					let result = A::$Entity::create(&mut *tx, $($param),+).await;
+					create(&state, Code::Success, tx, result).await
 				},
 			)
 	};
@@ -191,6 +472,20 @@ where
 	DbUserStore<A::Db>: UserStore,
 	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
 {
+	/// The handler for the [`routes::BATCH`](crate::api::routes::BATCH).
+	pub fn batch(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
+			 Json(request): Json<request::Batch>| async move {
+				validate::<Post<Entity>>(&request)?;
+				batch::execute::<A>(&state, &user, &headers, request.into_operations(), false).await
+			},
+		)
+	}
+
 	/// The handler for the [`routes::CONTACT`](crate::api::routes::CONTACT).
 	pub fn contact(&self) -> MethodRouter<ServerState<A::Db>>
 	{
@@ -219,7 +514,7 @@ where
 					p => p.unreachable(),
 				};
 
-				delete::<A::Department>(state.pool(), entities, code).await
+				delete::<A::Department>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -227,6 +522,7 @@ where
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Post<MatchDepartment>>| async move {
 				const ACTION: Action = Action::Retrieve;
+				let expand = request.expand();
 				let mut condition = request.into_condition();
 				let code = match state.department_permissions(&user, ACTION).await?
 				{
@@ -239,17 +535,21 @@ where
 						Code::SuccessForPermissions
 					},
 
-					p @ Object::AssignedDepartment => return no_effective_perms(ACTION, p, Reason::NoDepartment),
+					p @ Object::AssignedDepartment =>
+					{
+						return no_effective_perms(ACTION, p, Reason::NoDepartment).and_then(Err);
+					},
 					p => p.unreachable(),
 				};
 
-				retrieve::<A::Department>(state.pool(), condition, code).await
+				expand::respond(expand, retrieve::<A::Department>(state.pool(), condition, code).await)
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Patch<Department>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let mut entities = request.into_entities();
 				let code = match state.department_permissions(&user, ACTION).await?
@@ -272,27 +572,19 @@ where
 					p => p.unreachable(),
 				};
 
-				update::<A::Department>(state.pool(), entities, code).await
+				update::<A::Department>(&state, entities, code).await
 			},
 		)
 		.put(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Put<String>>| async move {
-				const ACTION: Action = Action::Create;
+				validate(&request)?;
 				let name = request.into_args();
-				let code = match state.department_permissions(&user, ACTION).await?
-				{
-					Object::Department => Code::Success,
-
-					p @ Object::AssignedDepartment =>
-					{
-						return no_effective_perms(ACTION, p, Reason::ResourceExists);
-					},
-					p => p.unreachable(),
-				};
-
-				create(code, A::Department::create(state.pool(), name).await)
+				let code = authorize::department(&state, &user).await?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result = A::Department::create(&mut *tx, name).await;
+				create(&state, code, tx, result).await
 			},
 		)
 	}
@@ -333,7 +625,7 @@ where
 					p => p.unreachable(),
 				};
 
-				delete::<A::Employee>(state.pool(), entities, code).await
+				delete::<A::Employee>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -341,6 +633,7 @@ where
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Post<MatchEmployee>>| async move {
 				const ACTION: Action = Action::Retrieve;
+				let expand = request.expand();
 				let mut condition = request.into_condition();
 				let code = match state.employee_permissions(&user, ACTION).await?
 				{
@@ -360,22 +653,26 @@ where
 						Code::SuccessForPermissions
 					},
 
-					p @ Object::EmployeeInDepartment => return no_effective_perms(ACTION, p, Reason::NoDepartment),
+					p @ Object::EmployeeInDepartment =>
+					{
+						return no_effective_perms(ACTION, p, Reason::NoDepartment).and_then(Err);
+					},
 					p @ Object::EmployeeSelf =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoEmployee);
+						return no_effective_perms(ACTION, p, Reason::NoEmployee).and_then(Err);
 					},
 
 					p => p.unreachable(),
 				};
 
-				retrieve::<A::Employee>(state.pool(), condition, code).await
+				expand::respond(expand, retrieve::<A::Employee>(state.pool(), condition, code).await)
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Patch<Employee>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let mut entities = request.into_entities();
 				let code = match state.employee_permissions(&user, ACTION).await?
@@ -411,31 +708,51 @@ where
 					p => p.unreachable(),
 				};
 
-				update::<A::Employee>(state.pool(), entities, code).await
+				update::<A::Employee>(&state, entities, code).await
 			},
 		)
 		.put(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Put<(Department, String, String)>>| async move {
-				const ACTION: Action = Action::Create;
+				validate(&request)?;
 				let (department, name, title) = request.into_args();
-				let code = match state.employee_permissions(&user, ACTION).await?
+				let code = authorize::employee(&state, &user, &department).await?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result = A::Employee::create(&mut *tx, department, name, title).await;
+				create(&state, code, tx, result).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::EVENTS`](crate::api::routes::EVENTS).
+	pub fn events(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::get(|Extension(user): Extension<User>, State(state): State<ServerState<A::Db>>| async move {
+			let receiver = state.subscribe();
+			let stream = stream::unfold((state, user, receiver), |(state, user, mut receiver)| async move {
+				loop
 				{
-					Object::Employee => Code::Success,
-					Object::EmployeeInDepartment if user.department().map_or(false, |d| d.id == department.id) =>
+					let item = match receiver.recv().await
 					{
-						Code::Success
-					},
+						Ok(event) => match scope::event::<A>(&state, &user, event).await
+						{
+							Some(e) => sse::Event::default().json_data(e),
+							None => continue,
+						},
+						Err(RecvError::Lagged(skipped)) =>
+						{
+							Ok(sse::Event::default().event("lagged").data(skipped.to_string()))
+						},
+						Err(RecvError::Closed) => return None,
+					};
 
-					p @ Object::EmployeeInDepartment => return no_effective_perms(ACTION, p, Reason::NoDepartment),
-					p @ Object::EmployeeSelf => return no_effective_perms(ACTION, p, Reason::ResourceExists),
-					p => p.unreachable(),
-				};
+					return Some((item, (state, user, receiver)));
+				}
+			});
 
-				create(code, A::Employee::create(state.pool(), department, name, title).await)
-			},
-		)
+			Sse::new(stream).keep_alive(KeepAlive::default())
+		})
 	}
 
 	/// The handler for the [`routes::EXPENSE`](crate::api::routes::EXPENSE).
@@ -482,7 +799,8 @@ where
 						true => Reason::NoEmployee,
 						false => Reason::NoDepartment,
 					})
-					.map_all(Into::into, Into::into);
+					.and_then(Err)
+					.map_err(Into::into);
 				}
 			};
 		}
@@ -513,7 +831,20 @@ where
 					},
 				};
 
-				delete::<A::Expenses>(state.pool(), entities, code).await
+				let locked = invoiced::expenses::<A>(state.pool(), &entities).await.map_err(DeleteResponse::from)?;
+				let code = match invoiced::retain(&state, &user, ACTION, &mut entities, &locked, |x| x.id).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				let code = match review::retain_expenses::<A, _>(&state, &user, &mut entities).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				delete::<A::Expenses>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -524,6 +855,7 @@ where
 				let permission = state.expense_permissions(&user, ACTION).await?;
 				enforce_effective_permissions!(user, ACTION, permission);
 
+				let expand = request.expand();
 				let condition = request.into_condition();
 
 				let mut vec = A::Expenses::retrieve(state.pool(), condition)
@@ -546,13 +878,14 @@ where
 					},
 				};
 
-				Ok::<_, Response<_>>(Response::from(Post::new(vec, code.into())))
+				expand::respond(expand, Ok(Response::from(Post::new(vec, code.into()))))
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Patch<Expense>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let permission = state.expense_permissions(&user, ACTION).await?;
 				enforce_effective_permissions!(user, ACTION, permission);
@@ -575,7 +908,20 @@ where
 					},
 				};
 
-				update::<A::Expenses>(state.pool(), entities, code).await
+				let locked = invoiced::expenses::<A>(state.pool(), &entities).await.map_err(PatchResponse::from)?;
+				let code = match invoiced::retain(&state, &user, ACTION, &mut entities, &locked, |x| x.id).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				let code = match review::retain_expenses::<A, _>(&state, &user, &mut entities).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				update::<A::Expenses>(&state, entities, code).await
 			},
 		)
 		.put(
@@ -583,70 +929,71 @@ where
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Put<(Vec<(String, Money, String)>, Id)>>| async move {
+				validate(&request)?;
 				#[warn(clippy::type_complexity)]
 				const ACTION: Action = Action::Create;
-				let permission = state.expense_permissions(&user, ACTION).await?;
-
-				// A user has no effective permissions in two scenarios:
-				//
-				// 1. They have the `CreatedExpenses` permission
-				// 2. They have the ExpensesInDepartment` permission but have no department
-				if permission != Object::Expenses
-				{
-					let created = permission == Object::CreatedExpenses;
-					if created || user.employee().is_none()
-					{
-						return no_effective_perms(
-							ACTION,
-							permission,
-							created.then_some_or(Reason::ResourceExists, Reason::NoDepartment),
-						)
-						.map_all(Into::into, Into::into);
-					}
-				};
-
 				let (expenses, timesheet_id) = request.into_args();
 
-				let code = match permission
+				let pool = state.pool();
+				let timesheet = match A::Timesheet::retrieve(pool, timesheet_id.into())
+					.await
+					.map_err(|e| Response::from(Put::from(Status::from(&e))))?
+					.pop()
 				{
-					Object::Expenses => Code::Success,
-
-					// The user can only post expenses iff they are in the same department, or were created
-					// by that user.
-					p =>
+					Some(t) => t,
+					None =>
 					{
-						let matching: HashSet<_> = A::Timesheet::retrieve(state.pool(), match permission
-						{
-							Object::ExpensesInDepartment =>
-							{
-								MatchJob::from(MatchDepartment::from(user.department().unwrap().id)).into()
-							},
-							_ => permission.unreachable(),
-						})
-						.await
-						.map_all(
-							|vec| vec.into_iter().map(|t| t.id).collect(),
-							|e| Response::from(Put::from(Status::from(&e))),
-						)?;
-
-						if !matching.contains(&timesheet_id)
-						{
-							return no_effective_perms(ACTION, p, Reason::NoResourceExists);
-						}
-
-						Code::Success
+						state.expense_permissions(&user, ACTION).await?;
+						let message = format!("timesheet {timesheet_id} does not exist");
+						return Err(Response::from(Put::from(Status::new(Code::InvalidReference, message))));
 					},
 				};
 
-				let pool = state.pool();
-				create(
-					code,
-					<A::Timesheet>::retrieve(pool, timesheet_id.into())
-						.and_then(|t| A::Expenses::create(pool, expenses, (timesheet_id, t[0].time_begin)))
-						.await,
-				)
+				let code = authorize::expenses::<A, _>(&state, &user, &timesheet).await?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result = A::Expenses::create(&mut *tx, expenses, (timesheet.id, timesheet.time_begin)).await;
+				create(&state, code, tx, result).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::EXPENSE_ATTACHMENT`](crate::api::routes::EXPENSE_ATTACHMENT).
+	pub fn expense_attachment(&self, attachments: Attachments) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::delete(
+			|Extension(attachments): Extension<Attachments>,
+			 Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Delete<Id>>| async move {
+				attachment::delete::<A>(&state, &user, &attachments, request.entities()).await
+			},
+		)
+		.get(
+			|Extension(attachments): Extension<Attachments>,
+			 Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Query(params): Query<attachment::Download>| async move {
+				attachment::download::<A>(&state, &user, &attachments, params).await
+			},
+		)
+		.post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<Id>>| async move {
+				attachment::list::<A>(&state, &user, *request.condition()).await
+			},
+		)
+		.put(
+			|Extension(attachments): Extension<Attachments>,
+			 Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
+			 Query(params): Query<attachment::Upload>,
+			 body: RawBody| async move {
+				attachment::upload::<A>(&state, &user, &attachments, &headers, params, body).await
 			},
 		)
+		.layer(Extension(attachments))
 	}
 
 	/// The handler for the [`routes::EXPORT`](crates::api::routes::EXPORT).
@@ -661,11 +1008,15 @@ where
 				.await
 				.map_all(|vec| vec.into_iter().map(|c| (c.label, c.kind)).collect(), ExportResponse::from)?;
 
-			stream::iter(request.jobs.into_iter().map(Result::<_, ExportResponse>::Ok))
+			let rates = A::Rate::retrieve(state.pool()).await.map_err(ExportResponse::from)?;
+			let approved_only = request.approved_only();
+			let jobs = request.jobs.clone();
+			let exported = stream::iter(request.jobs.into_iter().map(Result::<_, ExportResponse>::Ok))
 				.and_then(|mut job| {
 					let contacts = &contacts;
 					let pool = state.pool();
 					let history = &history;
+					let rates = &rates;
 					async move {
 						let currency = requested_currency.unwrap_or_else(|| job.client.location.currency());
 						let mut timesheets = A::Timesheet::retrieve(pool, MatchTimesheet {
@@ -676,9 +1027,31 @@ where
 						.await
 						.map_err(ExportResponse::from)?;
 
+						if approved_only
+						{
+							let ids = timesheets.iter().map(|t| t.id).collect::<Vec<_>>();
+							let approved = A::Review::retrieve(pool, &ids)
+								.await
+								.map_err(ExportResponse::from)?
+								.into_iter()
+								.filter(|r| r.status() == ReviewStatus::Approved)
+								.map(|r| r.timesheet_id())
+								.collect::<HashSet<_>>();
+
+							timesheets.retain(|t| approved.contains(&t.id));
+						}
+
 						timesheets.sort_by_key(|t| t.time_begin);
+						timesheets.iter_mut().for_each(|t| {
+							if let Some(r) = Rate::applicable(rates, t)
+							{
+								t.job.invoice.hourly_rate = *r.hourly_rate();
+							}
+						});
 
-						if currency != Default::default() || currency != job.invoice.hourly_rate.currency
+						if currency != Default::default() ||
+							currency != job.invoice.hourly_rate.currency ||
+							timesheets.iter().any(|t| t.job.invoice.hourly_rate.currency != currency)
 						{
 							let job_rates =
 								HistoricalExchangeRates::index_ref_from(history, Some(job.date_open.into()));
@@ -694,8 +1067,12 @@ where
 					}
 				})
 				.try_collect::<HashMap<_, _>>()
-				.await
-				.map(ExportResponse::from)
+				.await?;
+
+			// nothing is changed by an export, so its event is published by itself
+			let mut connection = state.pool().acquire().await.map_err(ExportResponse::from)?;
+			state.publish(&mut connection, EventKind::Export, jobs).await.map_err(ExportResponse::from)?;
+			Ok(ExportResponse::from(exported))
 		})
 	}
 
@@ -708,6 +1085,65 @@ where
 		})
 	}
 
+	/// The handler for the [`routes::IMPORT`](crate::api::routes::IMPORT).
+	pub fn import(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
+			 Json(request): Json<request::Import>| async move {
+				import::execute::<A>(&state, &user, &headers, request).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::INVITATION`](crate::api::routes::INVITATION).
+	pub fn invitation(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::delete(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Delete<Id>>| async move {
+				state.enforce_permission(&user, Object::User, Action::Delete).await?;
+				A::Invitation::delete(state.pool(), request.entities()).await.map_err(DeleteResponse::from)?;
+				Ok::<_, DeleteResponse>(DeleteResponse::from(Code::Success))
+			},
+		)
+		.get(|Extension(user): Extension<User>, State(state): State<ServerState<A::Db>>| async move {
+			state.enforce_permission(&user, Object::User, Action::Retrieve).await?;
+			A::Invitation::retrieve(state.pool()).await.map_all(
+				|vec| Response::from(Post::new(vec, Code::Success.into())),
+				|e| Response::from(Post::from(Status::from(&e))),
+			)
+		})
+		.put(
+			#[allow(clippy::type_complexity)]
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Put<(Option<Employee>, Role, Serde<Duration>, String)>>| async move {
+				validate(&request)?;
+				#[warn(clippy::type_complexity)]
+				let (employee, role, ttl, username) = request.into_args();
+				invitation::create::<A>(&state, &user, employee, role, ttl.into_inner(), username).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::INVITATION_ACCEPT`](crate::api::routes::INVITATION_ACCEPT).
+	pub fn invitation_accept(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|mut auth: AuthContext<A::Db>,
+			 State(state): State<ServerState<A::Db>>,
+			 Query(token): Query<Token>,
+			 Json(password): Json<String>| {
+				async move { invitation::redeem::<A>(&state, &mut auth, token, password).await }
+					.instrument(tracing::info_span!("invitation_accept_handler"))
+			},
+		)
+	}
+
 	/// The handler for the [`routes::JOB`](crate::api::routes::JOB).
 	pub fn job(&self) -> MethodRouter<ServerState<A::Db>>
 	{
@@ -737,7 +1173,7 @@ where
 					p => p.unreachable(),
 				};
 
-				delete::<A::Job>(state.pool(), entities, code).await
+				delete::<A::Job>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -745,6 +1181,7 @@ where
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Post<MatchJob>>| async move {
 				const ACTION: Action = Action::Retrieve;
+				let expand = request.expand();
 				let mut condition = request.into_condition();
 
 				let code = match state.job_permissions(&user, ACTION).await?
@@ -760,19 +1197,20 @@ where
 
 					p @ Object::JobInDepartment =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment);
+						return no_effective_perms(ACTION, p, Reason::NoDepartment).and_then(Err);
 					},
 
 					p => p.unreachable(),
 				};
 
-				retrieve::<A::Job>(state.pool(), condition, code).await
+				expand::respond(expand, retrieve::<A::Job>(state.pool(), condition, code).await)
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Patch<Job>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let mut entities = request.into_entities();
 				let code = match state.job_permissions(&user, ACTION).await?
@@ -795,7 +1233,8 @@ where
 					p => p.unreachable(),
 				};
 
-				update::<A::Job>(state.pool(), entities, code).await
+				lifecycle::guard::<A, Delete>(&state, &entities).await?;
+				update::<A::Job>(&state, entities, code).await
 			},
 		)
 		.put(
@@ -814,102 +1253,238 @@ where
 					String,
 				)>,
 			>| async move {
+				validate(&request)?;
 				#[warn(clippy::type_complexity)]
-				const ACTION: Action = Action::Create;
 				let (client, date_close, date_open, departments, serde_increment, invoice, notes, objectives) =
 					request.into_args();
 
 				let increment = serde_increment.into_inner();
-				let code = match state.job_permissions(&user, ACTION).await?
-				{
-					Object::Job => Code::Success,
-					Object::JobInDepartment
-						if user.department().map_or(false, |d| departments.iter().any(|d2| d2.id == d.id)) =>
-					{
-						Code::Success
-					},
-
-					p @ Object::JobInDepartment =>
-					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment).map_all(Into::into, Into::into);
-					},
+				let code = authorize::job(&state, &user, departments.iter()).await?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result = A::Job::create(
+					&mut tx,
+					client,
+					date_close,
+					date_open,
+					departments,
+					increment,
+					invoice,
+					notes,
+					objectives,
+				)
+				.await;
 
-					p => p.unreachable(),
-				};
+				create(&state, code, tx, result).await
+			},
+		)
+	}
 
-				create(
-					code,
-					state
-						.pool()
-						.begin()
-						.and_then(|mut tx| async move {
-							let j = A::Job::create(
-								&mut tx,
-								client,
-								date_close,
-								date_open,
-								departments,
-								increment,
-								invoice,
-								notes,
-								objectives,
-							)
-							.await?;
-
-							tx.commit().await?;
-							Ok(j)
-						})
-						.await,
-				)
+	/// The handler for the [`routes::JOB_BUDGET`](crate::api::routes::JOB_BUDGET).
+	pub fn job_budget(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::delete(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Delete<Id>>| async move {
+				budget::remove::<A>(&state, &user, request.into_entities()).await
+			},
+		)
+		.post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<MatchJob>>| async move {
+				budget::retrieve::<A>(&state, &user, request.into_condition()).await
+			},
+		)
+		.put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Put<Budget>>| async move {
+				validate(&request)?;
+				budget::set::<A>(&state, &user, request.into_args()).await
 			},
 		)
 	}
 
-	/// The handler for the [`routes::LOCATION`](crate::api::routes::LOCATION).
-	pub fn location(&self) -> MethodRouter<ServerState<A::Db>>
+	/// The handler for the [`routes::JOB_CLOSE`](crate::api::routes::JOB_CLOSE).
+	pub fn job_close(&self) -> MethodRouter<ServerState<A::Db>>
 	{
-		route!(Location, (Option<Currency>, String, Option<Location>), currency, name, outer)
+		Self::job_lifecycle(lifecycle::close, EventKind::Close)
 	}
 
-	/// The handler for the [`routes::LOGIN`](crate::api::routes::LOGIN).
-	pub fn login(&self) -> MethodRouter<ServerState<A::Db>>
+	/// The handler for the [`routes::JOB_INVOICE_ISSUE`](crate::api::routes::JOB_INVOICE_ISSUE).
+	pub fn job_invoice_issue(&self) -> MethodRouter<ServerState<A::Db>>
 	{
-		routing::post(
-			|mut auth: AuthContext<A::Db>,
-			 State(state): State<ServerState<A::Db>>,
-			 TypedHeader(credentials): TypedHeader<Authorization<Basic>>| {
-				async move {
-					let user = match A::User::retrieve(state.pool(), MatchUser {
-						username: credentials.username().to_owned().into(),
-						..Default::default()
-					})
-					.await
-					.map(|mut v| v.pop())
-					{
-						Ok(Some(u)) => u,
-						Ok(None) => return Err(LoginResponse::invalid_credentials(None)),
-						Err(e) => return Err(LoginResponse::from(e)),
-					};
+		Self::job_lifecycle(lifecycle::issue, EventKind::Update)
+	}
 
-					PasswordHash::new(user.password()).map_or_else(
-						|e| {
-							tracing::error!(
-								"Failed to decode user {}'s password hash stored in database",
-								user.username()
-							);
-							Err(LoginResponse::new(
-								StatusCode::INTERNAL_SERVER_ERROR,
-								Status::new(Code::EncodingError, e.to_string()),
-								None,
-							))
-						},
-						|hash| {
-							Argon2::default().verify_password(credentials.password().as_bytes(), &hash).map_err(|e| {
-								tracing::info!("Invalid login attempt for user {}", user.username());
-								LoginResponse::from(e)
-							})
-						},
-					)?;
+	/// The handler for the [`routes::JOB_INVOICE_PAY`](crate::api::routes::JOB_INVOICE_PAY).
+	pub fn job_invoice_pay(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		Self::job_lifecycle(lifecycle::pay, EventKind::Update)
+	}
+
+	/// Create a handler which applies a lifecycle `transition` to the requested [`Job`]s, and publishes them as the
+	/// `kind` of [`Event`](crate::api::Event).
+	///
+	/// Either every requested job which the user can see is transitioned, or none are.
+	fn job_lifecycle(transition: Transition, kind: EventKind) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::patch(
+			move |Extension(user): Extension<User>,
+			      State(state): State<ServerState<A::Db>>,
+			      Json(request): Json<request::Patch<Id>>| async move {
+				const ACTION: Action = Action::Update;
+				let mut condition = MatchJob {
+					id: request.into_entities().into_iter().collect::<Match<_>>(),
+					..Default::default()
+				};
+
+				let code = match state.job_permissions(&user, ACTION).await?
+				{
+					Object::Job => Code::Success,
+
+					// HACK: no if-let guards…
+					Object::JobInDepartment if user.employee().is_some() =>
+					{
+						condition.departments &= MatchDepartment::from(user.department().unwrap().id).into();
+						Code::SuccessForPermissions
+					},
+
+					p @ Object::JobInDepartment => return no_effective_perms(ACTION, p, Reason::NoDepartment),
+					p => p.unreachable(),
+				};
+
+				let mut entities = A::Job::retrieve(state.pool(), condition)
+					.await
+					.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+				let now = Utc::now();
+				let invalid = entities
+					.iter_mut()
+					.filter_map(|j| transition(j, now).err().map(|r| format!("job {} cannot change: {r}", j.id)))
+					.collect::<Vec<_>>();
+
+				if !invalid.is_empty()
+				{
+					return Err(Response::from(Post::from(Status::new(Code::InvalidTransition, invalid.join("; ")))));
+				}
+
+				update_returning::<A::Job>(&state, kind, entities, code).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::JOB_REOPEN`](crate::api::routes::JOB_REOPEN).
+	pub fn job_reopen(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		Self::job_lifecycle(lifecycle::reopen, EventKind::Update)
+	}
+
+	/// The handler for the [`routes::LOCATION`](crate::api::routes::LOCATION).
+	pub fn location(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		route!(
+			routing::delete(
+				|Extension(user): Extension<User>,
+				 State(state): State<ServerState<A::Db>>,
+				 Json(request): Json<request::Delete<Location>>| async move {
+					state.enforce_permission(&user, Object::Location, Action::Delete).await?;
+					delete::<A::Location>(&state, request.into_entities(), Code::Success).await
+				},
+			),
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Patch<Location>>| async move {
+				validate::<Delete>(&request)?;
+				hierarchy::update::<A>(&state, &user, request.into_entities()).await
+			},
+			Location,
+			(Option<Currency>, String, Option<Location>),
+			currency,
+			name,
+			outer
+		)
+	}
+
+	/// The handler for the [`routes::LOCATION_ANCESTORS`](crate::api::routes::LOCATION_ANCESTORS).
+	pub fn location_ancestors(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<Id>>| async move {
+				hierarchy::ancestors::<A>(&state, &user, request.into_condition()).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::LOCATION_DESCENDANTS`](crate::api::routes::LOCATION_DESCENDANTS).
+	pub fn location_descendants(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<Id>>| async move {
+				hierarchy::descendants::<A>(&state, &user, request.into_condition()).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::LOCATION_MOVE`](crate::api::routes::LOCATION_MOVE).
+	pub fn location_move(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::patch(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Patch<(Id, Option<Id>)>>| async move {
+				hierarchy::relocate::<A>(&state, &user, request.into_entities()).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::LOCATION_ORGANIZATIONS`](crate::api::routes::LOCATION_ORGANIZATIONS).
+	pub fn location_organizations(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<Id>>| async move {
+				hierarchy::organizations::<A>(&state, &user, request.into_condition()).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::LOGIN`](crate::api::routes::LOGIN).
+	///
+	/// When there is an `ldap` directory, credentials are checked against it first. The users which it manages are
+	/// never authenticated by their password in the database.
+	pub fn login(&self, ldap: Option<Ldap>) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|mut auth: AuthContext<A::Db>,
+			 State(state): State<ServerState<A::Db>>,
+			 Extension(ldap): Extension<Option<Ldap>>,
+			 TypedHeader(credentials): TypedHeader<Authorization<Basic>>| {
+				async move {
+					if let Some(l) = &ldap
+					{
+						// the password is not stored locally, so it cannot expire
+						if let Some(user) = ldap::authenticate::<A>(&state, l, &credentials).await?
+						{
+							return start_session(&mut auth, user).await;
+						}
+					}
+
+					let user = authenticate::<A>(&state, &credentials).await?;
+					if let Some(l) = &ldap
+					{
+						if ldap::managed::<A>(&state, l, &user).await?
+						{
+							tracing::info!("User {} attempted to login without the LDAP directory", user.username());
+							return Err(LoginResponse::invalid_credentials(None));
+						}
+					}
 
 					// HACK: no if-let chain…
 					if let Some(result) = user.password_expires()
@@ -922,20 +1497,40 @@ where
 						}
 					}
 
-					match auth.login(&user).await
-					{
-						Ok(_) => Ok(LoginResponse::from(user)),
-						Err(e) =>
-						{
-							const CODE: Code = Code::LoginError;
-							tracing::error!("Failed to to log in user {}: {e}", user.username());
-							Err(LoginResponse::from(Status::new(CODE, e.to_string())))
-						},
-					}
+					start_session(&mut auth, user).await
 				}
 				.instrument(tracing::info_span!("login_handler"))
 			},
 		)
+		.layer(Extension(ldap))
+	}
+
+	/// The handler for the [`routes::LOGIN_OIDC`](crate::api::routes::LOGIN_OIDC).
+	pub fn login_oidc(&self, oidc: Oidc) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::get(
+			|auth: AuthContext<A::Db>, Extension(session): Extension<SessionHandle>, Extension(oidc): Extension<Oidc>| {
+				async move { oidc::authorize(&session, &oidc, auth.current_user.as_ref()).await }
+					.instrument(tracing::info_span!("login_oidc_handler"))
+			},
+		)
+		.layer(Extension(oidc))
+	}
+
+	/// The handler for the [`routes::LOGIN_OIDC_CALLBACK`](crate::api::routes::LOGIN_OIDC_CALLBACK).
+	pub fn login_oidc_callback(&self, oidc: Oidc) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|mut auth: AuthContext<A::Db>,
+			 State(state): State<ServerState<A::Db>>,
+			 Extension(session): Extension<SessionHandle>,
+			 Extension(oidc): Extension<Oidc>,
+			 Query(callback): Query<oidc::Callback>| {
+				async move { oidc::callback::<A>(&state, &mut auth, &session, &oidc, callback).await }
+					.instrument(tracing::info_span!("login_oidc_callback_handler"))
+			},
+		)
+		.layer(Extension(oidc))
 	}
 
 	/// The handler for the [`routes::LOGOUT`](crate::api::routes::LOGOUT).
@@ -962,10 +1557,150 @@ where
 		route!(Organization, (Location, String), location, name)
 	}
 
+	/// The handler for the [`routes::PASSWORD`](crate::api::routes::PASSWORD).
+	pub fn password(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|mut auth: AuthContext<A::Db>,
+			 State(state): State<ServerState<A::Db>>,
+			 TypedHeader(credentials): TypedHeader<Authorization<Basic>>,
+			 Json(password): Json<String>| {
+				async move {
+					let mut user = authenticate::<A>(&state, &credentials).await?;
+					if password.is_empty() || password == credentials.password()
+					{
+						const CODE: Code = Code::ValidationError;
+						let message = "The new password must not be empty, or the same as the current one";
+						return Err(LoginResponse::new(CODE.into(), Status::new(CODE, message.into()), None));
+					}
+
+					user.password = password;
+					user.hash_password()?;
+					user.password_set = Utc::now();
+
+					let mut tx = state.pool().begin().await?;
+					A::User::update(&mut tx, [&user].into_iter()).await?;
+					state.publish(&mut tx, EventKind::Update, [user.clone()]).await?;
+					tx.commit().await?;
+
+					tracing::info!("User {} changed their password", user.username());
+					start_session(&mut auth, user).await
+				}
+				.instrument(tracing::info_span!("password_handler"))
+			},
+		)
+	}
+
+	/// The handler for the [`routes::PASSWORD_RESET`](crate::api::routes::PASSWORD_RESET).
+	pub fn password_reset(&self, resets: PasswordResets) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(resets): Extension<PasswordResets>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(username): Json<String>| {
+				async move { reset::request::<A>(&state, &resets, username).await }
+					.instrument(tracing::info_span!("password_reset_handler"))
+			},
+		)
+		.layer(Extension(resets))
+	}
+
+	/// The handler for the [`routes::PASSWORD_RESET_REDEEM`](crate::api::routes::PASSWORD_RESET_REDEEM).
+	pub fn password_reset_redeem(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|mut auth: AuthContext<A::Db>,
+			 State(state): State<ServerState<A::Db>>,
+			 Query(token): Query<Token>,
+			 Json(password): Json<String>| {
+				async move { reset::redeem::<A>(&state, &mut auth, token, password).await }
+					.instrument(tracing::info_span!("password_reset_redeem_handler"))
+			},
+		)
+	}
+
+	/// The handler for the [`routes::QUERY`](crate::api::routes::QUERY).
+	pub fn query(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Put<(serde_json::Value, String)>>| async move {
+				validate(&request)?;
+				state.enforce_permission(&user, Object::SavedQuery, Action::Create).await?;
+
+				let (condition, route) = request.into_args();
+				A::SavedQuery::create(state.pool(), condition, route, user.id()).await.map_all(
+					|q| Response::from(Put::new(q.into(), Code::Success.into())),
+					|e| Response::from(Put::from(Status::from(&e))),
+				)
+			},
+		)
+	}
+
+	/// The handler for the [`routes::RATE`](crate::api::routes::RATE).
+	pub fn rate(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::delete(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Delete<Rate>>| async move {
+				state.enforce_permission(&user, Object::Rate, Action::Delete).await?;
+				A::Rate::remove(state.pool(), request.entities()).await.map_err(DeleteResponse::from)?;
+				Ok::<_, DeleteResponse>(DeleteResponse::from(Code::Success))
+			},
+		)
+		.get(|Extension(user): Extension<User>, State(state): State<ServerState<A::Db>>| async move {
+			state.enforce_permission(&user, Object::Rate, Action::Retrieve).await?;
+			A::Rate::retrieve(state.pool()).await.map_all(
+				|vec| Response::from(Post::new(vec, Code::Success.into())),
+				|e| Response::from(Post::from(Status::from(&e))),
+			)
+		})
+		.put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Put<Rate>>| async move {
+				validate(&request)?;
+				state.enforce_permission(&user, Object::Rate, Action::Create).await?;
+				let rate = request.into_args();
+				A::Rate::set(state.pool(), &rate).await.map_all(
+					|_| Response::from(Put::new(rate.into(), Code::Success.into())),
+					|e| Response::from(Put::from(Status::from(&e))),
+				)
+			},
+		)
+	}
+
 	/// The handler for the [`routes::ROLE`](crate::api::routes::ROLE).
 	pub fn role(&self) -> MethodRouter<ServerState<A::Db>>
 	{
-		route!(Role, (String, Option<Serde<Duration>>), name, password_ttl = password_ttl.map(Serde::into_inner))
+		route!(
+			routing::delete(
+				|Extension(user): Extension<User>,
+				 State(state): State<ServerState<A::Db>>,
+				 Query(params): Query<role::Reassign>,
+				 Json(request): Json<request::Delete<Role>>| async move {
+					role::delete::<A>(&state, &user, request.into_entities(), params).await
+				},
+			),
+			Role,
+			(String, Option<Serde<Duration>>),
+			name,
+			password_ttl = password_ttl.map(Serde::into_inner)
+		)
+	}
+
+	/// The handler for the [`routes::SEARCH`](crate::api::routes::SEARCH).
+	pub fn search(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<String>>| async move {
+				search::execute::<A>(&state, &user, request.condition()).await
+			},
+		)
 	}
 
 	/// The handler for the [`routes::TIMESHEET`](crate::api::routes::TIMESHEET).
@@ -1010,7 +1745,21 @@ where
 					p => p.unreachable(),
 				};
 
-				delete::<A::Timesheet>(state.pool(), entities, code).await
+				let locked = invoiced::timesheets::<A>(state.pool(), &entities).await.map_err(DeleteResponse::from)?;
+				let code = match invoiced::retain(&state, &user, ACTION, &mut entities, &locked, |t| t.id).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				let reviewed = review::locked::<A>(state.pool(), &entities).await.map_err(DeleteResponse::from)?;
+				let code = match review::retain(&state, &user, &mut entities, &reviewed).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				delete::<A::Timesheet>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -1018,6 +1767,7 @@ where
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Post<MatchTimesheet>>| async move {
 				const ACTION: Action = Action::Retrieve;
+				let expand = request.expand();
 				let mut condition = request.into_condition();
 				let code = match state.timesheet_permissions(&user, ACTION).await?
 				{
@@ -1039,24 +1789,26 @@ where
 
 					p @ Object::TimesheetInDepartment =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment);
+						return no_effective_perms(ACTION, p, Reason::NoDepartment).and_then(Err);
 					},
 
 					p @ Object::CreatedTimesheet =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoEmployee);
+						return no_effective_perms(ACTION, p, Reason::NoEmployee).and_then(Err);
 					},
 
 					p => p.unreachable(),
 				};
 
-				retrieve::<A::Timesheet>(state.pool(), condition, code).await
+				expand::respond(expand, retrieve::<A::Timesheet>(state.pool(), condition, code).await)
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
 			 Json(request): Json<request::Patch<Timesheet>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let mut entities = request.into_entities();
 				let code = match state.timesheet_permissions(&user, ACTION).await?
@@ -1092,13 +1844,40 @@ where
 					p => p.unreachable(),
 				};
 
-				update::<A::Timesheet>(state.pool(), entities, code).await
+				let locked = invoiced::timesheets::<A>(state.pool(), &entities).await.map_err(PatchResponse::from)?;
+				let code = match invoiced::retain(&state, &user, ACTION, &mut entities, &locked, |t| t.id).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				let reviewed = review::locked::<A>(state.pool(), &entities).await.map_err(PatchResponse::from)?;
+				let code = match review::retain(&state, &user, &mut entities, &reviewed).await?
+				{
+					true => Code::SuccessForPermissions,
+					false => code,
+				};
+
+				let intervals = entities.iter().map(Interval::from).collect::<Vec<_>>();
+				let overlaps = overlap::find::<A::Timesheet>(state.pool(), &intervals).await?;
+
+				let crossed = budget::check::<A>(&state, &entities).await;
+				let mut warnings = overlap::resolve(&headers, overlaps)?;
+				warnings.extend(budget::warnings(&crossed));
+
+				let mut tx = state.pool().begin().await.map_err(PatchResponse::from)?;
+				A::Timesheet::update(&mut tx, entities.iter()).await.map_err(PatchResponse::from)?;
+				state.publish(&mut tx, EventKind::Update, entities).await.map_err(PatchResponse::from)?;
+				budget::publish(&state, &mut tx, crossed).await.map_err(PatchResponse::from)?;
+				tx.commit().await.map_err(PatchResponse::from)?;
+				Ok(PatchResponse::from(Status::from(code).with_warnings(warnings)))
 			},
 		)
 		.put(
 			#[allow(clippy::type_complexity)]
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
 			 Json(request): Json<
 				request::Put<(
 					Employee,
@@ -1109,47 +1888,186 @@ where
 					String,
 				)>,
 			>| async move {
+				validate(&request)?;
 				#[warn(clippy::type_complexity)]
-				const ACTION: Action = Action::Create;
 				let (employee, expenses, job, time_begin, time_end, work_notes) = request.into_args();
-				let code = match state.timesheet_permissions(&user, ACTION).await?
+				let code = authorize::timesheet::<A, _>(&state, &user, &job).await?;
+				let overlaps = overlap::find::<A::Timesheet>(state.pool(), &[Interval::new(
+					employee.id,
+					None,
+					time_begin,
+					time_end,
+				)])
+				.await
+				.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+
+				let mut warnings = overlap::resolve(&headers, overlaps)?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result =
+					A::Timesheet::create(&mut tx, employee, expenses, job, time_begin, time_end, work_notes).await;
+
+				// the timesheet is not committed yet, so what is stored is still how the budget was before it
+				if let Ok(t) = result.as_ref()
 				{
-					Object::Timesheet => Code::Success,
-					Object::TimesheetInDepartment
-						if user.department().map_or(false, |d| job.departments.iter().any(|d2| d2.id == d.id)) =>
+					let crossed = budget::check::<A>(&state, core::slice::from_ref(t)).await;
+					warnings.extend(budget::warnings(&crossed));
+					budget::publish(&state, &mut tx, crossed)
+						.await
+						.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				}
+
+				create(&state, Status::from(code).with_warnings(warnings), tx, result).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::TIMESHEET_REVIEW`](crate::api::routes::TIMESHEET_REVIEW).
+	pub fn timesheet_review(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::patch(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Patch<Review>>| async move {
+				review::review::<A>(&state, &user, request.into_entities()).await
+			},
+		)
+		.post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<MatchTimesheet>>| async move {
+				review::retrieve::<A>(&state, &user, request.into_condition()).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::TIMESHEET_START`](crate::api::routes::TIMESHEET_START).
+	pub fn timesheet_start(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::put(
+			#[allow(clippy::type_complexity)]
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 headers: HeaderMap,
+			 Json(request): Json<request::Put<(Vec<(String, Money, String)>, Job, String)>>| async move {
+				validate(&request)?;
+				#[warn(clippy::type_complexity)]
+				const ACTION: Action = Action::Create;
+				let (expenses, job, work_notes) = request.into_args();
+				let employee = match state.timesheet_permissions(&user, ACTION).await?
+				{
+					// HACK: no if-let guards
+					Object::Timesheet | Object::CreatedTimesheet if user.employee().is_some() =>
 					{
-						Code::Success
+						user.employee().unwrap().clone()
 					},
 
-					p @ Object::TimesheetInDepartment =>
+					Object::TimesheetInDepartment
+						if user.department().map_or(false, |d| job.departments.iter().any(|d2| d2.id == d.id)) =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment).map_all(Into::into, Into::into);
+						user.employee().unwrap().clone()
 					},
 
-					p @ Object::CreatedTimesheet =>
+					p @ Object::TimesheetInDepartment => return no_effective_perms(ACTION, p, Reason::NoDepartment),
+					p @ (Object::Timesheet | Object::CreatedTimesheet) =>
 					{
-						return no_effective_perms(ACTION, p, Reason::ResourceExists).map_all(Into::into, Into::into);
+						return no_effective_perms(ACTION, p, Reason::NoEmployee);
 					},
 
 					p => p.unreachable(),
 				};
 
-				create(
-					code,
-					state
-						.pool()
-						.begin()
-						.and_then(|mut tx| async move {
-							let t = A::Timesheet::create(
-								&mut tx, employee, expenses, job, time_begin, time_end, work_notes,
-							)
-							.await?;
-
-							tx.commit().await?;
-							Ok(t)
-						})
-						.await,
-				)
+				invoiced::enforce::<A, _>(&state, &user, ACTION, job.id).await?;
+
+				// the employee stays locked until the timesheet is created, so that no other timer can start first
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				if A::Timer::lock_running(&mut *tx, employee.id)
+					.await
+					.map_err(|e| Response::from(Put::from(Status::from(&e))))?
+				{
+					return Err(Response::from(Put::from(Status::new(
+						Code::TimesheetRunning,
+						format!("{} already has a running timesheet", user.username()),
+					))));
+				}
+
+				let time_begin = Utc::now();
+				let overlaps = overlap::find::<A::Timesheet>(state.pool(), &[Interval::new(
+					employee.id,
+					None,
+					time_begin,
+					None,
+				)])
+				.await
+				.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+
+				let warnings = overlap::resolve(&headers, overlaps)?;
+				let result = A::Timesheet::create(&mut tx, employee, expenses, job, time_begin, None, work_notes).await;
+				create(&state, Status::from(Code::Success).with_warnings(warnings), tx, result).await
+			},
+		)
+	}
+
+	/// The handler for the [`routes::TIMESHEET_STOP`](crate::api::routes::TIMESHEET_STOP).
+	pub fn timesheet_stop(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::patch(|Extension(user): Extension<User>, State(state): State<ServerState<A::Db>>| async move {
+			const ACTION: Action = Action::Update;
+
+			// Every timesheet permission assumes `CreatedTimesheet`, and the user is only stopping their own.
+			let permission = state.timesheet_permissions(&user, ACTION).await?;
+			let employee_id = match user.employee()
+			{
+				Some(e) => e.id,
+				None => return no_effective_perms(ACTION, permission, Reason::NoEmployee),
+			};
+
+			let mut entities = A::Timesheet::retrieve(state.pool(), MatchTimesheet {
+				employee: employee_id.into(),
+				time_end: MatchOption::None,
+				..Default::default()
+			})
+			.await
+			.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+			if entities.is_empty()
+			{
+				return Err(Response::from(Post::from(Status::new(
+					Code::TimesheetNotRunning,
+					format!("{} has no running timesheet", user.username()),
+				))));
+			}
+
+			let error = |e: sqlx::Error| Response::from(Post::from(Status::from(&e)));
+			let locked = invoiced::timesheets::<A>(state.pool(), &entities).await.map_err(error)?;
+			let code = match invoiced::retain(&state, &user, ACTION, &mut entities, &locked, |t| t.id).await?
+			{
+				true => Code::SuccessForPermissions,
+				false => Code::Success,
+			};
+
+			let time_end = Utc::now();
+			entities.iter_mut().for_each(|t| t.time_end = Some(time_end));
+
+			let crossed = budget::check::<A>(&state, &entities).await;
+			let status = Status::from(code).with_warnings(budget::warnings(&crossed));
+
+			let mut tx = state.pool().begin().await.map_err(error)?;
+			A::Timesheet::update(&mut tx, entities.iter()).await.map_err(error)?;
+			state.publish(&mut tx, EventKind::Update, entities.iter().cloned()).await.map_err(error)?;
+			budget::publish(&state, &mut tx, crossed).await.map_err(error)?;
+			tx.commit().await.map_err(error)?;
+			Ok(Response::from(Post::new(entities, status)))
+		})
+	}
+
+	/// The handler for the [`routes::TIMESHEET_SUBMIT`](crate::api::routes::TIMESHEET_SUBMIT).
+	pub fn timesheet_submit(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::patch(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Patch<Id>>| async move {
+				review::submit::<A>(&state, &user, request.into_entities()).await
 			},
 		)
 	}
@@ -1190,7 +2108,8 @@ where
 					p => p.unreachable(),
 				};
 
-				delete::<A::User>(state.pool(), entities, code).await
+				lockout::guard::<A, Delete>(&state, &entities, &[]).await?;
+				delete::<A::User>(&state, entities, code).await
 			},
 		)
 		.post(
@@ -1198,6 +2117,7 @@ where
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Post<MatchUser>>| async move {
 				const ACTION: Action = Action::Retrieve;
+				let expand = request.expand();
 				let mut condition = request.into_condition();
 				let code = match state.user_permissions(&user, ACTION).await?
 				{
@@ -1227,19 +2147,20 @@ where
 
 					p @ Object::UserInDepartment =>
 					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment);
+						return no_effective_perms(ACTION, p, Reason::NoDepartment).and_then(Err);
 					},
 
 					p => p.unreachable(),
 				};
 
-				retrieve::<A::User>(state.pool(), condition, code).await
+				expand::respond(expand, retrieve::<A::User>(state.pool(), condition, code).await)
 			},
 		)
 		.patch(
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Patch<User>>| async move {
+				validate::<Delete>(&request)?;
 				const ACTION: Action = Action::Update;
 				let mut entities = request.into_entities();
 				let code = match state.user_permissions(&user, ACTION).await?
@@ -1269,6 +2190,8 @@ where
 					p => p.unreachable(),
 				};
 
+				lockout::guard::<A, Delete>(&state, &[], &entities).await?;
+
 				// retrieve all passwords which were potentially updated.
 				let passwords = A::User::retrieve(
 					state.pool(),
@@ -1293,7 +2216,7 @@ where
 					Ok::<_, HashError>(())
 				})?;
 
-				update::<A::User>(state.pool(), entities, code).await
+				update::<A::User>(&state, entities, code).await
 			},
 		)
 		.put(
@@ -1301,28 +2224,64 @@ where
 			|Extension(user): Extension<User>,
 			 State(state): State<ServerState<A::Db>>,
 			 Json(request): Json<request::Put<(Option<Employee>, String, Role, String)>>| async move {
+				validate(&request)?;
 				#[warn(clippy::type_complexity)]
-				const ACTION: Action = Action::Create;
 				let (employee, password, role, username) = request.into_args();
-				let code = match state.user_permissions(&user, ACTION).await?
-				{
-					Object::User => Code::Success,
-					Object::UserInDepartment
-						if user.department().zip(employee.as_ref()).map_or(false, |(d, e)| d.id == e.department.id) =>
-					{
-						Code::Success
-					},
-
-					p @ Object::UserSelf => return no_effective_perms(ACTION, p, Reason::ResourceExists),
-					p @ Object::UserInDepartment =>
-					{
-						return no_effective_perms(ACTION, p, Reason::NoDepartment).map_all(Into::into, Into::into);
-					},
+				let code = authorize::user(&state, &user, employee.as_ref()).await?;
+				let mut tx = state.pool().begin().await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+				let result = A::User::create(&mut *tx, employee, password, role, username).await;
+				create(&state, code, tx, result).await
+			},
+		)
+	}
 
-					p => p.unreachable(),
-				};
+	/// The handler for the [`routes::WEBHOOK`](crate::api::routes::WEBHOOK).
+	pub fn webhook(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::delete(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Delete<Id>>| async move {
+				state.enforce_permission(&user, Object::Webhook, Action::Delete).await?;
+				A::Webhook::delete(state.pool(), request.entities()).await.map_err(DeleteResponse::from)?;
+				Ok::<_, DeleteResponse>(DeleteResponse::from(Code::Success))
+			},
+		)
+		.get(|Extension(user): Extension<User>, State(state): State<ServerState<A::Db>>| async move {
+			state.enforce_permission(&user, Object::Webhook, Action::Retrieve).await?;
+			A::Webhook::retrieve(state.pool()).await.map_all(
+				|vec| Response::from(Post::new(vec, Code::Success.into())),
+				|e| Response::from(Post::from(Status::from(&e))),
+			)
+		})
+		.put(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Put<(Vec<EventKind>, String, String)>>| async move {
+				validate(&request)?;
+				state.enforce_permission(&user, Object::Webhook, Action::Create).await?;
+
+				let (events, secret, url) = request.into_args();
+				A::Webhook::create(state.pool(), events, secret, url).await.map_all(
+					|w| Response::from(Put::new(w.into(), Code::Success.into())),
+					|e| Response::from(Put::from(Status::from(&e))),
+				)
+			},
+		)
+	}
 
-				create(code, A::User::create(state.pool(), employee, password, role, username).await)
+	/// The handler for the [`routes::WEBHOOK_DELIVERIES`](crate::api::routes::WEBHOOK_DELIVERIES).
+	pub fn webhook_deliveries(&self) -> MethodRouter<ServerState<A::Db>>
+	{
+		routing::post(
+			|Extension(user): Extension<User>,
+			 State(state): State<ServerState<A::Db>>,
+			 Json(request): Json<request::Post<Id>>| async move {
+				state.enforce_permission(&user, Object::Webhook, Action::Retrieve).await?;
+				A::Webhook::deliveries(state.pool(), request.into_condition()).await.map_all(
+					|vec| Response::from(Post::new(vec, Code::Success.into())),
+					|e| Response::from(Post::from(Status::from(&e))),
+				)
 			},
 		)
 	}
@@ -0,0 +1,246 @@
+//! Contains the uploading and downloading of [`Attachment`]s to [`Expense`](winvoice_schema::Expense)s.
+//!
+//! Access to an [`Attachment`] is governed by the same permissions as the expense which it is attached to.
+
+use axum::{
+	body::{Bytes, HttpBody},
+	extract::RawBody,
+	http::{header, HeaderMap, HeaderValue},
+	response::IntoResponse,
+};
+use serde::Deserialize;
+use winvoice_adapter::{schema::TimesheetAdapter, Retrievable};
+use winvoice_match::{MatchExpense, MatchTimesheet};
+use winvoice_schema::Id;
+
+use super::{authorize, no_effective_perms, Reason};
+use crate::{
+	api::{
+		response::{Delete, Post, Put},
+		Code,
+		Status,
+	},
+	permissions::{Action, Object},
+	schema::{Adapter, Attachment, AttachmentAdapter, User},
+	server::{
+		response::{DeleteResponse, Response, ResponseResult},
+		Attachments,
+		ServerState,
+	},
+	ResultExt,
+};
+
+/// The query parameters of a `GET` request, which downloads an [`Attachment`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Download
+{
+	/// The [`Id`] of the [`Attachment`].
+	id: Id,
+}
+
+/// The query parameters of a `PUT` request, which uploads an [`Attachment`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Upload
+{
+	/// The [`Id`] of the [`Expense`](winvoice_schema::Expense) to attach the file to.
+	expense: Id,
+
+	/// The name of the file.
+	name: String,
+}
+
+/// Enforce that the `user` can perform the `action` on the attachments of the expense with the `expense_id`.
+///
+/// For [`Action::Create`], this is the same check as [creating](authorize::expenses) the expense. Otherwise, it is
+/// the same as the [`Object`] the `user` has for expenses, applied to the timesheet of the expense.
+async fn enforce<A, R>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	action: Action,
+	expense_id: Id,
+) -> Result<Code, Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	let timesheet = A::Timesheet::retrieve(state.pool(), MatchTimesheet {
+		expenses: MatchExpense { id: expense_id.into(), ..Default::default() }.into(),
+		..Default::default()
+	})
+	.await
+	.map_err(|e| Response::from(R::from(Status::from(&e))))?
+	.pop();
+
+	let Some(timesheet) = timesheet
+	else
+	{
+		let permission = state.expense_permissions(user, action).await?;
+		return no_effective_perms(action, permission, Reason::NoResourceExists).and_then(Err);
+	};
+
+	if action == Action::Create
+	{
+		return authorize::expenses::<A, _>(state, user, &timesheet).await;
+	}
+
+	let department = user.department().map(|d| d.id);
+	let employee = user.employee().map(|e| e.id);
+	let (permission, allowed) = match state.expense_permissions(user, action).await?
+	{
+		p @ Object::Expenses => (p, true),
+		p @ Object::ExpensesInDepartment => (p, timesheet.job.departments.iter().any(|d| Some(d.id) == department)),
+		p @ Object::CreatedExpenses => (p, employee == Some(timesheet.employee.id)),
+		p => p.unreachable(),
+	};
+
+	if !allowed
+	{
+		return no_effective_perms(action, permission, Reason::NoResourceExists).and_then(Err);
+	}
+
+	if action != Action::Retrieve && timesheet.job.invoice.date.is_some()
+	{
+		state.enforce_permission(user, Object::InvoicedJob, action).await?;
+	}
+
+	Ok(Code::Success)
+}
+
+/// Delete the [`Attachment`]s with the `ids`, and their contents.
+///
+/// Either all of the [`Attachment`]s are deleted, or none of them are.
+pub async fn delete<A>(state: &ServerState<A::Db>, user: &User, attachments: &Attachments, ids: &[Id]) -> DeleteResponse
+where
+	A: Adapter,
+{
+	for id in ids
+	{
+		let attachment = match A::Attachment::retrieve(state.pool(), *id).await
+		{
+			Ok(Some(a)) => a,
+			Ok(None) => return Status::new(Code::InvalidReference, format!("attachment {id} does not exist")).into(),
+			Err(e) => return e.into(),
+		};
+
+		if let Err(e) = enforce::<A, Delete>(state, user, Action::Delete, attachment.expense_id()).await
+		{
+			return e.into();
+		}
+	}
+
+	if let Err(e) = A::Attachment::delete(state.pool(), ids).await
+	{
+		return e.into();
+	}
+
+	// any contents which cannot be removed now are removed by `Attachments::clean` later
+	attachments.remove::<A::Attachment>(state.pool(), ids).await;
+	Code::Success.into()
+}
+
+/// Respond with the contents of the [`Attachment`] which the `params` refer to.
+pub async fn download<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	attachments: &Attachments,
+	params: Download,
+) -> Result<axum::response::Response, Response<Post<Attachment>>>
+where
+	A: Adapter,
+{
+	let attachment = A::Attachment::retrieve(state.pool(), params.id)
+		.await
+		.map_err(|e| Response::from(Post::from(Status::from(&e))))?
+		.ok_or_else(|| {
+			let status = Status::new(Code::InvalidReference, format!("attachment {} does not exist", params.id));
+			Response::from(Post::from(status))
+		})?;
+
+	enforce::<A, Post<Attachment>>(state, user, Action::Retrieve, attachment.expense_id()).await?;
+	let contents = attachments
+		.storage()
+		.read(attachment.id())
+		.await
+		.map_err(|e| Response::from(Post::from(Status::new(Code::Other, e.to_string()))))?;
+
+	let content_type = HeaderValue::from_str(attachment.content_type())
+		.unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
+
+	// quotes and backslashes cannot be escaped reliably, so they are removed instead
+	let name = attachment.name().replace(['"', '\\'], "");
+	let disposition = HeaderValue::from_str(&format!("attachment; filename=\"{name}\""))
+		.unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+
+	Ok(([(header::CONTENT_TYPE, content_type), (header::CONTENT_DISPOSITION, disposition)], contents).into_response())
+}
+
+/// Respond with the [`Attachment`]s of the expense with the `expense_id`.
+pub async fn list<A>(state: &ServerState<A::Db>, user: &User, expense_id: Id) -> ResponseResult<Post<Attachment>>
+where
+	A: Adapter,
+{
+	enforce::<A, Post<Attachment>>(state, user, Action::Retrieve, expense_id).await?;
+	A::Attachment::retrieve_for(state.pool(), expense_id).await.map_all(
+		|vec| Response::from(Post::new(vec, Code::Success.into())),
+		|e| Response::from(Post::from(Status::from(&e))),
+	)
+}
+
+/// Attach the `body` to the expense which the `params` refer to, as long as it is within the limits of the
+/// `attachments`.
+pub async fn upload<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	attachments: &Attachments,
+	headers: &HeaderMap,
+	params: Upload,
+	RawBody(mut body): RawBody,
+) -> ResponseResult<Put<Attachment>>
+where
+	A: Adapter,
+{
+	/// Create a [`Response`] with the `code` and `message`.
+	fn reject(code: Code, message: String) -> Response<Put<Attachment>>
+	{
+		Response::from(Put::from(Status::new(code, message)))
+	}
+
+	let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+	if !attachments.accepts(content_type)
+	{
+		let message = format!("attachments of type {content_type:?} are not allowed");
+		return Err(reject(Code::AttachmentTypeNotAllowed, message));
+	}
+
+	enforce::<A, Put<Attachment>>(state, user, Action::Create, params.expense).await?;
+
+	let max_size = attachments.max_size();
+	let mut contents = Vec::new();
+	while let Some(chunk) = body.data().await
+	{
+		let chunk: Bytes = chunk.map_err(|e| reject(Code::EncodingError, e.to_string()))?;
+		if contents.len() + chunk.len() > max_size
+		{
+			return Err(reject(Code::AttachmentTooLarge, format!("attachments may be at most {max_size} bytes")));
+		}
+
+		contents.extend_from_slice(&chunk);
+	}
+
+	let size = i64::try_from(contents.len()).map_err(|e| reject(Code::AttachmentTooLarge, e.to_string()))?;
+	let attachment = A::Attachment::create(state.pool(), content_type.to_owned(), params.expense, params.name, size)
+		.await
+		.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+
+	if let Err(e) = attachments.storage().write(attachment.id(), &contents).await
+	{
+		if let Err(e) = A::Attachment::delete(state.pool(), &[attachment.id()]).await
+		{
+			tracing::error!("Could not remove attachment {} after failing to store it: {e}", attachment.id());
+		}
+
+		return Err(reject(Code::Other, format!("The attachment could not be stored: {e}")));
+	}
+
+	Ok(Response::from(Put::new(attachment.into(), Code::Success.into())))
+}
@@ -0,0 +1,180 @@
+//! Contains the permission checks for creating entities, which are shared between the `PUT` handlers and
+//! [`batch`](super::batch)es.
+
+use sqlx::Database;
+use winvoice_schema::{Department, Employee, Job, Timesheet};
+
+use super::{invoiced, no_effective_perms, review, Reason};
+use crate::{
+	api::{Code, Status},
+	permissions::{Action, Object},
+	schema::{Adapter, User},
+	server::{response::Response, ServerState},
+};
+
+/// The [`Action`] which every check in this module is for.
+const ACTION: Action = Action::Create;
+
+/// Return an [`Err`] for when the `user` has permission to create an `object`, but not effectively.
+fn deny<R>(object: Object, reason: Reason) -> Result<Code, Response<R>>
+where
+	R: AsRef<Code> + From<Status>,
+{
+	no_effective_perms(ACTION, object, reason).and_then(Err)
+}
+
+/// Whether the `user` can create a [`Department`].
+pub async fn department<Db, R>(state: &ServerState<Db>, user: &User) -> Result<Code, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	match state.department_permissions(user, ACTION).await?
+	{
+		Object::Department => Ok(Code::Success),
+		p @ Object::AssignedDepartment => deny(p, Reason::ResourceExists),
+		p => p.unreachable(),
+	}
+}
+
+/// Whether the `user` can create an [`Employee`] in the `department`.
+pub async fn employee<Db, R>(state: &ServerState<Db>, user: &User, department: &Department) -> Result<Code, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	match state.employee_permissions(user, ACTION).await?
+	{
+		Object::Employee => Ok(Code::Success),
+		Object::EmployeeInDepartment if user.department().map_or(false, |d| d.id == department.id) =>
+		{
+			Ok(Code::Success)
+		},
+
+		p @ Object::EmployeeInDepartment => deny(p, Reason::NoDepartment),
+		p @ Object::EmployeeSelf => deny(p, Reason::ResourceExists),
+		p => p.unreachable(),
+	}
+}
+
+/// Whether the `user` can create [`Expense`](winvoice_schema::Expense)s for the `timesheet`.
+///
+/// A timesheet which has been [submitted for review](review::locked) only accepts new expenses from someone who can
+/// approve it.
+pub async fn expenses<A, R>(state: &ServerState<A::Db>, user: &User, timesheet: &Timesheet) -> Result<Code, Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	let code = match state.expense_permissions(user, ACTION).await?
+	{
+		Object::Expenses => Code::Success,
+
+		// The user can only create expenses iff the timesheet is in the same department.
+		Object::ExpensesInDepartment if user.employee().is_some() =>
+		{
+			let id = user.department().unwrap().id;
+			if !timesheet.job.departments.iter().any(|d| d.id == id)
+			{
+				return deny(Object::ExpensesInDepartment, Reason::NoResourceExists);
+			}
+
+			Code::Success
+		},
+
+		p @ Object::ExpensesInDepartment => return deny(p, Reason::NoDepartment),
+		p @ Object::CreatedExpenses => return deny(p, Reason::ResourceExists),
+		p => p.unreachable(),
+	};
+
+	if timesheet.job.invoice.date.is_some()
+	{
+		state.enforce_permission(user, Object::InvoicedJob, ACTION).await?;
+	}
+
+	let mut timesheets = vec![timesheet.clone()];
+	let locked = review::locked::<A>(state.pool(), &timesheets)
+		.await
+		.map_err(|e| Response::from(R::from(Status::from(&e))))?;
+
+	if review::retain(state, user, &mut timesheets, &locked).await?
+	{
+		let message = format!("timesheet {} has been submitted for review", timesheet.id);
+		return Err(Response::from(R::from(Status::new(Code::Unauthorized, message))));
+	}
+
+	Ok(code)
+}
+
+/// Whether the `user` can create a [`Job`] for the `departments`.
+pub async fn job<'dpt, Db, R>(
+	state: &ServerState<Db>,
+	user: &User,
+	mut departments: impl Iterator<Item = &'dpt Department>,
+) -> Result<Code, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	match state.job_permissions(user, ACTION).await?
+	{
+		Object::Job => Ok(Code::Success),
+		Object::JobInDepartment if user.department().map_or(false, |d| departments.any(|d2| d2.id == d.id)) =>
+		{
+			Ok(Code::Success)
+		},
+
+		p @ Object::JobInDepartment => deny(p, Reason::NoDepartment),
+		p => p.unreachable(),
+	}
+}
+
+/// Whether the `user` can create a [`Timesheet`] for the `job`.
+pub async fn timesheet<A, R>(state: &ServerState<A::Db>, user: &User, job: &Job) -> Result<Code, Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	let code = match state.timesheet_permissions(user, ACTION).await?
+	{
+		Object::Timesheet => Code::Success,
+		Object::TimesheetInDepartment
+			if user.department().map_or(false, |d| job.departments.iter().any(|d2| d2.id == d.id)) =>
+		{
+			Code::Success
+		},
+
+		p @ Object::TimesheetInDepartment => return deny(p, Reason::NoDepartment),
+		p @ Object::CreatedTimesheet => return deny(p, Reason::ResourceExists),
+		p => p.unreachable(),
+	};
+
+	match job.invoice.date
+	{
+		Some(_) => state.enforce_permission(user, Object::InvoicedJob, ACTION).await?,
+		None => invoiced::enforce::<A, _>(state, user, ACTION, job.id).await?,
+	};
+
+	Ok(code)
+}
+
+/// Whether the `user` can create a [`User`] for the `employee`.
+pub async fn user<Db, R>(state: &ServerState<Db>, user: &User, employee: Option<&Employee>) -> Result<Code, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	match state.user_permissions(user, ACTION).await?
+	{
+		Object::User => Ok(Code::Success),
+		Object::UserInDepartment
+			if user.department().zip(employee).map_or(false, |(d, e)| d.id == e.department.id) =>
+		{
+			Ok(Code::Success)
+		},
+
+		p @ Object::UserSelf => deny(p, Reason::ResourceExists),
+		p @ Object::UserInDepartment => deny(p, Reason::NoDepartment),
+		p => p.unreachable(),
+	}
+}
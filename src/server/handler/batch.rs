@@ -0,0 +1,275 @@
+//! Contains the execution of a [`Batch`](crate::api::request::Batch) of [`Operation`]s.
+
+use std::collections::BTreeSet;
+
+use axum::http::HeaderMap;
+use sqlx::{Database, Executor, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		DepartmentAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Retrievable,
+};
+use winvoice_schema::{Department, Employee, Job, Location, Organization, Timesheet};
+
+use super::{authorize, overlap, Interval};
+use crate::{
+	api::{
+		request::{Arg, Operation},
+		response::Post,
+		Code,
+		Entity,
+		EventKind,
+		Status,
+	},
+	permissions::{Action, Object},
+	schema::{Adapter, Role, RoleAdapter, User, UserAdapter},
+	server::{
+		response::{Response, ResponseResult},
+		ServerState,
+	},
+};
+
+/// The [`Action`] which every [`Operation`] performs.
+const ACTION: Action = Action::Create;
+
+/// The response to a [`Batch`](crate::api::request::Batch).
+type BatchResponse = Response<Post<Entity>>;
+
+/// An entity which an [`Arg::Ref`] may refer to.
+trait Referent: Sized
+{
+	/// The name of the entity, for error messages.
+	const NAME: &'static str;
+
+	/// Get `Self` out of the `entity`, if it is the right kind.
+	fn from_entity(entity: &Entity) -> Option<Self>;
+}
+
+/// Implement [`Referent`] for the `$Entity` which is stored in the [`Entity`] variant of the same name.
+macro_rules! impl_referent {
+	($($Entity:ident),+) => {
+		$(
+			impl Referent for $Entity
+			{
+				const NAME: &'static str = stringify!($Entity);
+
+				fn from_entity(entity: &Entity) -> Option<Self>
+				{
+					match entity
+					{
+						Entity::$Entity(e) => Some(e.clone()),
+						_ => None,
+					}
+				}
+			}
+		)+
+	};
+}
+
+impl_referent!(Department, Employee, Job, Location, Organization, Role, Timesheet);
+
+/// Run every operation in a single [`Transaction`], so that either all of them are committed or none are.
+///
+/// Each operation is checked against the same permissions as a `PUT` to its route would be, and may refer to the
+/// entities `created` by the operations before it. The response has the [`Code`] of the least permissive operation.
+/// Any overlapping [`Timesheet`]s are [resolved](overlap::resolve) once every operation has run.
+///
+/// When `dry_run` is `true`, the transaction is rolled back instead, so that the response describes what would have
+/// been created.
+pub async fn execute<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	headers: &HeaderMap,
+	operations: Vec<Operation>,
+	dry_run: bool,
+) -> ResponseResult<Post<Entity>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let mut tx = state.pool().begin().await.map_err(database)?;
+	let mut created = Vec::with_capacity(operations.len());
+	let mut code = Code::Success;
+	for (i, operation) in operations.into_iter().enumerate()
+	{
+		let (entity, c) = step::<A>(state, user, &mut tx, &created, operation).await.map_err(|r| {
+			let status = r.into_content().into_status();
+			Response::from(Post::from(Status::new(status.code(), format!("operation {i}: {}", status.message()))))
+		})?;
+
+		if c != Code::Success
+		{
+			code = c;
+		}
+
+		created.push(entity);
+	}
+
+	let intervals = created
+		.iter()
+		.filter_map(|e| match e
+		{
+			Entity::Timesheet(t) => Some(Interval::from(t)),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	let overlaps = overlap::find::<A::Timesheet>(state.pool(), &intervals).await.map_err(database)?;
+	let warnings = overlap::resolve(headers, overlaps)?;
+
+	if dry_run
+	{
+		tx.rollback().await.map_err(database)?;
+	}
+	else
+	{
+		state.publish(&mut tx, EventKind::Create, created.iter().cloned()).await.map_err(database)?;
+		tx.commit().await.map_err(database)?;
+	}
+
+	Ok(Response::from(Post::new(created, Status::from(code).with_warnings(warnings))))
+}
+
+/// Map an [`sqlx::Error`] into a [`BatchResponse`].
+fn database(error: sqlx::Error) -> BatchResponse
+{
+	Response::from(Post::from(Status::from(&error)))
+}
+
+/// Create a [`BatchResponse`] for a [`Code::InvalidReference`] with the given `message`.
+fn invalid_reference(message: String) -> BatchResponse
+{
+	Response::from(Post::from(Status::new(Code::InvalidReference, message)))
+}
+
+/// Get the `T` which the `arg` stands for.
+fn resolve<T>(created: &[Entity], arg: Arg<T>) -> Result<T, BatchResponse>
+where
+	T: Referent,
+{
+	match arg
+	{
+		Arg::Value(t) => Ok(t),
+		Arg::Ref { r#ref: index } => created.get(index).and_then(T::from_entity).ok_or_else(|| {
+			invalid_reference(match index < created.len()
+			{
+				true => format!("operation {index} did not create a {}", T::NAME),
+				false => format!("operation {index} does not come before this one"),
+			})
+		}),
+	}
+}
+
+/// Perform one `operation` inside of the `tx`, returning what it created and the [`Code`] which its permissions
+/// allowed it to succeed with.
+async fn step<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	tx: &mut Transaction<'_, A::Db>,
+	created: &[Entity],
+	operation: Operation,
+) -> Result<(Entity, Code), BatchResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let (entity, code) = match operation
+	{
+		Operation::Contact { kind, label } =>
+		{
+			state.enforce_permission(user, Object::Contact, ACTION).await?;
+			(A::Contact::create(&mut **tx, kind, label).await.map(Entity::Contact), Code::Success)
+		},
+
+		Operation::Department { name } =>
+		{
+			let code = authorize::department(state, user).await?;
+			(A::Department::create(&mut **tx, name).await.map(Entity::Department), code)
+		},
+
+		Operation::Employee { department, name, title } =>
+		{
+			let department = resolve(created, department)?;
+			let code = authorize::employee(state, user, &department).await?;
+			(A::Employee::create(&mut **tx, department, name, title).await.map(Entity::Employee), code)
+		},
+
+		Operation::Expenses { expenses, timesheet } =>
+		{
+			let timesheet = match timesheet
+			{
+				Arg::Ref { r#ref } => resolve::<Timesheet>(created, Arg::Ref { r#ref })?,
+				Arg::Value(id) => A::Timesheet::retrieve(state.pool(), id.into())
+					.await
+					.map_err(database)?
+					.pop()
+					.ok_or_else(|| invalid_reference(format!("timesheet {id} does not exist")))?,
+			};
+
+			let code = authorize::expenses::<A, _>(state, user, &timesheet).await?;
+			let entity = A::Expenses::create(&mut **tx, expenses, (timesheet.id, timesheet.time_begin)).await;
+			(entity.map(Entity::Expenses), code)
+		},
+
+		Operation::Job { client, date_close, date_open, departments, increment, invoice, notes, objectives } =>
+		{
+			let client = resolve(created, client)?;
+			let departments =
+				departments.into_iter().map(|d| resolve(created, d)).collect::<Result<BTreeSet<_>, _>>()?;
+
+			let code = authorize::job(state, user, departments.iter()).await?;
+			let entity =
+				A::Job::create(tx, client, date_close, date_open, departments, increment, invoice, notes, objectives)
+					.await;
+
+			(entity.map(Entity::Job), code)
+		},
+
+		Operation::Location { currency, name, outer } =>
+		{
+			let outer = outer.map(|o| resolve(created, o)).transpose()?;
+			state.enforce_permission(user, Object::Location, ACTION).await?;
+			(A::Location::create(&mut **tx, currency, name, outer).await.map(Entity::Location), Code::Success)
+		},
+
+		Operation::Organization { location, name } =>
+		{
+			let location = resolve(created, location)?;
+			state.enforce_permission(user, Object::Organization, ACTION).await?;
+			(A::Organization::create(&mut **tx, location, name).await.map(Entity::Organization), Code::Success)
+		},
+
+		Operation::Role { name, password_ttl } =>
+		{
+			state.enforce_permission(user, Object::Role, ACTION).await?;
+			(A::Role::create(&mut **tx, name, password_ttl).await.map(Entity::Role), Code::Success)
+		},
+
+		Operation::Timesheet { employee, expenses, job, time_begin, time_end, work_notes } =>
+		{
+			let employee = resolve(created, employee)?;
+			let job = resolve(created, job)?;
+			let code = authorize::timesheet::<A, _>(state, user, &job).await?;
+			let entity = A::Timesheet::create(tx, employee, expenses, job, time_begin, time_end, work_notes).await;
+			(entity.map(Entity::Timesheet), code)
+		},
+
+		Operation::User { employee, password, role, username } =>
+		{
+			let employee = employee.map(|e| resolve(created, e)).transpose()?;
+			let role = resolve(created, role)?;
+			let code = authorize::user(state, user, employee.as_ref()).await?;
+			(A::User::create(&mut **tx, employee, password, role, username).await.map(Entity::User), code)
+		},
+	};
+
+	entity.map(|e| (e, code)).map_err(database)
+}
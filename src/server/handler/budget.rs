@@ -0,0 +1,264 @@
+//! Contains the [`Budget`]s of [`Job`]s, how much of them the [`Timesheet`]s of each job have consumed, and the
+//! warnings which are raised when a timesheet pushes a job past one of the [thresholds](Budget::thresholds).
+
+use std::collections::{HashMap, HashSet};
+
+use money2::{Exchange, HistoricalExchangeRates};
+use sqlx::{Database, Executor, Pool};
+use winvoice_adapter::Retrievable;
+use winvoice_match::{Match, MatchDepartment, MatchJob, MatchTimesheet};
+use winvoice_schema::{Id, Job, Timesheet};
+
+use super::{no_effective_perms, Reason};
+use crate::{
+	api::{
+		response::{Delete, Post, Put},
+		Code,
+		EventKind,
+		Status,
+	},
+	permissions::{Action, Object},
+	schema::{Adapter, Allowance, Budget, BudgetAdapter, BudgetUsage, User},
+	server::{
+		response::{DeleteResponse, Response, ResponseResult},
+		ServerState,
+	},
+	twin_result::TwinResult,
+};
+
+/// Remove the [`Budget`]s of the jobs with the given `ids` which the `user` can [update](Action::Update).
+pub async fn remove<A>(state: &ServerState<A::Db>, user: &User, ids: Vec<Id>) -> TwinResult<DeleteResponse>
+where
+	A: Adapter,
+{
+	let mut condition = MatchJob { id: ids.into_iter().collect::<Match<_>>(), ..Default::default() };
+	let code = scope::<_, Delete>(state, user, Action::Update, &mut condition).await?;
+	let jobs = A::Job::retrieve(state.pool(), condition).await.map_err(DeleteResponse::from)?;
+
+	let ids = jobs.into_iter().map(|j| j.id).collect::<Vec<_>>();
+	A::Budget::remove(state.pool(), &ids).await.map_err(DeleteResponse::from)?;
+	Ok(DeleteResponse::from(code))
+}
+
+/// Respond with how much of the [`Budget`]s of the jobs matching the `condition` which the `user` can retrieve have
+/// been consumed. Jobs without a budget are skipped.
+pub async fn retrieve<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	mut condition: MatchJob,
+) -> ResponseResult<Post<BudgetUsage>>
+where
+	A: Adapter,
+{
+	let code = scope(state, user, Action::Retrieve, &mut condition).await?;
+	let jobs =
+		A::Job::retrieve(state.pool(), condition).await.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+	let ids = jobs.into_iter().map(|j| j.id).collect::<Vec<_>>();
+	let budgets =
+		A::Budget::retrieve(state.pool(), &ids).await.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+	let mut consumers = consumers::<A>(state.pool(), &budgets, &[]).await.map_err(|s| Response::from(Post::from(s)))?;
+	let usages = budgets
+		.into_iter()
+		.map(|b| {
+			let timesheets = consumers.remove(&b.job_id()).unwrap_or_default();
+			BudgetUsage::new(b, &timesheets)
+		})
+		.collect();
+
+	Ok(Response::from(Post::new(usages, code.into())))
+}
+
+/// Set the `budget` of its job, if the `user` can [update](Action::Update) the job.
+pub async fn set<A>(state: &ServerState<A::Db>, user: &User, budget: Budget) -> ResponseResult<Put<Budget>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let mut condition = MatchJob::from(budget.job_id());
+	let code = scope(state, user, Action::Update, &mut condition).await?;
+	let jobs =
+		A::Job::retrieve(state.pool(), condition).await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+	if jobs.is_empty()
+	{
+		return Err(Response::from(Put::from(Status::new(
+			Code::InvalidReference,
+			format!("job {} does not exist, or this user cannot update it", budget.job_id()),
+		))));
+	}
+
+	A::Budget::set(state.pool(), &budget).await.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+	Ok(Response::from(Put::new(Some(budget), code.into())))
+}
+
+/// The job of each [`Budget`] which the `changed` timesheets push past one of its thresholds, how much of the budget
+/// it will have consumed (as a percentage), and the highest threshold which is passed.
+///
+/// What is stored is taken to be how the timesheets were before they changed, so this must be checked before the
+/// changes are committed. Budgets are only advisory, so if they cannot be checked the error is logged rather than
+/// returned.
+pub async fn check<A>(state: &ServerState<A::Db>, changed: &[Timesheet]) -> Vec<(Job, f64, u8)>
+where
+	A: Adapter,
+{
+	crossed::<A>(state.pool(), changed).await.unwrap_or_else(|s| {
+		tracing::error!("Could not check the budgets of the changed timesheets: {}", s.message());
+		Vec::new()
+	})
+}
+
+/// [`publish`](ServerState::publish) the jobs whose budgets were [`check`]ed to be `crossed`, with the `connection` of
+/// the transaction which changes their timesheets.
+pub async fn publish<Db>(
+	state: &ServerState<Db>,
+	connection: &mut Db::Connection,
+	crossed: Vec<(Job, f64, u8)>,
+) -> sqlx::Result<()>
+where
+	Db: Database,
+{
+	state.publish(connection, EventKind::BudgetThreshold, crossed.into_iter().map(|(job, ..)| job)).await
+}
+
+/// Warn about every [`Budget`] which was [`check`]ed to be `crossed`.
+pub fn warnings(crossed: &[(Job, f64, u8)]) -> Vec<String>
+{
+	crossed
+		.iter()
+		.map(|(job, percent, threshold)| {
+			format!("job {} has used {percent:.0}% of its budget, passing the {threshold}% threshold", job.id)
+		})
+		.collect()
+}
+
+/// The job of each [`Budget`] which the `changed` timesheets pushed past one of its thresholds, how much of the budget
+/// it has now consumed (as a percentage), and the highest threshold which was passed.
+async fn crossed<A>(pool: &Pool<A::Db>, changed: &[Timesheet]) -> Result<Vec<(Job, f64, u8)>, Status>
+where
+	A: Adapter,
+{
+	let mut jobs = changed.iter().map(|t| (t.job.id, t.job.clone())).collect::<HashMap<_, _>>();
+	if jobs.is_empty()
+	{
+		return Ok(Vec::new());
+	}
+
+	let ids = jobs.keys().copied().collect::<Vec<_>>();
+	let budgets = A::Budget::retrieve(pool, &ids).await.map_err(|e| Status::from(&e))?;
+	let mut consumers = consumers::<A>(pool, &budgets, changed).await?;
+
+	Ok(budgets
+		.into_iter()
+		.filter_map(|b| {
+			let (before, after) = consumers.remove(&b.job_id()).unwrap_or_default();
+
+			let job_id = b.job_id();
+			let thresholds = b.thresholds().clone();
+			let before = BudgetUsage::new(b.clone(), &before).percent();
+			let after = BudgetUsage::new(b, &after).percent();
+
+			thresholds
+				.into_iter()
+				.rev()
+				.find(|t| before < f64::from(*t) && f64::from(*t) <= after)
+				.and_then(|t| jobs.remove(&job_id).map(|j| (j, after, t)))
+		})
+		.collect())
+}
+
+/// The [`Timesheet`]s of the job of each of the `budgets`, both as they are stored and with the `changed` timesheets in
+/// place of what is stored (or alongside it, for timesheets which are not stored yet).
+///
+/// The timesheets of a budget of [`Money`](winvoice_schema::Money) are exchanged into its currency, using the rates
+/// from when each timesheet began.
+async fn consumers<A>(
+	pool: &Pool<A::Db>,
+	budgets: &[Budget],
+	changed: &[Timesheet],
+) -> Result<HashMap<Id, (Vec<Timesheet>, Vec<Timesheet>)>, Status>
+where
+	A: Adapter,
+{
+	if budgets.is_empty()
+	{
+		return Ok(HashMap::new());
+	}
+
+	let job_ids = budgets.iter().map(Budget::job_id).collect::<HashSet<_>>();
+	let changed_ids = changed.iter().map(|t| t.id).collect::<HashSet<_>>();
+	let stored = A::Timesheet::retrieve(pool, MatchTimesheet {
+		job: MatchJob { id: job_ids.iter().copied().collect::<Match<_>>(), ..Default::default() },
+		..Default::default()
+	})
+	.await
+	.map_err(|e| Status::from(&e))?;
+
+	let mut consumers = HashMap::<_, (Vec<_>, Vec<_>)>::new();
+	stored.into_iter().for_each(|t| {
+		let (before, after) = consumers.entry(t.job.id).or_default();
+		if !changed_ids.contains(&t.id)
+		{
+			after.push(t.clone());
+		}
+
+		before.push(t);
+	});
+
+	changed
+		.iter()
+		.filter(|t| job_ids.contains(&t.job.id))
+		.for_each(|t| consumers.entry(t.job.id).or_default().1.push(t.clone()));
+
+	if budgets.iter().any(|b| matches!(b.allowance(), Allowance::Money(_)))
+	{
+		let history = HistoricalExchangeRates::history().await.map_err(|e| Status::from(&e))?;
+		for b in budgets
+		{
+			let currency = match b.allowance()
+			{
+				Allowance::Money(m) => m.currency,
+				Allowance::Time(_) => continue,
+			};
+
+			consumers
+				.get_mut(&b.job_id())
+				.into_iter()
+				.flat_map(|(before, after)| before.iter_mut().chain(after))
+				.for_each(|t| {
+					let job_rates = HistoricalExchangeRates::index_ref_from(&history, Some(t.job.date_open.into()));
+					let rates = HistoricalExchangeRates::index_ref_from(&history, Some(t.time_begin.into()));
+					t.exchange_mut_historically(currency, rates, job_rates);
+				});
+		}
+	}
+
+	Ok(consumers)
+}
+
+/// Restrict the `condition` to the jobs which the `user` can perform the `action` on.
+async fn scope<Db, R>(
+	state: &ServerState<Db>,
+	user: &User,
+	action: Action,
+	condition: &mut MatchJob,
+) -> Result<Code, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	match state.job_permissions(user, action).await?
+	{
+		Object::Job => Ok(Code::Success),
+
+		// HACK: no if-let guards…
+		Object::JobInDepartment if user.employee().is_some() =>
+		{
+			condition.departments &= MatchDepartment::from(user.department().unwrap().id).into();
+			Ok(Code::SuccessForPermissions)
+		},
+
+		p @ Object::JobInDepartment => no_effective_perms(action, p, Reason::NoDepartment).and_then(Err),
+		p => p.unreachable(),
+	}
+}
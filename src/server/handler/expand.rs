@@ -0,0 +1,196 @@
+//! Contains the [`SideLoad`] trait, which is used to respond to a `POST` request whose [`Expand`] is [`Expand::Ids`]
+//! with a [`SideLoaded`] response.
+
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::Value;
+use winvoice_schema::{Contact, Department, Employee, Expense, Id, Job, Location, Organization, Timesheet};
+
+use crate::{
+	api::{
+		request::Expand,
+		response::{Post, Related, SideLoaded},
+		Code,
+		Status,
+	},
+	schema::{Role, User},
+	server::response::{Response, ResponseResult},
+};
+
+/// Implementors can be serialized with each entity they refer to replaced by its [`Id`].
+pub trait SideLoad: Serialize
+{
+	/// Serialize this entity, with each entity it refers to replaced by its [`Id`] and added to the `related`
+	/// entities.
+	fn side_load(&self, _related: &mut Related) -> serde_json::Result<Value>
+	{
+		serde_json::to_value(self)
+	}
+}
+
+/// Implementors can be referred to by other entities, and so are added to [`Related`] when they are side-loaded.
+trait Referable: SideLoad
+{
+	/// The key of this kind of entity in [`Related`].
+	const KIND: &'static str;
+
+	/// The [`Id`] of this entity.
+	fn id(&self) -> Id;
+}
+
+/// Add the `entity` to the `related` entities, unless it is there already, and return its [`Id`].
+fn refer<T>(related: &mut Related, entity: &T) -> serde_json::Result<Value>
+where
+	T: Referable,
+{
+	let id = entity.id();
+	if !related.get(T::KIND).map_or(false, |r| r.contains_key(&id))
+	{
+		let value = entity.side_load(related)?;
+		related.entry(T::KIND.into()).or_default().insert(id, value);
+	}
+
+	serde_json::to_value(id)
+}
+
+/// Respond with the `result` of a `POST` request, including the entities which its entities refer to as the `expand`
+/// option of the request asks.
+pub fn respond<T>(
+	expand: Expand,
+	result: ResponseResult<Post<T>>,
+) -> Result<axum::response::Response, Response<Post<T>>>
+where
+	T: SideLoad,
+{
+	let response = match (expand, result)
+	{
+		(Expand::Ids, Ok(r)) => r,
+		(Expand::Nested, Ok(r)) => return Ok(r.into_response()),
+		(_, Err(e)) => return Err(e),
+	};
+
+	let mut related = Related::new();
+	let entities = response
+		.content()
+		.entities()
+		.iter()
+		.map(|t| t.side_load(&mut related))
+		.collect::<serde_json::Result<Vec<_>>>()
+		.map_err(|e| Response::from(Post::from(Status::new(Code::EncodingError, e.to_string()))))?;
+
+	let status = response.content().status().clone();
+	Ok(Response::new(response.status(), SideLoaded::new(entities, related, status)).into_response())
+}
+
+/// Implement [`Referable`] for each `$Entity`, as the `$Kind` of entity.
+macro_rules! impl_referable {
+	($($Entity:ident => $Kind:literal),+ $(,)?) => {
+		$(
+			impl Referable for $Entity
+			{
+				const KIND: &'static str = $Kind;
+
+				fn id(&self) -> Id
+				{
+					self.id
+				}
+			}
+		)+
+	};
+}
+
+impl_referable!(
+	Department => "department",
+	Employee => "employee",
+	Job => "job",
+	Location => "location",
+	Organization => "organization",
+);
+
+impl Referable for Role
+{
+	const KIND: &'static str = "role";
+
+	fn id(&self) -> Id
+	{
+		Self::id(self)
+	}
+}
+
+impl SideLoad for Contact {}
+impl SideLoad for Department {}
+impl SideLoad for Expense {}
+impl SideLoad for Role {}
+
+impl SideLoad for Employee
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		value["department"] = refer(related, &self.department)?;
+		Ok(value)
+	}
+}
+
+impl SideLoad for Job
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		value["client"] = refer(related, &self.client)?;
+		value["departments"] =
+			self.departments.iter().map(|d| refer(related, d)).collect::<serde_json::Result<_>>()?;
+
+		Ok(value)
+	}
+}
+
+impl SideLoad for Location
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		if let Some(outer) = self.outer.as_deref()
+		{
+			value["outer"] = refer(related, outer)?;
+		}
+
+		Ok(value)
+	}
+}
+
+impl SideLoad for Organization
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		value["location"] = refer(related, &self.location)?;
+		Ok(value)
+	}
+}
+
+impl SideLoad for Timesheet
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		value["employee"] = refer(related, &self.employee)?;
+		value["job"] = refer(related, &self.job)?;
+		Ok(value)
+	}
+}
+
+impl SideLoad for User
+{
+	fn side_load(&self, related: &mut Related) -> serde_json::Result<Value>
+	{
+		let mut value = serde_json::to_value(self)?;
+		if let Some(employee) = self.employee()
+		{
+			value["employee"] = refer(related, employee)?;
+		}
+
+		value["role"] = refer(related, self.role())?;
+		Ok(value)
+	}
+}
@@ -0,0 +1,223 @@
+//! Contains the routes which walk the tree of [`Location`]s, which is formed by their
+//! [`outer`](Location::outer) locations, and which [`relocate`] parts of it.
+
+use std::collections::HashMap;
+
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{Retrievable, Updatable};
+use winvoice_match::{Match, MatchLocation, MatchOrganization};
+use winvoice_schema::{Id, Location, Organization};
+
+use crate::{
+	api::{
+		response::{Delete, Post},
+		Code,
+		EventKind,
+		Status,
+	},
+	permissions::{Action, Object},
+	schema::{Adapter, LocationTreeAdapter, User},
+	server::{
+		response::{PatchResponse, Response, ResponseResult},
+		ServerState,
+	},
+	twin_result::TwinResult,
+	ResultExt,
+};
+
+/// Respond with the locations which the location with the `id` is inside of, the nearest first.
+pub async fn ancestors<A>(state: &ServerState<A::Db>, user: &User, id: Id) -> ResponseResult<Post<Location>>
+where
+	A: Adapter,
+{
+	state.enforce_permission(user, Object::Location, Action::Retrieve).await?;
+	let location = retrieve::<A>(state.pool(), [id]).await?.remove(&id).ok_or_else(|| missing(&[id]))?;
+
+	let mut ancestors = Vec::new();
+	let mut outer = location.outer;
+	while let Some(o) = outer
+	{
+		outer = o.outer.clone();
+		ancestors.push(*o);
+	}
+
+	Ok(Response::from(Post::new(ancestors, Code::Success.into())))
+}
+
+/// Respond with the locations which are inside of the location with the `id`, however deeply.
+pub async fn descendants<A>(state: &ServerState<A::Db>, user: &User, id: Id) -> ResponseResult<Post<Location>>
+where
+	A: Adapter,
+{
+	state.enforce_permission(user, Object::Location, Action::Retrieve).await?;
+	let ids = A::LocationTree::descendants(state.pool(), &[id]).await.map_err(database)?;
+	let descendants = retrieve::<A>(state.pool(), ids).await?.into_values().collect();
+	Ok(Response::from(Post::new(descendants, Code::Success.into())))
+}
+
+/// Respond with the organizations which are located in the location with the `id`, or in any location inside of it.
+pub async fn organizations<A>(state: &ServerState<A::Db>, user: &User, id: Id) -> ResponseResult<Post<Organization>>
+where
+	A: Adapter,
+{
+	state.enforce_permission(user, Object::Location, Action::Retrieve).await?;
+	state.enforce_permission(user, Object::Organization, Action::Retrieve).await?;
+
+	let mut ids = A::LocationTree::descendants(state.pool(), &[id])
+		.await
+		.map_err(|e| Response::from(Post::from(Status::from(&e))))?;
+
+	ids.push(id);
+
+	let condition = MatchOrganization {
+		location: MatchLocation { id: ids.into_iter().collect::<Match<_>>(), ..Default::default() },
+		..Default::default()
+	};
+
+	A::Organization::retrieve(state.pool(), condition)
+		.await
+		.map_all(
+			|vec| Response::from(Post::new(vec, Code::Success.into())),
+			|e| Response::from(Post::from(Status::from(&e))),
+		)
+}
+
+/// Move each location of the `moves` inside of the location with the [`Id`] which is paired with it, or to the top
+/// of the tree if there is none, and respond with the locations which were moved.
+///
+/// Either every location is moved, or none are. A location cannot be moved inside of itself, or inside of any location
+/// which is inside of it.
+pub async fn relocate<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	moves: Vec<(Id, Option<Id>)>,
+) -> ResponseResult<Post<Location>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	state.enforce_permission(user, Object::Location, Action::Update).await?;
+
+	let ids = moves.iter().flat_map(|(id, outer)| [Some(*id), *outer]).flatten().collect::<Vec<_>>();
+	let mut locations = retrieve::<A>(state.pool(), ids.iter().copied()).await?;
+	let unknown = ids.into_iter().filter(|id| !locations.contains_key(id)).collect::<Vec<_>>();
+	if !unknown.is_empty()
+	{
+		return Err(missing(&unknown));
+	}
+
+	let mut tx = state.pool().begin().await.map_err(database)?;
+	A::LocationTree::lock(&mut *tx).await.map_err(database)?;
+	for (id, outer) in &moves
+	{
+		if let Some(o) = outer
+		{
+			acyclic::<A, Post<Location>>(&mut tx, *id, *o).await?;
+		}
+
+		let location = Location { outer: outer.map(|o| locations[&o].clone().into()), ..locations[id].clone() };
+		A::Location::update(&mut tx, [&location].into_iter()).await.map_err(database)?;
+		locations.insert(*id, location);
+	}
+
+	// the `outer` of a location which was moved may have been moved after it
+	let moved = moves.into_iter().map(|(id, _)| nest(&locations, locations[&id].clone())).collect::<Vec<_>>();
+	state.publish(&mut tx, EventKind::Update, moved.iter().cloned()).await.map_err(database)?;
+	tx.commit().await.map_err(database)?;
+	Ok(Response::from(Post::new(moved, Code::Success.into())))
+}
+
+/// The `location`, with each location which it is inside of replaced by the one with the same [`Id`] in the
+/// `locations`, if there is one.
+fn nest(locations: &HashMap<Id, Location>, location: Location) -> Location
+{
+	let outer = location.outer.map(|o| nest(locations, locations.get(&o.id).cloned().unwrap_or(*o)).into());
+	Location { outer, ..location }
+}
+
+/// [Update](Updatable::update) the `entities`, as with any other `PATCH`, except that a location cannot be moved inside
+/// of itself, or inside of any location which is inside of it.
+///
+/// Either every location is updated, or none are.
+pub async fn update<A>(state: &ServerState<A::Db>, user: &User, entities: Vec<Location>) -> TwinResult<PatchResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	state.enforce_permission(user, Object::Location, Action::Update).await?;
+
+	let mut tx = state.pool().begin().await.map_err(PatchResponse::from)?;
+	A::LocationTree::lock(&mut *tx).await.map_err(PatchResponse::from)?;
+	for location in &entities
+	{
+		if let Some(o) = &location.outer
+		{
+			acyclic::<A, Delete>(&mut tx, location.id, o.id).await?;
+		}
+
+		A::Location::update(&mut tx, [location].into_iter()).await.map_err(PatchResponse::from)?;
+	}
+
+	state.publish(&mut tx, EventKind::Update, entities).await.map_err(PatchResponse::from)?;
+	tx.commit().await.map_err(PatchResponse::from)?;
+	Ok(PatchResponse::from(Status::from(Code::Success)))
+}
+
+/// Ensure that the location with the `id` can be moved inside of the location with the `outer` [`Id`] without forming
+/// a cycle.
+///
+/// The changes which have already been made in the `tx` are taken into account, and the `tx` must have
+/// [locked](LocationTreeAdapter::lock) the tree so that no other transaction can make changes which are not.
+async fn acyclic<A, R>(tx: &mut Transaction<'_, A::Db>, id: Id, outer: Id) -> Result<(), Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let descendants = A::LocationTree::descendants(&mut **tx, &[id])
+		.await
+		.map_err(|e| Response::from(R::from(Status::from(&e))))?;
+
+	let message = match outer == id
+	{
+		true => format!("location {id} cannot be moved inside of itself"),
+		false if descendants.contains(&outer) => format!("location {outer} is inside of location {id}"),
+		false => return Ok(()),
+	};
+
+	Err(Response::from(R::from(Status::new(Code::LocationCycle, message))))
+}
+
+/// Retrieve the locations with the `ids`, by their [`Id`].
+async fn retrieve<A>(
+	pool: &Pool<A::Db>,
+	ids: impl IntoIterator<Item = Id>,
+) -> Result<HashMap<Id, Location>, Response<Post<Location>>>
+where
+	A: Adapter,
+{
+	let ids = ids.into_iter().collect::<Vec<_>>();
+	if ids.is_empty()
+	{
+		return Ok(HashMap::new());
+	}
+
+	let condition = MatchLocation { id: ids.into_iter().collect::<Match<_>>(), ..Default::default() };
+	A::Location::retrieve(pool, condition)
+		.await
+		.map_all(|vec| vec.into_iter().map(|l| (l.id, l)).collect(), database)
+}
+
+/// A [`Response`] for when there are no locations with the `ids`.
+fn missing(ids: &[Id]) -> Response<Post<Location>>
+{
+	let ids = ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+	let message = format!("There are no locations {}", ids.join(", "));
+	Response::from(Post::from(Status::new(Code::InvalidReference, message)))
+}
+
+/// Map an [`sqlx::Error`] into a [`Response`].
+fn database(error: sqlx::Error) -> Response<Post<Location>>
+{
+	Response::from(Post::from(Status::from(&error)))
+}
@@ -0,0 +1,405 @@
+//! Contains the conversion of the rows of an [`Import`] into [`Operation`]s, which are then run as a
+//! [`batch`](super::batch).
+
+use core::fmt::Display;
+use std::collections::{BTreeMap, HashMap};
+
+use axum::http::HeaderMap;
+use csv::{Reader, StringRecord};
+use sqlx::{Database, Executor};
+use winvoice_adapter::Retrievable;
+use winvoice_match::{MatchDepartment, MatchEmployee, MatchJob, MatchLocation, MatchOrganization};
+use winvoice_schema::{
+	chrono::{DateTime, ParseError, Utc},
+	Department,
+	Employee,
+	Id,
+	Invoice,
+	Job,
+	Location,
+	Money,
+	Organization,
+};
+
+use super::{batch, scope, validate};
+use crate::{
+	api::{
+		request::{Import, ImportKind, Operation},
+		response::Post,
+		Code,
+		Entity,
+		Status,
+	},
+	permissions::{Action, Object},
+	schema::{Adapter, User},
+	server::{
+		response::{Response, ResponseResult},
+		ServerState,
+	},
+};
+
+/// The separator between the names of the departments of a job.
+const DEPARTMENT_SEPARATOR: char = ';';
+
+/// The entities which have already been looked up, so that each is only retrieved once.
+#[derive(Default)]
+struct Cache
+{
+	/// [`Department`]s by name.
+	departments: HashMap<String, Department>,
+
+	/// [`Employee`]s by name.
+	employees: HashMap<String, Employee>,
+
+	/// [`Job`]s by [`Id`].
+	jobs: HashMap<String, Job>,
+
+	/// [`Location`]s by name.
+	locations: HashMap<String, Location>,
+
+	/// [`Organization`]s by name.
+	organizations: HashMap<String, Organization>,
+}
+
+/// One row of the CSV, whose values can be looked up by field.
+struct Row<'r>
+{
+	/// The index of the column of each field.
+	columns: &'r HashMap<&'static str, usize>,
+
+	/// The values of the row.
+	record: &'r StringRecord,
+}
+
+impl Row<'_>
+{
+	/// The value of the `field`, if it is present and not blank.
+	fn optional(&self, field: &str) -> Option<&str>
+	{
+		self.columns.get(field).and_then(|i| self.record.get(*i)).map(str::trim).filter(|s| !s.is_empty())
+	}
+
+	/// The value of the `field`.
+	fn required(&self, field: &str) -> Result<&str, String>
+	{
+		self.optional(field).ok_or_else(|| format!("{field} is missing"))
+	}
+
+	/// The value of the `field`, after it is `parse`d.
+	fn parse<T, E>(&self, field: &str, parse: impl FnOnce(&str) -> Result<T, E>) -> Result<T, String>
+	where
+		E: Display,
+	{
+		self.required(field).and_then(|s| parse(s).map_err(|e| format!("{field} {s:?} is invalid: {e}")))
+	}
+
+	/// The value of the `field`, after it is `parse`d, if it is present and not blank.
+	fn parse_optional<T, E>(&self, field: &str, parse: impl FnOnce(&str) -> Result<T, E>) -> Result<Option<T>, String>
+	where
+		E: Display,
+	{
+		self.optional(field).map(|_| self.parse(field, parse)).transpose()
+	}
+}
+
+/// Create an entity of the [`ImportKind`] for each row of the `request`.
+///
+/// Every row is converted before any are created, so that all of the rows which cannot be imported are reported at
+/// once. Then, the rows are [executed](batch::execute) as a single batch, where each operation is one row.
+pub async fn execute<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	headers: &HeaderMap,
+	request: Import,
+) -> ResponseResult<Post<Entity>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	/// Create a [`Response`] for a [`Code::EncodingError`] with the given `message`.
+	fn encoding(message: String) -> Response<Post<Entity>>
+	{
+		Response::from(Post::from(Status::new(Code::EncodingError, message)))
+	}
+
+	let kind = request.kind();
+
+	// nothing is looked up for a user who could not create any of the entities anyway
+	match kind
+	{
+		ImportKind::Expense => state.expense_permissions(user, Action::Create).await.map(drop)?,
+		ImportKind::Job => state.job_permissions(user, Action::Create).await.map(drop)?,
+		ImportKind::Organization => state.enforce_permission(user, Object::Organization, Action::Create).await?,
+		ImportKind::Timesheet => state.timesheet_permissions(user, Action::Create).await.map(drop)?,
+	};
+
+	let mut reader = Reader::from_reader(request.csv().as_bytes());
+	let header = reader.headers().map_err(|e| encoding(e.to_string()))?.clone();
+	let columns = columns(kind, request.columns(), &header).map_err(encoding)?;
+
+	let mut cache = Cache::default();
+	let mut errors = Vec::new();
+	let mut operations = Vec::new();
+	for record in reader.records()
+	{
+		let result = match record
+		{
+			Ok(ref r) => operation::<A>(state, user, &mut cache, kind, &Row { columns: &columns, record: r })
+				.await
+				.and_then(valid)
+				.map_err(|e| (r.position().map(|p| p.line()), e)),
+			Err(e) => Err((e.position().map(|p| p.line()), e.to_string())),
+		};
+
+		match result
+		{
+			Ok(o) => operations.push(o),
+			Err((Some(line), e)) => errors.push(format!("line {line}: {e}")),
+			Err((None, e)) => errors.push(e),
+		}
+	}
+
+	if !errors.is_empty()
+	{
+		let status = Status::new(Code::EncodingError, format!("{} of the rows could not be imported", errors.len()));
+		return Err(Response::from(Post::from(status.with_warnings(errors))));
+	}
+
+	batch::execute::<A>(state, user, headers, operations, request.dry_run()).await
+}
+
+/// The fields of each [`ImportKind`], and whether they are required.
+const fn fields(kind: ImportKind) -> &'static [(&'static str, bool)]
+{
+	match kind
+	{
+		ImportKind::Expense => &[("category", true), ("cost", true), ("description", false), ("timesheet", true)],
+		ImportKind::Job => &[
+			("client", true),
+			("date_close", false),
+			("date_open", true),
+			("departments", false),
+			("hourly_rate", true),
+			("increment", true),
+			("notes", false),
+			("objectives", true),
+		],
+		ImportKind::Organization => &[("location", true), ("name", true)],
+		ImportKind::Timesheet =>
+		{
+			&[("employee", true), ("job", true), ("time_begin", true), ("time_end", false), ("work_notes", false)]
+		},
+	}
+}
+
+/// Find the column of each field of the `kind` in the `header`, according to the `mapping` of field to header.
+fn columns(
+	kind: ImportKind,
+	mapping: &BTreeMap<String, String>,
+	header: &StringRecord,
+) -> Result<HashMap<&'static str, usize>, String>
+{
+	let fields = fields(kind);
+	if let Some(unknown) = mapping.keys().find(|k| fields.iter().all(|(f, _)| *f != k.as_str()))
+	{
+		return Err(format!("{unknown:?} is not a field of {kind:?}"));
+	}
+
+	let mut columns = HashMap::with_capacity(fields.len());
+	for (field, required) in fields
+	{
+		let name = mapping.get(*field).map_or(*field, String::as_str);
+		match header.iter().position(|h| h.trim() == name)
+		{
+			Some(i) =>
+			{
+				columns.insert(*field, i);
+			},
+			None if *required => return Err(format!("there is no {name:?} column for the {field} field")),
+			None => (),
+		}
+	}
+
+	Ok(columns)
+}
+
+/// Parse an RFC 3339 date.
+fn date(s: &str) -> Result<DateTime<Utc>, ParseError>
+{
+	DateTime::parse_from_rfc3339(s).map(|d| d.with_timezone(&Utc))
+}
+
+/// The `operation`, if it is [valid](validate::violations).
+fn valid(operation: Operation) -> Result<Operation, String>
+{
+	let violations = validate::violations(&operation);
+	if violations.is_empty()
+	{
+		return Ok(operation);
+	}
+
+	let messages = violations.iter().map(|v| format!("{}: {}", v.path(), v.message())).collect::<Vec<_>>();
+	Err(messages.join("; "))
+}
+
+/// Get the only `R::Entity` which matches the `condition` for the `key`, checking the `cache` first.
+///
+/// Only the entities which the `user` can [retrieve](scope::entity) are considered, so that an import cannot refer to
+/// (or reveal the existence of) anything else.
+///
+/// The `kind` and `by` describe what was being looked for (e.g. "organization" and "named"), for error messages.
+async fn lookup<A, R>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	cache: &mut HashMap<String, R::Entity>,
+	kind: &str,
+	by: &str,
+	key: &str,
+	condition: impl FnOnce(String) -> R::Match,
+) -> Result<R::Entity, String>
+where
+	A: Adapter,
+	R: Retrievable<Db = A::Db>,
+	R::Entity: Clone + Into<Entity>,
+{
+	if let Some(entity) = cache.get(key)
+	{
+		return Ok(entity.clone());
+	}
+
+	let retrieved = R::retrieve(state.pool(), condition(key.to_owned())).await.map_err(|e| e.to_string())?;
+	let mut found = Vec::with_capacity(retrieved.len());
+	for entity in retrieved
+	{
+		if scope::entity::<A>(state, user, entity.clone().into()).await.is_some()
+		{
+			found.push(entity);
+		}
+	}
+
+	let entity = match found.len()
+	{
+		1 => found.remove(0),
+		0 => return Err(format!("there is no {kind} {by} {key:?}")),
+		n => return Err(format!("there are {n} {kind}s {by} {key:?}")),
+	};
+
+	cache.insert(key.to_owned(), entity.clone());
+	Ok(entity)
+}
+
+/// Convert the `row` into an [`Operation`] which creates an entity of the `kind`.
+async fn operation<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	cache: &mut Cache,
+	kind: ImportKind,
+	row: &Row<'_>,
+) -> Result<Operation, String>
+where
+	A: Adapter,
+{
+	Ok(match kind
+	{
+		ImportKind::Expense => Operation::Expenses {
+			expenses: vec![(
+				row.required("category")?.to_owned(),
+				row.parse("cost", str::parse::<Money>)?,
+				row.optional("description").unwrap_or_default().to_owned(),
+			)],
+			timesheet: row.parse("timesheet", str::parse::<Id>)?.into(),
+		},
+
+		ImportKind::Job =>
+		{
+			let client = lookup::<A, A::Organization>(
+				state,
+				user,
+				&mut cache.organizations,
+				"organization",
+				"named",
+				row.required("client")?,
+				|name| MatchOrganization { name: name.into(), ..Default::default() },
+			)
+			.await?;
+
+			let mut departments = Vec::new();
+			for name in row
+				.optional("departments")
+				.into_iter()
+				.flat_map(|d| d.split(DEPARTMENT_SEPARATOR))
+				.map(str::trim)
+				.filter(|n| !n.is_empty())
+			{
+				let department = lookup::<A, A::Department>(
+					state,
+					user,
+					&mut cache.departments,
+					"department",
+					"named",
+					name,
+					|name| MatchDepartment { name: name.into(), ..Default::default() },
+				)
+				.await?;
+
+				departments.push(department.into());
+			}
+
+			Operation::Job {
+				client: client.into(),
+				date_close: row.parse_optional("date_close", date)?,
+				date_open: row.parse("date_open", date)?,
+				departments,
+				increment: row.parse("increment", humantime::parse_duration)?,
+				invoice: Invoice { date: None, hourly_rate: row.parse("hourly_rate", str::parse::<Money>)? },
+				notes: row.optional("notes").unwrap_or_default().to_owned(),
+				objectives: row.required("objectives")?.to_owned(),
+			}
+		},
+
+		ImportKind::Organization =>
+		{
+			let location = lookup::<A, A::Location>(
+				state,
+				user,
+				&mut cache.locations,
+				"location",
+				"named",
+				row.required("location")?,
+				|name| MatchLocation { name: name.into(), ..Default::default() },
+			)
+			.await?;
+
+			Operation::Organization { location: location.into(), name: row.required("name")?.to_owned() }
+		},
+
+		ImportKind::Timesheet =>
+		{
+			let employee = lookup::<A, A::Employee>(
+				state,
+				user,
+				&mut cache.employees,
+				"employee",
+				"named",
+				row.required("employee")?,
+				|name| MatchEmployee { name: name.into(), ..Default::default() },
+			)
+			.await?;
+
+			let id = row.parse("job", str::parse::<Id>)?;
+			let job = lookup::<A, A::Job>(state, user, &mut cache.jobs, "job", "with the id", &id.to_string(), |_| {
+				MatchJob { id: id.into(), ..Default::default() }
+			})
+			.await?;
+
+			Operation::Timesheet {
+				employee: employee.into(),
+				expenses: Vec::new(),
+				job: job.into(),
+				time_begin: row.parse("time_begin", date)?,
+				time_end: row.parse_optional("time_end", date)?,
+				work_notes: row.optional("work_notes").unwrap_or_default().to_owned(),
+			}
+		},
+	})
+}
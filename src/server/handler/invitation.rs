@@ -0,0 +1,100 @@
+//! Contains the creation and redemption of [`Invitation`]s, which let somebody become a [`User`] with a password that
+//! they choose themselves.
+
+use core::time::Duration;
+
+use sqlx::{Database, Executor};
+use winvoice_adapter::Retrievable;
+use winvoice_match::MatchEmployee;
+use winvoice_schema::{
+	chrono::{self, Utc},
+	Employee,
+};
+
+use super::{authorize, start_session, Token};
+use crate::{
+	api::{response::Put, routes, Code, EventKind, Status},
+	schema::{Adapter, Invitation, InvitationAdapter, Role, User, UserAdapter},
+	server::{
+		auth::{AuthContext, DbUserStore, UserStore},
+		response::{LoginResponse, Response, ResponseResult},
+		ServerState,
+	},
+};
+
+/// Create an [`Invitation`] for somebody to become a user with the `employee` (if any), `role`, and `username`, which
+/// expires after the `ttl`. Responds with the invitation and its link.
+pub async fn create<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	employee: Option<Employee>,
+	role: Role,
+	ttl: Duration,
+	username: String,
+) -> ResponseResult<Put<Invitation>>
+where
+	A: Adapter,
+{
+	let code = authorize::user(state, user, employee.as_ref()).await?;
+	let expires = chrono::Duration::from_std(ttl)
+		.map(|d| Utc::now() + d)
+		.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+
+	let (invitation, token) = A::Invitation::create(state.pool(), employee.map(|e| e.id), expires, role.id(), username)
+		.await
+		.map_err(|e| Response::from(Put::from(Status::from(&e))))?;
+
+	let link = format!("{}?token={token}", routes::INVITATION_ACCEPT);
+	Ok(Response::from(Put::new(invitation.with_link(link).into(), code.into())))
+}
+
+/// Redeem the [`Invitation`] which the `token` is for by creating its [`User`] with the `password`, and then log in
+/// as them.
+pub async fn redeem<A>(
+	state: &ServerState<A::Db>,
+	auth: &mut AuthContext<A::Db>,
+	Token { token }: Token,
+	password: String,
+) -> Result<LoginResponse, LoginResponse>
+where
+	A: Adapter,
+	DbUserStore<A::Db>: UserStore,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	if password.trim().is_empty()
+	{
+		const CODE: Code = Code::ValidationError;
+		return Err(LoginResponse::new(CODE.into(), Status::new(CODE, "The password must not be empty".into()), None));
+	}
+
+	let mut tx = state.pool().begin().await?;
+	let Some(invitation) = A::Invitation::redeem(&mut *tx, &token).await?
+	else
+	{
+		return Err(LoginResponse::invalid_credentials(Some("There is no such invitation, or it has expired".into())));
+	};
+
+	let missing = |kind: &str| {
+		let message = format!("The {kind} of the invitation for {} no longer exists", invitation.username());
+		LoginResponse::from(Status::new(Code::InvalidReference, message))
+	};
+
+	let role =
+		A::Role::retrieve(state.pool(), invitation.role_id().into()).await?.pop().ok_or_else(|| missing("role"))?;
+	let employee = match invitation.employee_id()
+	{
+		Some(id) => A::Employee::retrieve(state.pool(), MatchEmployee { id: id.into(), ..Default::default() })
+			.await?
+			.pop()
+			.map(Some)
+			.ok_or_else(|| missing("employee"))?,
+		None => None,
+	};
+
+	let user = A::User::create(&mut *tx, employee, password, role, invitation.username().into()).await?;
+	state.publish(&mut tx, EventKind::Create, [user.clone()]).await?;
+	tx.commit().await?;
+
+	tracing::info!("User {} was created by redeeming an invitation", user.username());
+	start_session(auth, user).await
+}
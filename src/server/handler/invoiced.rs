@@ -0,0 +1,102 @@
+//! Contains functions which lock the [`Timesheet`]s and [`Expense`]s of [`Job`](winvoice_schema::Job)s whose invoice
+//! has been issued.
+
+use std::collections::HashSet;
+
+use sqlx::{Database, Pool};
+use winvoice_adapter::Retrievable;
+use winvoice_match::Match;
+use winvoice_schema::{Expense, Id, Timesheet};
+
+use crate::{
+	api::{Code, Status},
+	permissions::{Action, Object},
+	schema::{Adapter, User},
+	server::{response::Response, ServerState},
+};
+
+/// Ensure that the `user` can perform the `action` on [`Object::InvoicedJob`], if the job with the given `id` has been
+/// invoiced.
+pub async fn enforce<A, R>(state: &ServerState<A::Db>, user: &User, action: Action, id: Id) -> Result<(), Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	let jobs = A::Job::retrieve(state.pool(), id.into()).await.map_err(|e| Response::from(R::from(Status::from(&e))))?;
+	if jobs.iter().any(|j| j.invoice.date.is_some())
+	{
+		state.enforce_permission(user, Object::InvoicedJob, action).await?;
+	}
+
+	Ok(())
+}
+
+/// The [`Id`]s of the `entities` which either belong to, or would be moved to, a [`Timesheet`] of an invoiced job.
+pub async fn expenses<A>(pool: &Pool<A::Db>, entities: &[Expense]) -> sqlx::Result<HashSet<Id>>
+where
+	A: Adapter,
+{
+	if entities.is_empty()
+	{
+		return Ok(HashSet::new());
+	}
+
+	let existing = A::Expenses::retrieve(pool, entities.iter().map(|x| x.id).collect::<Match<_>>().into()).await?;
+	let invoiced = A::Timesheet::retrieve(
+		pool,
+		entities.iter().chain(existing.iter()).map(|x| x.timesheet_id).collect::<Match<_>>().into(),
+	)
+	.await
+	.map(|vec| vec.into_iter().filter(|t| t.job.invoice.date.is_some()).map(|t| t.id).collect::<HashSet<_>>())?;
+
+	Ok(entities.iter().chain(existing.iter()).filter(|x| invoiced.contains(&x.timesheet_id)).map(|x| x.id).collect())
+}
+
+/// Remove the `entities` whose [`Id`] is `locked`, unless the `user` has permission to perform the `action` on
+/// [`Object::InvoicedJob`].
+///
+/// Returns `true` if any `entities` were removed.
+pub async fn retain<Db, R, T>(
+	state: &ServerState<Db>,
+	user: &User,
+	action: Action,
+	entities: &mut Vec<T>,
+	locked: &HashSet<Id>,
+	id: impl Fn(&T) -> Id,
+) -> Result<bool, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	if locked.is_empty() || state.has_permission(user, Object::InvoicedJob, action).await?
+	{
+		return Ok(false);
+	}
+
+	entities.retain(|e| !locked.contains(&id(e)));
+	Ok(true)
+}
+
+/// The [`Id`]s of the `entities` which either belong to, or would be moved to, an invoiced job.
+pub async fn timesheets<A>(pool: &Pool<A::Db>, entities: &[Timesheet]) -> sqlx::Result<HashSet<Id>>
+where
+	A: Adapter,
+{
+	if entities.is_empty()
+	{
+		return Ok(HashSet::new());
+	}
+
+	let (existing, invoiced) = futures::try_join!(
+		A::Timesheet::retrieve(pool, entities.iter().map(|t| t.id).collect::<Match<_>>().into()),
+		A::Job::retrieve(pool, entities.iter().map(|t| t.job.id).collect::<Match<_>>().into()),
+	)?;
+
+	let invoiced = invoiced.into_iter().filter(|j| j.invoice.date.is_some()).map(|j| j.id).collect::<HashSet<_>>();
+	Ok(existing
+		.into_iter()
+		.filter(|t| t.job.invoice.date.is_some())
+		.map(|t| t.id)
+		.chain(entities.iter().filter(|t| invoiced.contains(&t.job.id)).map(|t| t.id))
+		.collect())
+}
@@ -0,0 +1,63 @@
+//! Contains the authentication of users with an [LDAP directory](crate::server::Ldap).
+
+use axum::headers::authorization::Basic;
+use sqlx::{Database, Executor};
+use winvoice_adapter::Retrievable;
+
+use super::external_user;
+use crate::{
+	r#match::MatchUser,
+	schema::{Adapter, IdentityAdapter, User},
+	server::{response::LoginResponse, Ldap, ServerState},
+};
+
+/// Authenticate the `credentials` with the `ldap` directory, and return the [`User`] whose identity there (i.e. their
+/// DN) they are for (see [`external_user`]).
+///
+/// Returns [`None`] if the directory does not accept the credentials, cannot be reached, or accepts them for a user who
+/// only exists in the database (e.g. the first user), so that they may be checked against the password hashes in the
+/// database instead. Those users are never logged in by the directory, since it does not manage them.
+pub async fn authenticate<A>(
+	state: &ServerState<A::Db>,
+	ldap: &Ldap,
+	credentials: &Basic,
+) -> Result<Option<User>, LoginResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let username = credentials.username();
+	let groups = match ldap.authenticate(username, credentials.password()).await
+	{
+		Ok(Some(groups)) => groups,
+		Ok(None) => return Ok(None),
+		Err(e) =>
+		{
+			tracing::error!("Could not authenticate user {username} with the LDAP directory: {e}");
+			return Ok(None);
+		},
+	};
+
+	let dn = ldap.dn(username);
+	if A::Identity::find(state.pool(), ldap.url(), &dn).await?.is_none()
+	{
+		let condition = MatchUser { username: username.to_owned().into(), ..Default::default() };
+		if !A::User::retrieve(state.pool(), condition).await?.is_empty()
+		{
+			tracing::info!("User {username} is not managed by the LDAP directory, so their password is checked");
+			return Ok(None);
+		}
+	}
+
+	external_user::<A>(state, ldap.url(), &dn, username, &groups, ldap.provision()).await.map(Some)
+}
+
+/// Whether the `user` is managed by the `ldap` directory, and so must not be authenticated by the password hash in the
+/// database (which nobody knows, and which the directory would not know to change when the user is disabled there).
+pub async fn managed<A>(state: &ServerState<A::Db>, ldap: &Ldap, user: &User) -> Result<bool, LoginResponse>
+where
+	A: Adapter,
+{
+	let identities = A::Identity::retrieve(state.pool(), &[user.id()]).await?;
+	Ok(identities.iter().any(|i| i.issuer() == ldap.url()))
+}
@@ -0,0 +1,129 @@
+//! Contains the transitions which a [`Job`] may go through over its lifetime.
+
+use std::collections::HashMap;
+
+use winvoice_adapter::Retrievable;
+use winvoice_match::Match;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	InvoiceDate,
+	Job,
+};
+
+use crate::{
+	api::{Code, Status},
+	schema::Adapter,
+	server::{response::Response, ServerState},
+};
+
+/// A function which moves a [`Job`] into a different state at a given time, or returns why it cannot.
+pub type Transition = fn(&mut Job, DateTime<Utc>) -> Result<(), &'static str>;
+
+/// Close the `job` at the given time.
+pub fn close(job: &mut Job, now: DateTime<Utc>) -> Result<(), &'static str>
+{
+	match job.date_close
+	{
+		Some(_) => Err("it is already closed"),
+		None =>
+		{
+			job.date_close = Some(now);
+			Ok(())
+		},
+	}
+}
+
+/// Refuse to update the `patched` jobs if any of them would be closed, reopened, issued, or paid by doing so, since
+/// that must be done by a [`Transition`] instead.
+pub async fn guard<A, R>(state: &ServerState<A::Db>, patched: &[Job]) -> Result<(), Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	if patched.is_empty()
+	{
+		return Ok(());
+	}
+
+	let existing = A::Job::retrieve(state.pool(), patched.iter().map(|j| j.id).collect::<Match<_>>().into())
+		.await
+		.map_err(|e| Response::from(R::from(Status::from(&e))))?
+		.into_iter()
+		.map(|j| (j.id, j))
+		.collect::<HashMap<_, _>>();
+
+	// the database is less precise than `DateTime`, so the dates are compared at its precision
+	let micros = |date: Option<DateTime<Utc>>| date.map(|d| d.timestamp_micros());
+	let invalid = patched
+		.iter()
+		.filter(|p| {
+			existing.get(&p.id).map_or(false, |e| {
+				micros(e.date_close) != micros(p.date_close) ||
+					micros(e.invoice.date.as_ref().map(|d| d.issued)) !=
+						micros(p.invoice.date.as_ref().map(|d| d.issued)) ||
+					micros(e.invoice.date.as_ref().and_then(|d| d.paid)) !=
+						micros(p.invoice.date.as_ref().and_then(|d| d.paid))
+			})
+		})
+		.map(|p| format!("job {} can only be closed, reopened, issued, or paid through its lifecycle routes", p.id))
+		.collect::<Vec<_>>();
+
+	match invalid.is_empty()
+	{
+		true => Ok(()),
+		false => Err(Response::from(R::from(Status::new(Code::InvalidTransition, invalid.join("; "))))),
+	}
+}
+
+/// Mark the `job`'s invoice as issued at the given time.
+pub fn issue(job: &mut Job, now: DateTime<Utc>) -> Result<(), &'static str>
+{
+	if job.date_close.is_none()
+	{
+		return Err("it has not been closed");
+	}
+
+	match job.invoice.date
+	{
+		Some(_) => Err("its invoice has already been issued"),
+		None =>
+		{
+			job.invoice.date = Some(InvoiceDate { issued: now, paid: None });
+			Ok(())
+		},
+	}
+}
+
+/// Mark the `job`'s invoice as paid at the given time.
+pub fn pay(job: &mut Job, now: DateTime<Utc>) -> Result<(), &'static str>
+{
+	match job.invoice.date.as_mut()
+	{
+		None => Err("its invoice has not been issued"),
+		Some(date) if date.paid.is_some() => Err("its invoice has already been paid"),
+		Some(date) =>
+		{
+			date.paid = Some(now);
+			Ok(())
+		},
+	}
+}
+
+/// Reopen the `job`, so long as its invoice has not been issued.
+pub fn reopen(job: &mut Job, _: DateTime<Utc>) -> Result<(), &'static str>
+{
+	if job.invoice.date.is_some()
+	{
+		return Err("its invoice has already been issued");
+	}
+
+	match job.date_close
+	{
+		None => Err("it is not closed"),
+		Some(_) =>
+		{
+			job.date_close = None;
+			Ok(())
+		},
+	}
+}
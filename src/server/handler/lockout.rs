@@ -0,0 +1,93 @@
+//! Contains the [`guard`] which keeps at least one [`User`] able to manage users and [`Role`](crate::schema::Role)s,
+//! so that changes to users cannot lock everyone out of changing their permissions.
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::Database;
+use winvoice_adapter::Retrievable;
+use winvoice_match::Match;
+
+use crate::{
+	api::{Code, Status},
+	permissions::{Action, Object},
+	r#match::{MatchRole, MatchUser},
+	schema::{Adapter, User},
+	server::{response::Response, ServerState},
+};
+
+/// The [`Object`]s which some [`User`] must always be able to [update](Action::Update).
+const MANAGED: [Object; 2] = [Object::User, Object::Role];
+
+/// Refuse to delete the `deleted` users or to make the `updated` users if, afterwards, nobody could update one of
+/// the [`MANAGED`] objects when somebody could before.
+pub async fn guard<A, R>(state: &ServerState<A::Db>, deleted: &[User], updated: &[User]) -> Result<(), Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	if deleted.is_empty() && updated.is_empty()
+	{
+		return Ok(());
+	}
+
+	let database = |e: sqlx::Error| Response::from(R::from(Status::from(&e)));
+	let changed = deleted.iter().chain(updated).map(User::id).collect::<HashSet<_>>();
+	let (before, unchanged): (Vec<_>, Vec<_>) = A::User::retrieve(state.pool(), MatchUser::default())
+		.await
+		.map_err(database)?
+		.into_iter()
+		.partition(|u| changed.contains(&u.id()));
+
+	// the role of an updated user is the one it will have, not whatever name the request gave it
+	let roles = match updated.is_empty()
+	{
+		true => HashMap::new(),
+		false => A::Role::retrieve(state.pool(), MatchRole {
+			id: updated.iter().map(|u| u.role().id()).collect::<Match<_>>(),
+			..Default::default()
+		})
+		.await
+		.map_err(database)?
+		.into_iter()
+		.map(|r| (r.id(), r))
+		.collect::<HashMap<_, _>>(),
+	};
+
+	let after = updated
+		.iter()
+		.filter_map(|u| roles.get(&u.role().id()).map(|r| User { role: r.clone(), ..u.clone() }))
+		.collect::<Vec<_>>();
+
+	for object in MANAGED
+	{
+		// none of the changed users could update it to begin with, one of them still can, or somebody else can
+		if !any(state, &before, object).await? ||
+			any(state, &after, object).await? ||
+			any(state, &unchanged, object).await?
+		{
+			continue;
+		}
+
+		let message = format!("Afterwards, no user would be able to update {object}");
+		return Err(Response::from(R::from(Status::new(Code::LastManager, message))));
+	}
+
+	Ok(())
+}
+
+/// Whether any of the `users` can update the `object`.
+async fn any<Db, R>(state: &ServerState<Db>, users: &[User], object: Object) -> Result<bool, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	for user in users
+	{
+		if state.has_permission(user, object, Action::Update).await?
+		{
+			return Ok(true);
+		}
+	}
+
+	Ok(false)
+}
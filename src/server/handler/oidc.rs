@@ -0,0 +1,122 @@
+//! Contains the logins through an [OpenID Connect](crate::server::Oidc) identity provider.
+
+use axum_login::axum_sessions::SessionHandle;
+use serde::Deserialize;
+use sqlx::{Database, Executor};
+use winvoice_schema::Id;
+
+use super::{external_user, start_session};
+use crate::{
+	api::{response::Redirect, Code, Status},
+	schema::{Adapter, Identity, IdentityAdapter, User},
+	server::{
+		auth::{AuthContext, DbUserStore, UserStore},
+		oidc::{Claims, Pending},
+		response::{LoginResponse, Response},
+		Oidc,
+		ServerState,
+	},
+};
+
+/// The key of the session which the [`Pending`] login of a client is kept under.
+const PENDING: &str = "oidc_pending";
+
+/// The query parameters which the identity provider sends a user back with.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct Callback
+{
+	/// The code which is exchanged for the claims of the user.
+	code: String,
+
+	/// Which login is being finished.
+	state: String,
+}
+
+/// Start a login through the `oidc` identity provider, and respond with the URL which the client should go to.
+///
+/// The login is kept in the `session` of the client, so that only it can [finish](callback) the login. When a `user`
+/// is logged in already, the identity which the provider vouches for is [linked](link) to them instead.
+pub async fn authorize(session: &SessionHandle, oidc: &Oidc, user: Option<&User>) -> Response<Redirect>
+{
+	let (url, pending) = oidc.authorize(user.map(User::id));
+	if let Err(e) = session.write().await.insert(PENDING, pending)
+	{
+		tracing::error!("Could not keep a login through the identity provider in the session: {e}");
+		return Response::from(Redirect::new(Status::new(Code::EncodingError, e.to_string()), None));
+	}
+
+	Response::from(Redirect::new(Code::Success.into(), Some(url)))
+}
+
+/// Finish the login which the client started with its `session`, and log in as the [`User`] whose identity the
+/// `oidc` identity provider claims (see [`external_user`]).
+pub async fn callback<A>(
+	state: &ServerState<A::Db>,
+	auth: &mut AuthContext<A::Db>,
+	session: &SessionHandle,
+	oidc: &Oidc,
+	Callback { code, state: login }: Callback,
+) -> Result<LoginResponse, LoginResponse>
+where
+	A: Adapter,
+	DbUserStore<A::Db>: UserStore,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	// the session is released before logging in, which writes to it as well
+	let pending = {
+		let mut session = session.write().await;
+		let pending = session.get::<Pending>(PENDING);
+		session.remove(PENDING);
+		pending
+	};
+
+	let link_to = pending.as_ref().and_then(Pending::link);
+	let claims = oidc.claims(&code, &login, pending).await?;
+	if let Some(id) = link_to
+	{
+		return link::<A>(state, auth, id, &claims).await;
+	}
+
+	let (issuer, subject) = (claims.issuer(), claims.subject());
+	let user = external_user::<A>(state, issuer, subject, claims.username(), claims.groups(), oidc.provision()).await?;
+	start_session(auth, user).await
+}
+
+/// Link the identity in the `claims` to the [`User`] with the `id`, who started linking it, so that they can log in
+/// with it from then on.
+///
+/// The user must still be logged in, and the identity must not already be linked to anybody else.
+async fn link<A>(
+	state: &ServerState<A::Db>,
+	auth: &AuthContext<A::Db>,
+	id: Id,
+	claims: &Claims,
+) -> Result<LoginResponse, LoginResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let user = auth.current_user.clone().filter(|u| u.id() == id).ok_or_else(|| {
+		let message = "The user who started linking an identity is no longer logged in".to_owned();
+		LoginResponse::invalid_credentials(Some(message))
+	})?;
+
+	match A::Identity::find(state.pool(), claims.issuer(), claims.subject()).await?
+	{
+		Some(i) if i.user_id() == user.id() => (),
+		Some(_) =>
+		{
+			tracing::info!("User {} attempted to link an identity which belongs to someone else", user.username());
+			let message = format!("The identity at {} is linked to another user", claims.issuer());
+			return Err(LoginResponse::invalid_credentials(Some(message)));
+		},
+		None =>
+		{
+			let identity = Identity::new(claims.issuer().to_owned(), claims.subject().to_owned(), user.id());
+			A::Identity::link(state.pool(), &identity).await?;
+			tracing::info!("User {} linked their identity at {}", user.username(), claims.issuer());
+		},
+	}
+
+	Ok(LoginResponse::from(user))
+}
@@ -0,0 +1,159 @@
+//! Contains the detection of [`Timesheet`]s which cover the same time for the same employee.
+
+mod display;
+
+use axum::http::HeaderMap;
+use sqlx::Pool;
+use winvoice_adapter::Retrievable;
+use winvoice_match::{Match, MatchEmployee, MatchTimesheet};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+	Timesheet,
+};
+
+use crate::{
+	api::{self, Code, Status},
+	server::response::Response,
+};
+
+/// The span of time which a [`Timesheet`] covers (or would cover, if it has not been created yet).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Interval
+{
+	/// The [`Id`] of the [`Employee`](winvoice_schema::Employee) which the timesheet is for.
+	employee_id: Id,
+
+	/// The [`Id`] of the [`Timesheet`], if it already exists.
+	id: Option<Id>,
+
+	/// See [`Timesheet::time_begin`].
+	time_begin: DateTime<Utc>,
+
+	/// See [`Timesheet::time_end`]. [`None`] means that the timesheet is still running.
+	time_end: Option<DateTime<Utc>>,
+}
+
+impl Interval
+{
+	/// Create a new [`Interval`].
+	pub const fn new(
+		employee_id: Id,
+		id: Option<Id>,
+		time_begin: DateTime<Utc>,
+		time_end: Option<DateTime<Utc>>,
+	) -> Self
+	{
+		Self { employee_id, id, time_begin, time_end }
+	}
+
+	/// Whether `self` and `other` belong to the same employee and cover some of the same time.
+	pub fn overlaps(&self, other: &Self) -> bool
+	{
+		self.employee_id == other.employee_id &&
+			self.time_end.map_or(true, |end| other.time_begin < end) &&
+			other.time_end.map_or(true, |end| self.time_begin < end)
+	}
+}
+
+impl From<&Timesheet> for Interval
+{
+	fn from(timesheet: &Timesheet) -> Self
+	{
+		Self::new(timesheet.employee.id, Some(timesheet.id), timesheet.time_begin, timesheet.time_end)
+	}
+}
+
+/// Two [`Interval`]s which [overlap](Interval::overlaps).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Overlap(Interval, Interval);
+
+/// Find every [`Overlap`] between the `candidates` and the existing timesheets of the same employees, as well as
+/// between the `candidates` themselves.
+///
+/// Existing timesheets which share an [`Id`] with a candidate are assumed to be replaced by that candidate.
+pub async fn find<T>(pool: &Pool<T::Db>, candidates: &[Interval]) -> sqlx::Result<Vec<Overlap>>
+where
+	T: Retrievable<Entity = Timesheet, Match = MatchTimesheet>,
+{
+	if candidates.is_empty()
+	{
+		return Ok(Vec::new());
+	}
+
+	let existing = T::retrieve(pool, MatchTimesheet {
+		employee: MatchEmployee {
+			id: Match::Or(candidates.iter().map(|c| c.employee_id.into()).collect()),
+			..Default::default()
+		},
+		..Default::default()
+	})
+	.await?;
+
+	let mut overlaps = Vec::new();
+	candidates.iter().enumerate().for_each(|(i, c)| {
+		overlaps.extend(
+			existing
+				.iter()
+				.map(Interval::from)
+				.filter(|e| candidates.iter().all(|c2| c2.id.is_none() || c2.id != e.id))
+				.chain(candidates.iter().skip(i + 1).copied())
+				.filter(|other| c.overlaps(other))
+				.map(|other| Overlap(*c, other)),
+		)
+	});
+
+	Ok(overlaps)
+}
+
+/// Decide what to do with the `overlaps` that were [found](find).
+///
+/// If the [`api::OVERLAP_HEADER`] is set to [`api::OVERLAP_WARN`], the `overlaps` are returned as warnings.
+/// Otherwise, any overlap results in an [`Err`] with [`Code::TimesheetOverlap`].
+pub fn resolve<R>(headers: &HeaderMap, overlaps: Vec<Overlap>) -> Result<Vec<String>, Response<R>>
+where
+	R: AsRef<Code> + From<Status>,
+{
+	if overlaps.is_empty()
+	{
+		return Ok(Vec::new());
+	}
+
+	let messages = overlaps.into_iter().map(ToString::to_string).collect::<Vec<_>>();
+	match headers.get(api::OVERLAP_HEADER).map_or(false, |h| h == api::OVERLAP_WARN)
+	{
+		true => Ok(messages),
+		false => Err(Response::from(R::from(Status::new(Code::TimesheetOverlap, messages.join("; "))))),
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use winvoice_schema::chrono::{Duration, Utc};
+
+	use super::{Id, Interval};
+
+	#[test]
+	fn overlaps()
+	{
+		let employee = Id::new_v4();
+		let now = Utc::now();
+		let interval = |begin: i64, end: Option<i64>| {
+			Interval::new(employee, None, now + Duration::hours(begin), end.map(|e| now + Duration::hours(e)))
+		};
+
+		assert!(interval(0, Some(2)).overlaps(&interval(1, Some(3))));
+		assert!(interval(0, None).overlaps(&interval(5, Some(6))));
+		assert!(interval(5, Some(6)).overlaps(&interval(0, None)));
+		assert!(interval(0, Some(4)).overlaps(&interval(1, Some(2))));
+
+		// touching is not overlapping
+		assert!(!interval(0, Some(2)).overlaps(&interval(2, Some(3))));
+		assert!(!interval(2, None).overlaps(&interval(0, Some(2))));
+
+		// different employees never overlap
+		let other = Interval::new(Id::new_v4(), None, now, Some(now + Duration::hours(1)));
+		assert!(!interval(0, Some(2)).overlaps(&other));
+	}
+}
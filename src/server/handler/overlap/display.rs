@@ -0,0 +1,30 @@
+use core::fmt::{Display, Formatter, Result};
+
+use super::{Interval, Overlap};
+
+impl Display for Interval
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result
+	{
+		match self.id
+		{
+			Some(id) => write!(f, "timesheet {id}")?,
+			None => "new timesheet".fmt(f)?,
+		};
+
+		write!(f, " for employee {} (beginning {}", self.employee_id, self.time_begin)?;
+		match self.time_end
+		{
+			Some(end) => write!(f, ", ending {end})"),
+			None => ", still running)".fmt(f),
+		}
+	}
+}
+
+impl Display for Overlap
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result
+	{
+		write!(f, "{} overlaps with {}", self.0, self.1)
+	}
+}
@@ -15,6 +15,9 @@ pub enum Reason
 	/// Another resource depends on it.
 	ResourceConstraint,
 
+	/// The resource was created by the user, and users may not review their own work.
+	ResourceCreated,
+
 	/// The specified resource already exists.
 	ResourceExists,
 }
@@ -12,6 +12,7 @@ impl Display for Reason
 			Self::NoEmployee => "they have no employee record",
 			Self::NoResourceExists => "no such resource exists",
 			Self::ResourceConstraint => "another resource depends on it",
+			Self::ResourceCreated => "they may not review what they created",
 			Self::ResourceExists => "that resource already exists",
 		}
 		.fmt(f)
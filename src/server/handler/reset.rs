@@ -0,0 +1,150 @@
+//! Contains the requests to reset forgotten passwords, and their redemption.
+
+use sqlx::{Database, Executor};
+use winvoice_adapter::{Retrievable, Updatable};
+use winvoice_schema::chrono::{self, Utc};
+
+use super::{start_session, Token};
+use crate::{
+	api::{routes, Code, EventKind, Status},
+	r#match::MatchUser,
+	schema::{Adapter, IdentityAdapter, PasswordResetAdapter, User},
+	server::{
+		auth::{AuthContext, DbUserStore, UserStore},
+		response::LoginResponse,
+		PasswordResets,
+		ServerState,
+	},
+};
+
+/// The subject of the message which delivers the link that resets a password.
+const SUBJECT: &str = "Reset your Winvoice password";
+
+/// Deliver a link which resets the password of the user with the `username` (if there is one) through the `resets`'
+/// [`Notifier`](crate::server::Notifier).
+///
+/// Always responds the same way, so that it cannot be used to find out which users exist. Any failures are logged
+/// instead. Users who log in through an identity provider (e.g. an LDAP directory) are not sent a link, since the
+/// provider manages their password.
+pub async fn request<A>(state: &ServerState<A::Db>, resets: &PasswordResets, username: String) -> LoginResponse
+where
+	A: Adapter,
+{
+	let user = match A::User::retrieve(state.pool(), MatchUser { username: username.into(), ..Default::default() })
+		.await
+		.map(|mut v| v.pop())
+	{
+		Ok(Some(u)) => u,
+		Ok(None) => return LoginResponse::from(Code::Success),
+		Err(e) =>
+		{
+			tracing::error!("Failed to retrieve the user whose password is being reset: {e}");
+			return LoginResponse::from(Code::Success);
+		},
+	};
+
+	match A::Identity::retrieve(state.pool(), &[user.id()]).await
+	{
+		Ok(identities) if identities.is_empty() => (),
+		Ok(_) =>
+		{
+			tracing::info!("User {} requested to reset a password which an identity provider manages", user.username());
+			return LoginResponse::from(Code::Success);
+		},
+		Err(e) =>
+		{
+			tracing::error!("Failed to retrieve the identities of user {}: {e}", user.username());
+			return LoginResponse::from(Code::Success);
+		},
+	}
+
+	let token = match chrono::Duration::from_std(resets.ttl())
+	{
+		Ok(ttl) =>
+		{
+			A::PasswordReset::create(state.pool(), user.id(), Utc::now() + ttl).await.map_err(|e| e.to_string())
+		},
+		Err(e) => Err(e.to_string()),
+	};
+
+	match token
+	{
+		Ok(token) =>
+		{
+			tracing::info!("User {} requested to reset their password", user.username());
+			let resets = resets.clone();
+			let body = format!(
+				"Somebody asked to reset the password of {}. To choose a new one, use {}?token={token} within {}. If \
+				 it was not you, nothing needs to be done.",
+				user.username(),
+				routes::PASSWORD_RESET_REDEEM,
+				humantime::format_duration(resets.ttl()),
+			);
+
+			// delivery may be slow, and how long it takes should not reveal that the user exists
+			tokio::spawn(async move {
+				if let Err(e) = resets.notifier().notify(&user, SUBJECT, &body).await
+				{
+					tracing::error!("Failed to deliver the password reset of user {}: {e}", user.username());
+				}
+			});
+		},
+		Err(e) => tracing::error!("Failed to create a password reset for user {}: {e}", user.username()),
+	}
+
+	LoginResponse::from(Code::Success)
+}
+
+/// Set the `password` of the [`User`] whose password the `token` resets, and then log in as them.
+///
+/// Because sessions are tied to the password, this logs out every other session of the user. The password of a user
+/// who logs in through an identity provider cannot be reset, even if a token was issued before they linked it.
+pub async fn redeem<A>(
+	state: &ServerState<A::Db>,
+	auth: &mut AuthContext<A::Db>,
+	Token { token }: Token,
+	password: String,
+) -> Result<LoginResponse, LoginResponse>
+where
+	A: Adapter,
+	DbUserStore<A::Db>: UserStore,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	if password.trim().is_empty()
+	{
+		const CODE: Code = Code::ValidationError;
+		return Err(LoginResponse::new(CODE.into(), Status::new(CODE, "The password must not be empty".into()), None));
+	}
+
+	let mut tx = state.pool().begin().await?;
+	let Some(id) = A::PasswordReset::redeem(&mut *tx, &token).await?
+	else
+	{
+		let message = "There is no such password reset, or it has expired";
+		return Err(LoginResponse::invalid_credentials(Some(message.into())));
+	};
+
+	let Some(mut user) = A::User::retrieve(state.pool(), MatchUser { id: id.into(), ..Default::default() }).await?.pop()
+	else
+	{
+		return Err(LoginResponse::invalid_credentials(None));
+	};
+
+	if !A::Identity::retrieve(state.pool(), &[user.id()]).await?.is_empty()
+	{
+		tracing::info!("User {} attempted to reset a password which an identity provider manages", user.username());
+		let message = "The password of a user who logs in through an identity provider cannot be reset";
+		return Err(LoginResponse::invalid_credentials(Some(message.into())));
+	}
+
+	user.password = password;
+	user.hash_password()?;
+	user.password_set = Utc::now();
+
+	A::User::update(&mut tx, [&user].into_iter()).await?;
+	state.publish(&mut tx, EventKind::Update, [user.clone()]).await?;
+	tx.commit().await?;
+
+	tracing::info!("User {} reset their password", user.username());
+	start_session(auth, user).await
+}
@@ -0,0 +1,289 @@
+//! Contains the review of [`Timesheet`]s, which are submitted by whoever can [submit](Action::Submit) them, and then
+//! approved or rejected by whoever can [approve](Action::Approve) them.
+
+use std::collections::HashSet;
+
+use sqlx::{Database, Executor, Pool};
+use winvoice_adapter::Retrievable;
+use winvoice_match::{Match, MatchDepartment, MatchTimesheet};
+use winvoice_schema::{Expense, Id, Timesheet};
+
+use super::{no_effective_perms, Reason};
+use crate::{
+	api::{response::Post, Code, Status},
+	permissions::{Action, Object},
+	schema::{Adapter, Review, ReviewAdapter, ReviewStatus, User},
+	server::{
+		response::{Response, ResponseResult},
+		ServerState,
+	},
+};
+
+/// The [`ReviewStatus`]es which a timesheet may be approved or rejected from.
+const REVIEWABLE: [ReviewStatus; 1] = [ReviewStatus::Submitted];
+
+/// The [`ReviewStatus`]es which a timesheet may be submitted from.
+const SUBMITTABLE: [ReviewStatus; 2] = [ReviewStatus::Draft, ReviewStatus::Rejected];
+
+/// Respond with the [`Review`]s of the timesheets matching the `condition` which the `user` can retrieve.
+pub async fn retrieve<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	mut condition: MatchTimesheet,
+) -> ResponseResult<Post<Review>>
+where
+	A: Adapter,
+{
+	let code = scope(state, user, Action::Retrieve, &mut condition).await?;
+	let timesheets = A::Timesheet::retrieve(state.pool(), condition).await.map_err(database)?;
+	let ids = timesheets.into_iter().map(|t| t.id).collect::<Vec<_>>();
+	let reviews = A::Review::retrieve(state.pool(), &ids).await.map_err(database)?;
+	Ok(Response::from(Post::new(reviews, code.into())))
+}
+
+/// Approve or reject the timesheets of the `reviews` which the `user` can [approve](Action::Approve), using their
+/// [`ReviewStatus`] and comment.
+///
+/// Either every requested timesheet which the user can review is changed, or none are.
+pub async fn review<A>(state: &ServerState<A::Db>, user: &User, reviews: Vec<Review>) -> ResponseResult<Post<Review>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	const ACTION: Action = Action::Approve;
+	let mut condition =
+		MatchTimesheet { id: reviews.iter().map(Review::timesheet_id).collect::<Match<_>>(), ..Default::default() };
+
+	let mut code = scope(state, user, ACTION, &mut condition).await?;
+	let timesheets = A::Timesheet::retrieve(state.pool(), condition).await.map_err(database)?;
+
+	let employee_id = user.employee().map(|e| e.id);
+	let reviewable =
+		timesheets.into_iter().filter(|t| Some(t.employee.id) != employee_id).map(|t| t.id).collect::<HashSet<_>>();
+
+	let mut invalid = Vec::new();
+	let mut changes = Vec::with_capacity(reviews.len());
+	for r in reviews
+	{
+		if !reviewable.contains(&r.timesheet_id())
+		{
+			code = Code::SuccessForPermissions;
+			continue;
+		}
+
+		match r.status()
+		{
+			ReviewStatus::Approved | ReviewStatus::Rejected =>
+			{
+				changes.push((r.timesheet_id(), r.status(), r.comment().map(ToOwned::to_owned)))
+			},
+			_ => invalid.push(format!("timesheet {} can only be approved or rejected", r.timesheet_id())),
+		}
+	}
+
+	transition::<A>(state.pool(), changes, &REVIEWABLE, "it has not been submitted", Some(user.id()), invalid)
+		.await
+		.map(|reviews| Response::from(Post::new(reviews, code.into())))
+}
+
+/// Submit the timesheets with the given `ids` which the `user` can [submit](Action::Submit) for review.
+///
+/// Either every requested timesheet which the user can submit is submitted, or none are.
+pub async fn submit<A>(state: &ServerState<A::Db>, user: &User, ids: Vec<Id>) -> ResponseResult<Post<Review>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let mut condition = MatchTimesheet { id: ids.into_iter().collect::<Match<_>>(), ..Default::default() };
+	let code = scope(state, user, Action::Submit, &mut condition).await?;
+	let timesheets = A::Timesheet::retrieve(state.pool(), condition).await.map_err(database)?;
+
+	let (running, stopped): (Vec<_>, Vec<_>) = timesheets.into_iter().partition(|t| t.time_end.is_none());
+	let invalid =
+		running.into_iter().map(|t| format!("timesheet {} cannot be submitted: it is running", t.id)).collect();
+	let changes = stopped.into_iter().map(|t| (t.id, ReviewStatus::Submitted, None)).collect();
+
+	transition::<A>(state.pool(), changes, &SUBMITTABLE, "it has already been submitted", None, invalid)
+		.await
+		.map(|reviews| Response::from(Post::new(reviews, code.into())))
+}
+
+/// Remove the `entities` which either belong to, or would be moved to, a [`locked`] timesheet, unless the `user` can
+/// [approve](Action::Approve) that timesheet.
+///
+/// Returns `true` if any `entities` were removed.
+pub async fn retain_expenses<A, R>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	entities: &mut Vec<Expense>,
+) -> Result<bool, Response<R>>
+where
+	A: Adapter,
+	R: AsRef<Code> + From<Status>,
+{
+	if entities.is_empty()
+	{
+		return Ok(false);
+	}
+
+	let error = |e: sqlx::Error| Response::from(R::from(Status::from(&e)));
+	let existing = A::Expenses::retrieve(state.pool(), entities.iter().map(|x| x.id).collect::<Match<_>>().into())
+		.await
+		.map_err(error)?;
+
+	let timesheets = A::Timesheet::retrieve(
+		state.pool(),
+		entities.iter().chain(existing.iter()).map(|x| x.timesheet_id).collect::<Match<_>>().into(),
+	)
+	.await
+	.map_err(error)?;
+
+	let locked = locked::<A>(state.pool(), &timesheets).await.map_err(error)?;
+	let mut unlocked = timesheets;
+	if !retain(state, user, &mut unlocked, &locked).await?
+	{
+		return Ok(false);
+	}
+
+	let unlocked = unlocked.into_iter().map(|t| t.id).collect::<HashSet<_>>();
+	let removed = entities
+		.iter()
+		.chain(existing.iter())
+		.filter(|x| locked.contains(&x.timesheet_id) && !unlocked.contains(&x.timesheet_id))
+		.map(|x| x.id)
+		.collect::<HashSet<_>>();
+
+	entities.retain(|x| !removed.contains(&x.id));
+	Ok(true)
+}
+
+/// The [`Id`]s of the `entities` which have been submitted for review or approved, and so may only be changed by
+/// someone who can [approve](Action::Approve) them.
+pub async fn locked<A>(pool: &Pool<A::Db>, entities: &[Timesheet]) -> sqlx::Result<HashSet<Id>>
+where
+	A: Adapter,
+{
+	if entities.is_empty()
+	{
+		return Ok(HashSet::new());
+	}
+
+	let ids = entities.iter().map(|t| t.id).collect::<Vec<_>>();
+	A::Review::retrieve(pool, &ids)
+		.await
+		.map(|vec| vec.into_iter().filter(|r| r.status().is_locked()).map(|r| r.timesheet_id()).collect())
+}
+
+/// Remove the `entities` whose [`Id`] is `locked`, unless the `user` can [approve](Action::Approve) them.
+///
+/// Returns `true` if any `entities` were removed.
+pub async fn retain<Db, R>(
+	state: &ServerState<Db>,
+	user: &User,
+	entities: &mut Vec<Timesheet>,
+	locked: &HashSet<Id>,
+) -> Result<bool, Response<R>>
+where
+	Db: Database,
+	R: AsRef<Code> + From<Status>,
+{
+	if locked.is_empty() || state.has_permission(user, Object::Timesheet, Action::Approve).await?
+	{
+		return Ok(false);
+	}
+
+	let department_id = match state.has_permission(user, Object::TimesheetInDepartment, Action::Approve).await?
+	{
+		true => user.department().map(|d| d.id),
+		false => None,
+	};
+
+	let employee_id = user.employee().map(|e| e.id);
+	let len = entities.len();
+	entities.retain(|t| {
+		!locked.contains(&t.id) ||
+			(Some(t.employee.id) != employee_id && t.job.departments.iter().any(|d| Some(d.id) == department_id))
+	});
+
+	Ok(entities.len() < len)
+}
+
+/// Restrict the `condition` to the timesheets which the `user` can perform the `action` on.
+async fn scope<Db>(
+	state: &ServerState<Db>,
+	user: &User,
+	action: Action,
+	condition: &mut MatchTimesheet,
+) -> Result<Code, Response<Post<Review>>>
+where
+	Db: Database,
+{
+	match state.timesheet_permissions(user, action).await?
+	{
+		Object::Timesheet => Ok(Code::Success),
+
+		// HACK: no if-let guards
+		Object::TimesheetInDepartment if user.employee().is_some() =>
+		{
+			condition.job.departments &= MatchDepartment::from(user.department().unwrap().id).into();
+			Ok(Code::SuccessForPermissions)
+		},
+
+		p @ Object::CreatedTimesheet if action == Action::Approve =>
+		{
+			no_effective_perms(action, p, Reason::ResourceCreated).and_then(Err)
+		},
+
+		// HACK: no if-let guards
+		Object::CreatedTimesheet if user.employee().is_some() =>
+		{
+			condition.employee.id &= user.employee().unwrap().id.into();
+			Ok(Code::SuccessForPermissions)
+		},
+
+		p @ Object::TimesheetInDepartment => no_effective_perms(action, p, Reason::NoDepartment).and_then(Err),
+		p @ Object::CreatedTimesheet => no_effective_perms(action, p, Reason::NoEmployee).and_then(Err),
+		p => p.unreachable(),
+	}
+}
+
+/// Move each of the `changes` from one of the `from` statuses to the given status, in one transaction.
+///
+/// If any cannot change (because of the `reason`), or there are already `invalid` changes, none are made.
+async fn transition<A>(
+	pool: &Pool<A::Db>,
+	changes: Vec<(Id, ReviewStatus, Option<String>)>,
+	from: &[ReviewStatus],
+	reason: &str,
+	reviewer_id: Option<Id>,
+	mut invalid: Vec<String>,
+) -> Result<Vec<Review>, Response<Post<Review>>>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	let mut tx = pool.begin().await.map_err(database)?;
+	let mut reviews = Vec::with_capacity(changes.len());
+	for (id, status, comment) in changes
+	{
+		match A::Review::transition(&mut *tx, id, from, status, comment, reviewer_id).await.map_err(database)?
+		{
+			Some(r) => reviews.push(r),
+			None => invalid.push(format!("timesheet {id} cannot change: {reason}")),
+		}
+	}
+
+	if !invalid.is_empty()
+	{
+		return Err(Response::from(Post::from(Status::new(Code::InvalidTransition, invalid.join("; ")))));
+	}
+
+	tx.commit().await.map_err(database)?;
+	Ok(reviews)
+}
+
+/// Map an [`sqlx::Error`] into a [`Response`].
+fn database(error: sqlx::Error) -> Response<Post<Review>>
+{
+	Response::from(Post::from(Status::from(&error)))
+}
@@ -0,0 +1,103 @@
+//! Contains the deletion of [`Role`]s, which reassigns the [`User`]s who still have them to another role.
+
+use serde::Deserialize;
+use sqlx::{Database, Executor};
+use winvoice_adapter::{Deletable, Retrievable, Updatable};
+use winvoice_match::Match;
+use winvoice_schema::Id;
+
+use super::lockout;
+use crate::{
+	api::{response::Delete, Code, EventKind, Status},
+	permissions::{Action, Object},
+	r#match::{MatchRole, MatchUser},
+	schema::{Adapter, Role, User},
+	server::{
+		response::{DeleteResponse, Response},
+		ServerState,
+	},
+	twin_result::TwinResult,
+};
+
+/// The query parameters of a `DELETE` request, which deletes [`Role`]s.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct Reassign
+{
+	/// The [`Id`] of the [`Role`] which the [`User`]s of the deleted roles are given.
+	reassign_to: Option<Id>,
+}
+
+/// Delete the `roles`, after giving the [`User`]s who have them the role to [`Reassign`] them to.
+///
+/// Roles which some users still have cannot be deleted without a role to reassign them to, and the reassignment may
+/// not leave nobody who can manage users and roles (see [`lockout::guard`]).
+pub async fn delete<A>(
+	state: &ServerState<A::Db>,
+	user: &User,
+	roles: Vec<Role>,
+	reassign: Reassign,
+) -> TwinResult<DeleteResponse>
+where
+	A: Adapter,
+	for<'con> &'con mut <A::Db as Database>::Connection: Executor<'con, Database = A::Db>,
+{
+	state.enforce_permission(user, Object::Role, Action::Delete).await?;
+
+	let ids = roles.iter().map(Role::id).collect::<Vec<_>>();
+	let users = match ids.is_empty()
+	{
+		true => Vec::new(),
+		false => A::User::retrieve(state.pool(), MatchUser {
+			role: MatchRole { id: ids.iter().copied().collect::<Match<_>>(), ..Default::default() },
+			..Default::default()
+		})
+		.await
+		.map_err(DeleteResponse::from)?,
+	};
+
+	let role = match reassign.reassign_to
+	{
+		_ if users.is_empty() => None,
+		None =>
+		{
+			let message = format!("{} users have the roles, and there is no role to reassign them to", users.len());
+			return Err(Response::from(Delete::from(Status::new(Code::RoleInUse, message))).into());
+		},
+
+		Some(id) if ids.contains(&id) =>
+		{
+			let message = format!("Users cannot be reassigned to role {id}, because it is being deleted");
+			return Err(Response::from(Delete::from(Status::new(Code::InvalidReference, message))).into());
+		},
+
+		Some(id) => match A::Role::retrieve(state.pool(), id.into()).await.map_err(DeleteResponse::from)?.pop()
+		{
+			Some(r) => Some(r),
+			None =>
+			{
+				let message = format!("There is no role {id} to reassign users to");
+				return Err(Response::from(Delete::from(Status::new(Code::InvalidReference, message))).into());
+			},
+		},
+	};
+
+	let reassigned = match role
+	{
+		Some(r) => users.into_iter().map(|u| User { role: r.clone(), ..u }).collect::<Vec<_>>(),
+		None => Vec::new(),
+	};
+
+	if !reassigned.is_empty()
+	{
+		lockout::guard::<A, Delete>(state, &[], &reassigned).await?;
+	}
+
+	// the users must not be reassigned unless the roles are deleted, and vice versa
+	let mut tx = state.pool().begin().await.map_err(DeleteResponse::from)?;
+	A::User::update(&mut tx, reassigned.iter()).await.map_err(DeleteResponse::from)?;
+	A::Role::delete(&mut *tx, roles.iter()).await.map_err(DeleteResponse::from)?;
+	state.publish(&mut tx, EventKind::Update, reassigned).await.map_err(DeleteResponse::from)?;
+	state.publish(&mut tx, EventKind::Delete, roles).await.map_err(DeleteResponse::from)?;
+	tx.commit().await.map_err(DeleteResponse::from)?;
+	Ok(DeleteResponse::from(Code::Success))
+}
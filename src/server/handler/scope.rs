@@ -0,0 +1,140 @@
+//! Contains the scoping of [`Event`]s to what a [`User`] is allowed to retrieve, which mirrors the scoping done by the
+//! `POST` handlers.
+
+use std::collections::HashSet;
+
+use winvoice_adapter::Retrievable;
+use winvoice_match::{Match, MatchTimesheet};
+use winvoice_schema::{Expense, Id, Timesheet};
+
+use crate::{
+	api::{response::Post, Entity, Event},
+	permissions::{Action, Object},
+	schema::{Adapter, User},
+	server::ServerState,
+};
+
+/// The [`Action`] which a [`User`] must be able to perform on an [`Entity`] in order to be told that it changed.
+const ACTION: Action = Action::Retrieve;
+
+/// The type which permission errors are reported as. It does not matter which, since they are discarded.
+type Rejection = Post<Entity>;
+
+/// Remove the entities of the `event` which the `user` cannot retrieve. Returns [`None`] if none are left.
+pub async fn event<A>(state: &ServerState<A::Db>, user: &User, event: Event) -> Option<Event>
+where
+	A: Adapter,
+{
+	let kind = event.kind();
+	let mut entities = Vec::new();
+	for e in event.into_entities()
+	{
+		if let Some(e) = entity::<A>(state, user, e).await
+		{
+			entities.push(e);
+		}
+	}
+
+	(!entities.is_empty()).then_some(Event::new(kind, entities))
+}
+
+/// Return the part of the `entity` which the `user` can retrieve, if any.
+pub async fn entity<A>(state: &ServerState<A::Db>, user: &User, entity: Entity) -> Option<Entity>
+where
+	A: Adapter,
+{
+	let department = user.department().map(|d| d.id);
+	let employee = user.employee().map(|e| e.id);
+
+	let visible = match &entity
+	{
+		Entity::Contact(_) => state.has_permission::<Rejection>(user, Object::Contact, ACTION).await.ok()?,
+		Entity::Location(_) => state.has_permission::<Rejection>(user, Object::Location, ACTION).await.ok()?,
+		Entity::Organization(_) => state.has_permission::<Rejection>(user, Object::Organization, ACTION).await.ok()?,
+		Entity::Role(_) => state.has_permission::<Rejection>(user, Object::Role, ACTION).await.ok()?,
+
+		Entity::Department(d) => match state.department_permissions::<Rejection>(user, ACTION).await.ok()?
+		{
+			Object::Department => true,
+			Object::AssignedDepartment => department == Some(d.id),
+			p => p.unreachable(),
+		},
+
+		Entity::Employee(e) => match state.employee_permissions::<Rejection>(user, ACTION).await.ok()?
+		{
+			Object::Employee => true,
+			Object::EmployeeInDepartment => department == Some(e.department.id),
+			Object::EmployeeSelf => employee == Some(e.id),
+			p => p.unreachable(),
+		},
+
+		Entity::Expenses(x) =>
+		{
+			let expenses = match state.expense_permissions::<Rejection>(user, ACTION).await.ok()?
+			{
+				Object::Expenses => x.clone(),
+				permission =>
+				{
+					let matching = timesheets::<A>(state, x, |t| match permission
+					{
+						Object::ExpensesInDepartment => t.job.departments.iter().any(|d| Some(d.id) == department),
+						Object::CreatedExpenses => employee == Some(t.employee.id),
+						p => p.unreachable(),
+					})
+					.await?;
+
+					x.iter().filter(|x| matching.contains(&x.timesheet_id)).cloned().collect()
+				},
+			};
+
+			return (!expenses.is_empty()).then_some(Entity::Expenses(expenses));
+		},
+
+		Entity::Job(j) => match state.job_permissions::<Rejection>(user, ACTION).await.ok()?
+		{
+			Object::Job => true,
+			Object::JobInDepartment => j.departments.iter().any(|d| Some(d.id) == department),
+			p => p.unreachable(),
+		},
+
+		Entity::Timesheet(t) => match state.timesheet_permissions::<Rejection>(user, ACTION).await.ok()?
+		{
+			Object::Timesheet => true,
+			Object::TimesheetInDepartment => t.job.departments.iter().any(|d| Some(d.id) == department),
+			Object::CreatedTimesheet => employee == Some(t.employee.id),
+			p => p.unreachable(),
+		},
+
+		Entity::User(u) => match state.user_permissions::<Rejection>(user, ACTION).await.ok()?
+		{
+			Object::User => true,
+			Object::UserInDepartment => department.is_some() && department == u.department().map(|d| d.id),
+			Object::UserSelf => user.id() == u.id(),
+			p => p.unreachable(),
+		},
+	};
+
+	visible.then_some(entity)
+}
+
+/// The [`Id`]s of the [`Timesheet`]s of the `expenses` which satisfy the `predicate`.
+///
+/// The timesheets are retrieved rather than being part of the [`Event`], since an [`Expense`] only knows the [`Id`]
+/// of its timesheet.
+async fn timesheets<A>(
+	state: &ServerState<A::Db>,
+	expenses: &[Expense],
+	predicate: impl Fn(&Timesheet) -> bool,
+) -> Option<HashSet<Id>>
+where
+	A: Adapter,
+{
+	A::Timesheet::retrieve(state.pool(), MatchTimesheet {
+		id: expenses.iter().map(|x| x.timesheet_id).collect::<Match<_>>(),
+		..Default::default()
+	})
+	.await
+	.map_err(|e| tracing::error!("Could not scope an event's expenses: {e}"))
+	.ok()
+	.map(|vec| vec.into_iter().filter(&predicate).map(|t| t.id).collect())
+}
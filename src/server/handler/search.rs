@@ -0,0 +1,121 @@
+//! Contains the [`execute`]ion of a search for entities by their text.
+
+use std::collections::HashMap;
+
+use winvoice_adapter::{
+	schema::{ContactAdapter, ExpensesAdapter, JobAdapter, OrganizationAdapter, TimesheetAdapter},
+	Retrievable,
+};
+use winvoice_match::{Match, MatchContact, MatchExpense, MatchJob, MatchOrganization, MatchStr, MatchTimesheet};
+use winvoice_schema::Id;
+
+use super::scope;
+use crate::{
+	api::{response::Post, Code, Entity, Status},
+	schema::{Adapter, Hit, SearchAdapter, User},
+	server::{
+		response::{Response, ResponseResult},
+		ServerState,
+	},
+};
+
+/// The most entities which are responded with for a single search, and how many [`Hit`]s are searched at a time.
+const LIMIT: i64 = 100;
+
+/// Retrieve the entities of the `$Adapter` whose [`Id`]s are in `$ids`, and add them to `$entities`.
+macro_rules! retrieve {
+	($state:ident, $entities:ident, $Adapter:ident, $Match:ident, $ids:ident, |$x:ident| $entity:expr) => {
+		if !$ids.is_empty()
+		{
+			let condition = $Match { id: $ids.into_iter().collect::<Match<_>>(), ..Default::default() };
+			let retrieved = A::$Adapter::retrieve($state.pool(), condition).await.map_err(database)?;
+			$entities.extend(retrieved.into_iter().map(|$x| $entity));
+		}
+	};
+}
+
+/// Search for the `terms`, and respond with the matching entities which the `user` can retrieve, the most relevant
+/// first.
+///
+/// Since the `user` may not be able to retrieve some of the matches, they are searched a page at a time until there
+/// are [`LIMIT`] which the `user` can retrieve, or there are no more matches.
+///
+/// A matching [`Expense`](winvoice_schema::Expense) is responded with as an [`Entity::Expenses`] of one.
+pub async fn execute<A>(state: &ServerState<A::Db>, user: &User, terms: &str) -> ResponseResult<Post<Entity>>
+where
+	A: Adapter,
+{
+	let limit = usize::try_from(LIMIT).unwrap_or(usize::MAX);
+	let mut offset = 0;
+	let mut visible = Vec::new();
+	loop
+	{
+		let hits = A::Search::search(state.pool(), terms, LIMIT, offset).await.map_err(database)?;
+		let exhausted = hits.len() < limit;
+		offset += LIMIT;
+
+		visible.extend(page::<A>(state, user, hits).await?);
+		if exhausted || visible.len() >= limit
+		{
+			break;
+		}
+	}
+
+	visible.truncate(limit);
+	Ok(Response::from(Post::new(visible, Code::Success.into())))
+}
+
+/// The [`Entity`]s of the `hits` which the `user` can retrieve, in the same order.
+async fn page<A>(state: &ServerState<A::Db>, user: &User, hits: Vec<Hit>) -> Result<Vec<Entity>, Response<Post<Entity>>>
+where
+	A: Adapter,
+{
+	let mut contacts = Vec::new();
+	let mut expenses = Vec::<Id>::new();
+	let mut jobs = Vec::<Id>::new();
+	let mut organizations = Vec::<Id>::new();
+	let mut timesheets = Vec::<Id>::new();
+	for hit in &hits
+	{
+		match hit
+		{
+			Hit::Contact(label) => contacts.push(MatchStr::from(label.clone())),
+			Hit::Expense(id) => expenses.push(*id),
+			Hit::Job(id) => jobs.push(*id),
+			Hit::Organization(id) => organizations.push(*id),
+			Hit::Timesheet(id) => timesheets.push(*id),
+		}
+	}
+
+	let mut entities = HashMap::with_capacity(hits.len());
+	if !contacts.is_empty()
+	{
+		let condition = MatchContact { label: MatchStr::Or(contacts), ..Default::default() };
+		let retrieved = A::Contact::retrieve(state.pool(), condition).await.map_err(database)?;
+		entities.extend(retrieved.into_iter().map(|c| (Hit::Contact(c.label.clone()), Entity::Contact(c))));
+	}
+
+	retrieve!(state, entities, Expenses, MatchExpense, expenses, |x| (Hit::Expense(x.id), Entity::Expenses(vec![x])));
+	retrieve!(state, entities, Job, MatchJob, jobs, |j| (Hit::Job(j.id), Entity::Job(j)));
+	retrieve!(state, entities, Organization, MatchOrganization, organizations, |o| {
+		(Hit::Organization(o.id), Entity::Organization(o))
+	});
+	retrieve!(state, entities, Timesheet, MatchTimesheet, timesheets, |t| (Hit::Timesheet(t.id), Entity::Timesheet(t)));
+
+	let mut visible = Vec::with_capacity(entities.len());
+	for hit in hits
+	{
+		if let Some(entity) = entities.remove(&hit)
+		{
+			visible.extend(scope::entity::<A>(state, user, entity).await);
+		}
+	}
+
+	Ok(visible)
+}
+
+/// Map an [`sqlx::Error`] into a [`Response`].
+fn database(error: sqlx::Error) -> Response<Post<Entity>>
+{
+	Response::from(Post::from(Status::from(&error)))
+}
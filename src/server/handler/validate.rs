@@ -0,0 +1,491 @@
+//! Contains the [`Validate`] trait, which checks the bodies of `PUT` and `PATCH` requests for values which are not
+//! valid before they reach the database.
+
+use core::{fmt::Display, time::Duration};
+use std::collections::BTreeSet;
+
+use humantime_serde::Serde;
+use reqwest::Url;
+use serde_json::Value;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Contact,
+	ContactKind,
+	Currency,
+	Department,
+	Employee,
+	Expense,
+	Id,
+	Invoice,
+	Job,
+	Location,
+	Money,
+	Organization,
+	Timesheet,
+};
+
+use crate::{
+	api::{
+		request::{self, Operation},
+		Code,
+		EventKind,
+		Status,
+		Violation,
+	},
+	schema::{Allowance, Budget, Rate, Role, User},
+	server::{conditional::RETRIEVABLE, response::Response},
+};
+
+/// The arguments of an [`Expense`] which is created alongside a [`Timesheet`].
+type ExpenseArgs = (String, Money, String);
+
+/// Implementors can be checked for values which are not valid.
+pub trait Validate
+{
+	/// Report each value of `self` which is not valid to the `validator`, where `path` is the path to `self` in the
+	/// body of the request.
+	fn validate(&self, path: &str, validator: &mut Validator);
+}
+
+/// Collects the [`Violation`]s which are found by [`Validate`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Validator
+{
+	/// The values which are not valid.
+	violations: Vec<Violation>,
+}
+
+impl Validator
+{
+	/// Report the value at the `path` with the `message`, unless the `condition` holds.
+	fn check(&mut self, condition: bool, path: String, message: &str) -> &mut Self
+	{
+		if !condition
+		{
+			self.violations.push(Violation::new(path, message.into()));
+		}
+
+		self
+	}
+
+	/// [Validate](Validate::validate) each of the `values` in the sequence at the `path`.
+	fn each<'value, T>(&mut self, path: &str, values: impl IntoIterator<Item = &'value T>) -> &mut Self
+	where
+		T: 'value + Validate,
+	{
+		values.into_iter().enumerate().for_each(|(i, v)| v.validate(&index(path, i), self));
+		self
+	}
+
+	/// Report the `end` at the `path` if it is before the `begin`.
+	fn ordered<T>(&mut self, path: String, begin: &T, end: Option<&T>) -> &mut Self
+	where
+		T: PartialOrd,
+	{
+		self.check(end.map_or(true, |e| e >= begin), path, "must not be before the beginning")
+	}
+
+	/// Report the `money` at the `path` if it is negative.
+	fn non_negative(&mut self, path: String, money: &Money) -> &mut Self
+	{
+		self.check(!money.amount.is_sign_negative(), path, "must not be negative")
+	}
+
+	/// Report the `text` at the `path` if it is empty, or only whitespace.
+	fn non_empty(&mut self, path: String, text: &str) -> &mut Self
+	{
+		self.check(!text.trim().is_empty(), path, "must not be empty")
+	}
+
+	/// Report the `duration` at the `path` if it is zero.
+	fn non_zero(&mut self, path: String, duration: Duration) -> &mut Self
+	{
+		self.check(!duration.is_zero(), path, "must be longer than zero")
+	}
+}
+
+/// The path to the `field` of the value at the `path`.
+fn field(path: &str, field: impl Display) -> String
+{
+	match path.is_empty()
+	{
+		true => field.to_string(),
+		false => format!("{path}.{field}"),
+	}
+}
+
+/// The path to the element at index `i` of the sequence at the `path`.
+fn index(path: &str, i: usize) -> String
+{
+	format!("{path}[{i}]")
+}
+
+/// The path to the argument at index `i` of a [`request::Put`] at the `path`.
+fn arg(path: &str, i: usize) -> String
+{
+	index(&field(path, "args"), i)
+}
+
+/// [Validate](Validate::validate) the `body` of a request, rejecting it with [`Code::ValidationError`] if any of its
+/// values are not valid.
+pub fn validate<R>(body: &impl Validate) -> Result<(), Response<R>>
+where
+	R: AsRef<Code> + From<Status>,
+{
+	let violations = violations(body);
+	if violations.is_empty()
+	{
+		return Ok(());
+	}
+
+	let message = violations.iter().map(|v| format!("{}: {}", v.path(), v.message())).collect::<Vec<_>>();
+	Err(Response::from(R::from(Status::new(Code::ValidationError, message.join("; ")).with_violations(violations))))
+}
+
+/// Every value of the `body` which is not [valid](Validate::validate).
+pub fn violations(body: &impl Validate) -> Vec<Violation>
+{
+	let mut validator = Validator::default();
+	body.validate("", &mut validator);
+	validator.violations
+}
+
+impl<T> Validate for request::Patch<T>
+where
+	T: Validate,
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.each(&field(path, "entities"), self.entities());
+	}
+}
+
+impl Validate for request::Batch
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.each(&field(path, "operations"), self.operations());
+	}
+}
+
+/// The same checks as a `PUT` to the route of the entity which the [`Operation`] creates.
+impl Validate for Operation
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let at = |variant: &str, name: &str| field(&field(path, variant), name);
+		match self
+		{
+			Self::Contact { label, .. } => validator.non_empty(at("contact", "label"), label),
+			Self::Department { name } => validator.non_empty(at("department", "name"), name),
+			Self::Employee { name, title, .. } =>
+			{
+				validator.non_empty(at("employee", "name"), name).non_empty(at("employee", "title"), title)
+			},
+			Self::Expenses { expenses, .. } => validator.each(&at("expenses", "expenses"), expenses),
+			Self::Job { date_close, date_open, increment, invoice, .. } => validator
+				.ordered(at("job", "date_close"), date_open, date_close.as_ref())
+				.non_zero(at("job", "increment"), *increment)
+				.non_negative(field(&at("job", "invoice"), "hourly_rate"), &invoice.hourly_rate),
+			Self::Location { name, .. } => validator.non_empty(at("location", "name"), name),
+			Self::Organization { name, .. } => validator.non_empty(at("organization", "name"), name),
+			Self::Role { name, .. } => validator.non_empty(at("role", "name"), name),
+			Self::Timesheet { expenses, time_begin, time_end, .. } => validator
+				.each(&at("timesheet", "expenses"), expenses)
+				.ordered(at("timesheet", "time_end"), time_begin, time_end.as_ref()),
+			Self::User { password, username, .. } =>
+			{
+				validator.non_empty(at("user", "password"), password).non_empty(at("user", "username"), username)
+			},
+		};
+	}
+}
+
+impl Validate for Allowance
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let positive = match self
+		{
+			Self::Money(m) => !(m.amount.is_zero() || m.amount.is_sign_negative()),
+			Self::Time(t) => !t.is_zero(),
+		};
+
+		validator.check(positive, path.into(), "must allow more than nothing");
+	}
+}
+
+impl Validate for Budget
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		self.allowance().validate(&field(path, "allowance"), validator);
+		validator.check(!self.thresholds().contains(&0), field(path, "thresholds"), "must be greater than zero");
+	}
+}
+
+impl Validate for Contact
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "label"), &self.label);
+	}
+}
+
+impl Validate for Department
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "name"), &self.name);
+	}
+}
+
+impl Validate for Employee
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "name"), &self.name).non_empty(field(path, "title"), &self.title);
+	}
+}
+
+impl Validate for Expense
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "category"), &self.category).non_negative(field(path, "cost"), &self.cost);
+	}
+}
+
+impl Validate for ExpenseArgs
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(index(path, 0), &self.0).non_negative(index(path, 1), &self.1);
+	}
+}
+
+impl Validate for Job
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator
+			.ordered(field(path, "date_close"), &self.date_open, self.date_close.as_ref())
+			.non_zero(field(path, "increment"), self.increment)
+			.non_negative(field(&field(path, "invoice"), "hourly_rate"), &self.invoice.hourly_rate);
+	}
+}
+
+impl Validate for Location
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "name"), &self.name);
+	}
+}
+
+impl Validate for Organization
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "name"), &self.name);
+	}
+}
+
+impl Validate for Rate
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_negative(field(path, "hourly_rate"), self.hourly_rate());
+	}
+}
+
+impl Validate for Role
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "name"), self.name());
+	}
+}
+
+impl Validate for Timesheet
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator
+			.each(&field(path, "expenses"), &self.expenses)
+			.ordered(field(path, "time_end"), &self.time_begin, self.time_end.as_ref());
+	}
+}
+
+impl Validate for User
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(field(path, "username"), self.username());
+	}
+}
+
+impl Validate for request::Put<Budget>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		self.args().validate(&field(path, "args"), validator);
+	}
+}
+
+impl Validate for request::Put<Rate>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		self.args().validate(&field(path, "args"), validator);
+	}
+}
+
+/// The arguments of a new [`Department`].
+impl Validate for request::Put<String>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(arg(path, 0), self.args());
+	}
+}
+
+/// The arguments of a new [`Contact`].
+impl Validate for request::Put<(ContactKind, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(arg(path, 1), &self.args().1);
+	}
+}
+
+/// The arguments of a new [`Employee`].
+impl Validate for request::Put<(Department, String, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, name, title) = self.args();
+		validator.non_empty(arg(path, 1), name).non_empty(arg(path, 2), title);
+	}
+}
+
+/// The arguments of new [`Expense`]s.
+impl Validate for request::Put<(Vec<ExpenseArgs>, Id)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.each(&arg(path, 0), &self.args().0);
+	}
+}
+
+/// The arguments of a new [`Invitation`](crate::schema::Invitation).
+impl Validate for request::Put<(Option<Employee>, Role, Serde<Duration>, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, _, ttl, username) = self.args();
+		validator.non_zero(arg(path, 2), **ttl).non_empty(arg(path, 3), username);
+	}
+}
+
+/// The arguments of a new [`Job`].
+#[allow(clippy::type_complexity)]
+impl Validate
+	for request::Put<(
+		Organization,
+		Option<DateTime<Utc>>,
+		DateTime<Utc>,
+		BTreeSet<Department>,
+		Serde<Duration>,
+		Invoice,
+		String,
+		String,
+	)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, date_close, date_open, _, increment, invoice, ..) = self.args();
+		validator
+			.ordered(arg(path, 1), date_open, date_close.as_ref())
+			.non_zero(arg(path, 4), **increment)
+			.non_negative(field(&arg(path, 5), "hourly_rate"), &invoice.hourly_rate);
+	}
+}
+
+/// The arguments of a new [`Location`].
+impl Validate for request::Put<(Option<Currency>, String, Option<Location>)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(arg(path, 1), &self.args().1);
+	}
+}
+
+/// The arguments of a new [`Organization`].
+impl Validate for request::Put<(Location, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(arg(path, 1), &self.args().1);
+	}
+}
+
+/// The arguments of a new [`Role`].
+impl Validate for request::Put<(String, Option<Serde<Duration>>)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.non_empty(arg(path, 0), &self.args().0);
+	}
+}
+
+/// The arguments of a new [`SavedQuery`](crate::schema::SavedQuery).
+impl Validate for request::Put<(Value, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let route = self.args().1.as_str();
+		validator.check(RETRIEVABLE.contains(&route), arg(path, 1), "must be a route which retrieves entities");
+	}
+}
+
+/// The arguments of a new [`Timesheet`].
+#[allow(clippy::type_complexity)]
+impl Validate for request::Put<(Employee, Vec<ExpenseArgs>, Job, DateTime<Utc>, Option<DateTime<Utc>>, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, expenses, _, time_begin, time_end, _) = self.args();
+		validator.each(&arg(path, 1), expenses).ordered(arg(path, 4), time_begin, time_end.as_ref());
+	}
+}
+
+/// The arguments of a [`Timesheet`] which is started now.
+impl Validate for request::Put<(Vec<ExpenseArgs>, Job, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		validator.each(&arg(path, 0), &self.args().0);
+	}
+}
+
+/// The arguments of a new [`User`].
+impl Validate for request::Put<(Option<Employee>, String, Role, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, password, _, username) = self.args();
+		validator.non_empty(arg(path, 1), password).non_empty(arg(path, 3), username);
+	}
+}
+
+/// The arguments of a new [`Webhook`](crate::schema::Webhook).
+impl Validate for request::Put<(Vec<EventKind>, String, String)>
+{
+	fn validate(&self, path: &str, validator: &mut Validator)
+	{
+		let (_, secret, url) = self.args();
+		let web = Url::parse(url).map_or(false, |u| matches!(u.scheme(), "http" | "https"));
+		validator.non_empty(arg(path, 1), secret).check(web, arg(path, 2), "must be an HTTP or HTTPS URL");
+	}
+}
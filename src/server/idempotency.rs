@@ -0,0 +1,170 @@
+//! Contains middleware which makes `PUT` requests idempotent, when the client sends an
+//! [`IDEMPOTENCY_HEADER`](api::IDEMPOTENCY_HEADER).
+
+use core::{marker::PhantomData, time::Duration};
+
+use axum::{
+	body::{self, Body, Full, HttpBody},
+	extract::State,
+	http::{header, HeaderValue, Method, Request, StatusCode},
+	middleware::Next,
+	response::IntoResponse,
+	Extension,
+};
+use sha2::{Digest, Sha256};
+use sqlx::Pool;
+use winvoice_schema::Id;
+
+use super::response::Response;
+use crate::{
+	api::{self, response::Put, Code, Status},
+	schema::{IdempotencyAdapter, Reservation, User},
+};
+
+/// The largest body which is read to fingerprint a request, unless [attachments](super::Attachments) may be larger.
+/// This is the same as the [`DefaultBodyLimit`](axum::extract::DefaultBodyLimit) of the other requests.
+pub const BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// How long a request may hold its idempotency key, when the server has no timeout for requests.
+pub const LEASE: Duration = Duration::from_secs(5 * 60);
+
+/// [`release`](IdempotencyAdapter::release)s an idempotency key when dropped, unless it was
+/// [`disarm`](Self::disarm)ed. This way, the key is released even if the request which reserved it is cancelled
+/// (e.g. because it timed out, or the client disconnected).
+struct Release<I>
+where
+	I: IdempotencyAdapter,
+{
+	/// The key, and what is needed to release it.
+	key: Option<(Pool<I::Db>, Id, String, String)>,
+
+	/// The [`IdempotencyAdapter`] which releases the key.
+	phantom: PhantomData<fn() -> I>,
+}
+
+impl<I> Release<I>
+where
+	I: IdempotencyAdapter,
+{
+	/// Keep the key, because the response to its request was stored.
+	fn disarm(&mut self)
+	{
+		self.key = None;
+	}
+
+	/// Release the `key` of the `user` on the `route` when dropped.
+	const fn new(pool: Pool<I::Db>, user: Id, route: String, key: String) -> Self
+	{
+		Self { key: Some((pool, user, route, key)), phantom: PhantomData }
+	}
+}
+
+impl<I> Drop for Release<I>
+where
+	I: IdempotencyAdapter,
+{
+	fn drop(&mut self)
+	{
+		if let Some((pool, user, route, key)) = self.key.take()
+		{
+			tokio::spawn(async move {
+				if let Err(e) = I::release(&pool, user, &route, &key).await
+				{
+					tracing::error!("Could not release idempotency key {key:?}: {e}");
+				}
+			});
+		}
+	}
+}
+
+/// Respond with the `code` and `message`, in the same shape as the response to a `PUT`.
+fn error(code: Code, message: String) -> axum::response::Response
+{
+	Response::from(Put::<()>::from(Status::new(code, message))).into_response()
+}
+
+/// If the `request` is a `PUT` with an [`IDEMPOTENCY_HEADER`](api::IDEMPOTENCY_HEADER), return the stored response to
+/// the first request which had the same key — as long as it was sent less than `ttl` ago, and had the same body.
+/// Otherwise, run the `request` and store its response.
+///
+/// A key is held for at most the `lease` while its request runs. Responses with a server error are not stored, and
+/// neither are the responses to requests which are cancelled, so that the request can be tried again. Requests whose
+/// body is larger than the `limit` are rejected before any of them is stored.
+pub async fn handle<I>(
+	State((pool, lease, ttl, limit)): State<(Pool<I::Db>, Duration, Duration, usize)>,
+	Extension(user): Extension<User>,
+	request: Request<Body>,
+	next: Next<Body>,
+) -> axum::response::Response
+where
+	I: 'static + IdempotencyAdapter,
+{
+	let key = match (request.method(), request.headers().get(api::IDEMPOTENCY_HEADER))
+	{
+		(&Method::PUT, Some(k)) => match k.to_str()
+		{
+			Ok(k) => k.to_owned(),
+			Err(e) => return error(Code::EncodingError, format!("The idempotency key could not be read: {e}")),
+		},
+		_ => return next.run(request).await,
+	};
+
+	let (parts, mut body) = request.into_parts();
+	let mut bytes = Vec::new();
+	while let Some(chunk) = body.data().await
+	{
+		match chunk
+		{
+			Ok(c) if bytes.len() + c.len() > limit =>
+			{
+				return error(Code::RequestTooLarge, format!("requests may be at most {limit} bytes"));
+			},
+			Ok(c) => bytes.extend_from_slice(&c),
+			Err(e) => return error(Code::EncodingError, format!("The request could not be read: {e}")),
+		}
+	}
+
+	let fingerprint = Sha256::digest(&bytes);
+	let request = Request::from_parts(parts, Body::from(bytes));
+
+	let route = request.uri().path().to_owned();
+	match I::reserve(&pool, user.id(), &route, &key, &fingerprint, lease, ttl).await
+	{
+		Ok(Reservation::Reserved) => (),
+		Ok(Reservation::Mismatch) => return error(Code::IdempotencyKeyReused, Code::IdempotencyKeyReused.to_string()),
+		Ok(Reservation::Pending) => return error(Code::IdempotencyKeyInUse, Code::IdempotencyKeyInUse.to_string()),
+		Ok(Reservation::Completed { body, status }) =>
+		{
+			let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+			let mut response = (status, body).into_response();
+			let headers = response.headers_mut();
+			headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+			headers.insert(api::IDEMPOTENCY_REPLAY_HEADER, HeaderValue::from_static("true"));
+			return response;
+		},
+		Err(e) => return Response::from(Put::<()>::from(Status::from(&e))).into_response(),
+	};
+
+	let mut release = Release::<I>::new(pool.clone(), user.id(), route.clone(), key.clone());
+	let (parts, mut body) = next.run(request).await.into_parts();
+	let mut bytes = Vec::new();
+	while let Some(chunk) = body.data().await
+	{
+		match chunk
+		{
+			Ok(c) => bytes.extend_from_slice(&c),
+			Err(e) => return error(Code::Other, format!("The response could not be read: {e}")),
+		}
+	}
+
+	if !parts.status.is_server_error()
+	{
+		match I::complete(&pool, user.id(), &route, &key, parts.status.as_u16(), &bytes).await
+		{
+			Ok(()) => release.disarm(),
+			Err(e) => tracing::error!("Could not store the response for idempotency key {key:?}: {e}"),
+		}
+	}
+
+	axum::response::Response::from_parts(parts, body::boxed(Full::from(bytes)))
+}
@@ -0,0 +1,217 @@
+//! Contains the configuration of authenticating users with an LDAP directory (e.g. Active Directory), instead of the
+//! password hashes in the database.
+
+#[cfg(all(feature = "test-ldap", test))]
+pub(crate) mod fixture;
+
+use core::time::Duration;
+
+use ldap3::{drive, result::Result, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use tokio::time;
+
+/// The result code of a bind with credentials which the directory does not accept.
+const INVALID_CREDENTIALS: u32 = 49;
+
+/// How long the directory has to accept a connection, and then to authenticate a user.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Escape the `value` so that it can be part of a distinguished name, as in
+/// [RFC 4514](https://www.rfc-editor.org/rfc/rfc4514#section-2.4).
+fn escape_dn(value: &str) -> String
+{
+	let last = value.chars().count().saturating_sub(1);
+	value.chars().enumerate().fold(String::with_capacity(value.len()), |mut escaped, (i, c)| {
+		match c
+		{
+			'\0' => escaped.push_str("\\00"),
+			'"' | '+' | ',' | ';' | '<' | '=' | '>' | '\\' => escaped.extend(['\\', c]),
+			'#' if i == 0 => escaped.push_str("\\#"),
+			' ' if i == 0 || i == last => escaped.push_str("\\ "),
+			_ => escaped.push(c),
+		}
+
+		escaped
+	})
+}
+
+/// Escape the `value` so that it can be part of a search filter, as in
+/// [RFC 4515](https://www.rfc-editor.org/rfc/rfc4515#section-3).
+fn escape_filter(value: &str) -> String
+{
+	value.chars().fold(String::with_capacity(value.len()), |mut escaped, c| {
+		match c
+		{
+			'\0' => escaped.push_str("\\00"),
+			'(' => escaped.push_str("\\28"),
+			')' => escaped.push_str("\\29"),
+			'*' => escaped.push_str("\\2a"),
+			'\\' => escaped.push_str("\\5c"),
+			_ => escaped.push(c),
+		}
+
+		escaped
+	})
+}
+
+/// The LDAP directory which users are authenticated with by binding as them, and how its groups are mapped onto
+/// [`Role`](crate::schema::Role)s.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Ldap
+{
+	/// The DN which the groups of users are searched for under. When [`None`], users have no groups.
+	group_base: Option<String>,
+
+	/// The filter which finds the groups of a user, where `{dn}` is their DN and `{username}` is their username.
+	group_filter: String,
+
+	/// Whether a user who is authenticated by the directory, but does not exist yet, is created.
+	provision: bool,
+
+	/// The URL of the directory, e.g. `ldaps://ldap.example.com`.
+	url: String,
+
+	/// The DN which is bound as to authenticate a user, where `{username}` is their username. e.g.
+	/// `uid={username},ou=people,dc=example,dc=com`, or `{username}@example.com` for Active Directory.
+	user_dn: String,
+}
+
+impl Ldap
+{
+	/// Create a new [`Ldap`] configuration for the directory at the `url`, which binds as the `user_dn` (where
+	/// `{username}` is the username of a user) to authenticate them.
+	///
+	/// By default, users have no groups, and are not created when they do not exist.
+	pub fn new(url: String, user_dn: String) -> Self
+	{
+		Self { group_base: None, group_filter: "(member={dn})".into(), provision: false, url, user_dn }
+	}
+
+	/// Bind to the directory as the user with the `username` and `password`, and return the common names of the groups
+	/// which they are in. Returns [`None`] if the directory does not accept the credentials.
+	///
+	/// Fails if the directory does not respond within the [`TIMEOUT`].
+	pub async fn authenticate(&self, username: &str, password: &str) -> Result<Option<Vec<String>>>
+	{
+		// a bind without a password is anonymous, which many directories accept
+		if username.is_empty() || password.is_empty()
+		{
+			return Ok(None);
+		}
+
+		let (connection, mut ldap) =
+			LdapConnAsync::with_settings(LdapConnSettings::new().set_conn_timeout(TIMEOUT), &self.url).await?;
+		drive!(connection);
+
+		time::timeout(TIMEOUT, self.bind(&mut ldap, username, password)).await?
+	}
+
+	/// Bind to the directory with the `ldap` connection as the user with the `username` and `password`, and search for
+	/// their groups (see [`Ldap::authenticate`]).
+	async fn bind(&self, ldap: &mut ldap3::Ldap, username: &str, password: &str) -> Result<Option<Vec<String>>>
+	{
+		let dn = self.dn(username);
+		let bind = ldap.simple_bind(&dn, password).await?;
+		if bind.rc == INVALID_CREDENTIALS
+		{
+			ldap.unbind().await?;
+			return Ok(None);
+		}
+
+		bind.success()?;
+		let groups = match &self.group_base
+		{
+			Some(base) =>
+			{
+				let filter = self
+					.group_filter
+					.replace("{dn}", &escape_filter(&dn))
+					.replace("{username}", &escape_filter(username));
+
+				let (entries, _) = ldap.search(base, Scope::Subtree, &filter, vec!["cn"]).await?.success()?;
+				entries.into_iter().filter_map(|e| SearchEntry::construct(e).attrs.remove("cn")).flatten().collect()
+			},
+			None => Vec::new(),
+		};
+
+		ldap.unbind().await?;
+		Ok(Some(groups))
+	}
+
+	/// The DN which is bound as to authenticate the user with the `username`.
+	pub fn dn(&self, username: &str) -> String
+	{
+		self.user_dn.replace("{username}", &escape_dn(username))
+	}
+
+	/// Whether a user who is authenticated by the directory, but does not exist yet, is created.
+	pub const fn provision(&self) -> bool
+	{
+		self.provision
+	}
+
+	/// The URL of the directory, e.g. `ldaps://ldap.example.com`.
+	pub fn url(&self) -> &str
+	{
+		&self.url
+	}
+
+	/// Search for the groups of users under the `base` DN with the `filter`, where `{dn}` is the DN of the user and
+	/// `{username}` is their username.
+	pub fn with_groups(mut self, base: String, filter: String) -> Self
+	{
+		self.group_base = Some(base);
+		self.group_filter = filter;
+		self
+	}
+
+	/// Set whether a user who is authenticated by the directory, but does not exist yet, is created.
+	pub const fn with_provision(mut self, provision: bool) -> Self
+	{
+		self.provision = provision;
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	#[cfg(feature = "test-ldap")]
+	use super::fixture;
+	use super::{escape_dn, escape_filter, Ldap};
+	#[cfg(feature = "test-ldap")]
+	use crate::dyn_result::DynResult;
+
+	#[cfg(feature = "test-ldap")]
+	#[tokio::test]
+	async fn authenticate() -> DynResult<()>
+	{
+		let ldap = fixture::ldap();
+
+		let groups = ldap.authenticate(fixture::USERNAME, fixture::PASSWORD).await?;
+		assert_eq!(groups, Some(vec![fixture::GROUP.to_owned()]));
+
+		assert_eq!(ldap.authenticate(fixture::USERNAME, "wrong").await?, None);
+		assert_eq!(ldap.authenticate(fixture::USERNAME, "").await?, None);
+		assert_eq!(ldap.authenticate("nobody", fixture::PASSWORD).await?, None);
+		Ok(())
+	}
+
+	#[test]
+	fn escape()
+	{
+		assert_eq!(escape_dn("someone"), "someone");
+		assert_eq!(escape_dn("Doe, John"), "Doe\\, John");
+		assert_eq!(escape_dn("a=b+c<d>e;f\"g\\h"), "a\\=b\\+c\\<d\\>e\\;f\\\"g\\\\h");
+		assert_eq!(escape_dn("#admin "), "\\#admin\\ ");
+		assert_eq!(escape_dn(" a#b"), "\\ a#b");
+
+		let ldap = Ldap::new("ldap://localhost".into(), "uid={username},dc=example,dc=com".into());
+		assert_eq!(ldap.dn("Doe, John"), "uid=Doe\\, John,dc=example,dc=com");
+
+		assert_eq!(escape_filter("someone"), "someone");
+		assert_eq!(escape_filter("*)(uid=*"), "\\2a\\29\\28uid=\\2a");
+		assert_eq!(escape_filter("a\\b\0"), "a\\5cb\\00");
+	}
+}
@@ -0,0 +1,27 @@
+//! Contains the [`Ldap`] directory on the local machine which tests are run against, and what is in it (see
+//! `fixture.ldif`).
+//!
+//! Its URL is `LDAP_URL`, or `ldap://localhost:1389` when that is not set.
+
+use std::env;
+
+use super::Ldap;
+
+/// The group which the [`USERNAME`] is in.
+pub const GROUP: &str = "winvoice-ldap-test";
+
+/// The password of the [`USERNAME`].
+pub const PASSWORD: &str = "winvoice-ldap-password";
+
+/// The username of a user in the directory.
+pub const USERNAME: &str = "winvoice-ldap-user";
+
+/// The [`Ldap`] configuration of the directory, which finds the groups of users.
+pub fn ldap() -> Ldap
+{
+	Ldap::new(
+		env::var("LDAP_URL").unwrap_or_else(|_| "ldap://localhost:1389".into()),
+		"uid={username},ou=people,dc=example,dc=org".into(),
+	)
+	.with_groups("ou=groups,dc=example,dc=org".into(), "(member={dn})".into())
+}
@@ -0,0 +1,19 @@
+//! Contains the [`Notifier`]s which deliver messages to [`User`]s, such as the links which reset their passwords.
+
+mod log_file;
+mod smtp;
+
+use std::io::Result;
+
+pub use log_file::LogFileNotifier;
+pub use smtp::SmtpNotifier;
+
+use crate::schema::User;
+
+/// Implementors of this trait may deliver messages to [`User`]s.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync
+{
+	/// Deliver a message with the `subject` and `body` to the `user`.
+	async fn notify(&self, user: &User, subject: &str, body: &str) -> Result<()>;
+}
@@ -0,0 +1,72 @@
+//! Contains a [`Notifier`] which appends to a log file.
+
+use std::{io::Result, path::PathBuf};
+
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+use winvoice_schema::chrono::Utc;
+
+use super::Notifier;
+use crate::schema::User;
+
+/// Appends each message to a log file, for an administrator to pass on. Useful when there is no mail server.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LogFileNotifier
+{
+	/// The file which messages are appended to.
+	path: PathBuf,
+}
+
+impl LogFileNotifier
+{
+	/// Create a new [`LogFileNotifier`] which appends to the file at the `path`. It is created when the first message
+	/// is delivered.
+	pub const fn new(path: PathBuf) -> Self
+	{
+		Self { path }
+	}
+}
+
+#[async_trait::async_trait]
+impl Notifier for LogFileNotifier
+{
+	async fn notify(&self, user: &User, subject: &str, body: &str) -> Result<()>
+	{
+		let body = body.replace('\n', " ");
+		let line = format!("{} to {}: {subject}: {body}\n", Utc::now().to_rfc3339(), user.username());
+		let mut file = OpenOptions::new().append(true).create(true).open(&self.path).await?;
+		file.write_all(line.as_bytes()).await?;
+		file.flush().await
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::env;
+
+	use tokio::fs;
+	use winvoice_schema::Id;
+
+	use super::{LogFileNotifier, Notifier};
+	use crate::{dyn_result::DynResult, schema::User};
+
+	#[tokio::test]
+	async fn notify() -> DynResult<()>
+	{
+		let path = env::temp_dir().join(format!("winvoice-server-{}.log", Id::new_v4()));
+		let notifier = LogFileNotifier::new(path.clone());
+		let user = User { username: "someone".into(), ..Default::default() };
+
+		notifier.notify(&user, "Hello", "first\nmessage").await?;
+		notifier.notify(&user, "Goodbye", "second message").await?;
+
+		let contents = fs::read_to_string(&path).await?;
+		let lines: Vec<_> = contents.lines().collect();
+		assert_eq!(lines.len(), 2);
+		assert!(lines[0].ends_with("to someone: Hello: first message"));
+		assert!(lines[1].ends_with("to someone: Goodbye: second message"));
+
+		fs::remove_file(path).await?;
+		Ok(())
+	}
+}
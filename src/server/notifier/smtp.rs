@@ -0,0 +1,204 @@
+//! Contains a [`Notifier`] which sends mail through an SMTP relay.
+
+use std::io::{Error, ErrorKind, Result};
+
+use tokio::{
+	io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+	net::TcpStream,
+};
+use winvoice_schema::chrono::Utc;
+
+use super::Notifier;
+use crate::schema::User;
+
+/// Sends each message as mail to the username of a [`User`] (which must be an email address), through an SMTP relay
+/// which accepts it without authentication or TLS — such as one running on the same host.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SmtpNotifier
+{
+	/// The `host:port` of the SMTP relay.
+	address: String,
+
+	/// The address which mail is sent from.
+	from: String,
+}
+
+impl SmtpNotifier
+{
+	/// Create a new [`SmtpNotifier`] which sends mail `from` an address through the relay at the `address`.
+	pub const fn new(address: String, from: String) -> Self
+	{
+		Self { address, from }
+	}
+
+	/// Read a reply from the relay, and return an error unless its code is one of the `expected`.
+	async fn expect<R>(reader: &mut R, expected: &[&str]) -> Result<()>
+	where
+		R: AsyncBufRead + Send + Unpin,
+	{
+		let mut line = String::new();
+		loop
+		{
+			line.clear();
+			if reader.read_line(&mut line).await? == 0
+			{
+				return Err(ErrorKind::UnexpectedEof.into());
+			}
+
+			// "250-…" continues a reply over another line, and "250 …" ends it
+			if line.as_bytes().get(3) != Some(&b'-')
+			{
+				break;
+			}
+		}
+
+		match line.get(..3)
+		{
+			Some(code) if expected.contains(&code) => Ok(()),
+			_ => Err(Error::new(ErrorKind::Other, format!("The SMTP relay replied {:?}", line.trim_end()))),
+		}
+	}
+
+	/// Send a `command` to the relay, and return an error unless the code of its reply is one of the `expected`.
+	async fn send<R, W>(reader: &mut R, writer: &mut W, command: &str, expected: &[&str]) -> Result<()>
+	where
+		R: AsyncBufRead + Send + Unpin,
+		W: AsyncWrite + Send + Unpin,
+	{
+		writer.write_all(command.as_bytes()).await?;
+		writer.write_all(b"\r\n").await?;
+		writer.flush().await?;
+		Self::expect(reader, expected).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Notifier for SmtpNotifier
+{
+	async fn notify(&self, user: &User, subject: &str, body: &str) -> Result<()>
+	{
+		let to = user.username();
+		if !to.contains('@') || to.contains(|c: char| c.is_whitespace() || c.is_control() || c == '<' || c == '>')
+		{
+			return Err(Error::new(
+				ErrorKind::InvalidInput,
+				format!("The username {to:?} is not an email address which mail can be sent to"),
+			));
+		}
+
+		let mut stream = TcpStream::connect(&self.address).await?;
+		let (reader, mut writer) = stream.split();
+		let mut reader = BufReader::new(reader);
+
+		let domain = self.from.rsplit_once('@').map_or("localhost", |(_, d)| d);
+		Self::expect(&mut reader, &["220"]).await?;
+		Self::send(&mut reader, &mut writer, &format!("HELO {domain}"), &["250"]).await?;
+		Self::send(&mut reader, &mut writer, &format!("MAIL FROM:<{}>", self.from), &["250"]).await?;
+		Self::send(&mut reader, &mut writer, &format!("RCPT TO:<{to}>"), &["250", "251"]).await?;
+		Self::send(&mut reader, &mut writer, "DATA", &["354"]).await?;
+
+		let mut message = format!(
+			"Date: {}\r\nFrom: <{}>\r\nTo: <{to}>\r\nSubject: {}\r\n\r\n",
+			Utc::now().to_rfc2822(),
+			self.from,
+			subject.replace(['\r', '\n'], " "),
+		);
+
+		// a line which starts with '.' gets another, so that it is not mistaken for the end of the message
+		body.lines().for_each(|line| {
+			if line.starts_with('.')
+			{
+				message.push('.');
+			}
+
+			message.push_str(line);
+			message.push_str("\r\n");
+		});
+
+		message.push('.');
+		Self::send(&mut reader, &mut writer, &message, &["250"]).await?;
+		Self::send(&mut reader, &mut writer, "QUIT", &["221"]).await
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use tokio::{
+		io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+		net::TcpListener,
+	};
+
+	use super::{Notifier, SmtpNotifier};
+	use crate::{dyn_result::DynResult, schema::User};
+
+	/// Accept one connection, reply to it like an SMTP relay would, and return the commands which were received.
+	async fn relay(listener: TcpListener) -> Vec<String>
+	{
+		let (mut stream, _) = listener.accept().await.unwrap();
+		let (reader, mut writer) = stream.split();
+		let mut lines = BufReader::new(reader).lines();
+		let mut received = Vec::new();
+
+		writer.write_all(b"220 localhost ready\r\n").await.unwrap();
+		let mut data = false;
+		while let Some(line) = lines.next_line().await.unwrap()
+		{
+			let reply: &[u8] = match line.as_str()
+			{
+				"." if data =>
+				{
+					data = false;
+					b"250 queued\r\n"
+				},
+				_ if data => b"",
+				"DATA" =>
+				{
+					data = true;
+					b"354 go ahead\r\n"
+				},
+				"QUIT" => b"221 bye\r\n",
+				l if l.starts_with("HELO") => b"250-localhost\r\n250 hello\r\n",
+				_ => b"250 ok\r\n",
+			};
+
+			writer.write_all(reply).await.unwrap();
+			received.push(line);
+			if received.last().map(String::as_str) == Some("QUIT")
+			{
+				break;
+			}
+		}
+
+		received
+	}
+
+	#[tokio::test]
+	async fn notify() -> DynResult<()>
+	{
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let notifier = SmtpNotifier::new(listener.local_addr()?.to_string(), "winvoice@example.com".into());
+		let relay = tokio::spawn(relay(listener));
+
+		let user = User { username: "someone@example.com".into(), ..Default::default() };
+		notifier.notify(&user, "Hello", "first line\n.second line").await?;
+
+		let received = relay.await?;
+		assert_eq!(&received[..4], [
+			"HELO example.com",
+			"MAIL FROM:<winvoice@example.com>",
+			"RCPT TO:<someone@example.com>",
+			"DATA",
+		]);
+		assert!(received[4].starts_with("Date: "));
+		assert!(received.contains(&"Subject: Hello".into()));
+		assert!(received.contains(&"first line".into()));
+		assert!(received.contains(&"..second line".into()));
+		assert_eq!(&received[received.len() - 2..], [".", "QUIT"]);
+
+		let user = User { username: "someone".into(), ..Default::default() };
+		assert!(notifier.notify(&user, "Hello", "message").await.is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,489 @@
+//! Contains the configuration of logging in through an [OpenID Connect](https://openid.net/connect/) identity
+//! provider, using the authorization code flow with [PKCE](https://www.rfc-editor.org/rfc/rfc7636).
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+use core::{
+	fmt::{self, Debug, Formatter},
+	time::Duration,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use reqwest::{Client, Response, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use winvoice_schema::{
+	chrono::{self, DateTime, Utc},
+	Id,
+};
+
+use crate::{
+	api::{Code, Status},
+	dyn_result::DynResult,
+};
+
+/// How long the identity provider has to send a user back after they [start logging in](Oidc::authorize).
+const PENDING_TTL: Duration = Duration::from_secs(60 * 10);
+
+/// How long the identity provider has to respond to a request.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The parts of the [discovery document](https://openid.net/specs/openid-connect-discovery-1_0.html) of an identity
+/// provider which are used.
+#[derive(Deserialize)]
+struct Discovery
+{
+	authorization_endpoint: Url,
+	issuer: String,
+	token_endpoint: Url,
+}
+
+/// The parts of the response of the token endpoint of an identity provider which are used.
+#[derive(Deserialize)]
+struct TokenResponse
+{
+	id_token: String,
+}
+
+/// A login which was [started](Oidc::authorize), but not yet [finished](Oidc::claims).
+///
+/// It is kept in the session of the client which started it, so that it can only be finished by that client.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Pending
+{
+	/// When the login can no longer be finished.
+	expires: DateTime<Utc>,
+
+	/// The [`Id`] of the [`User`](crate::schema::User) who was logged in when the login was started, and who the
+	/// identity should be linked to.
+	link: Option<Id>,
+
+	/// The value which the ID token must have for its `nonce` claim.
+	nonce: String,
+
+	/// The value which the identity provider must send the user back with.
+	state: String,
+
+	/// The PKCE code verifier, whose hash was sent to the identity provider as the code challenge.
+	verifier: String,
+}
+
+impl Pending
+{
+	/// The [`Id`] of the [`User`](crate::schema::User) who was logged in when the login was started, and who the
+	/// identity should be linked to.
+	pub const fn link(&self) -> Option<Id>
+	{
+		self.link
+	}
+}
+
+/// The claims of a user which an identity provider vouched for.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Claims
+{
+	/// The groups which the user is in.
+	groups: Vec<String>,
+
+	/// The identity provider which vouched for the user.
+	issuer: String,
+
+	/// What the identity provider calls the user (i.e. the `sub` claim).
+	subject: String,
+
+	/// The username of the user.
+	username: String,
+}
+
+impl Claims
+{
+	/// The groups which the user is in.
+	pub fn groups(&self) -> &[String]
+	{
+		&self.groups
+	}
+
+	/// The identity provider which vouched for the user.
+	pub fn issuer(&self) -> &str
+	{
+		&self.issuer
+	}
+
+	/// What the identity provider calls the user (i.e. the `sub` claim).
+	pub fn subject(&self) -> &str
+	{
+		&self.subject
+	}
+
+	/// The username of the user.
+	pub fn username(&self) -> &str
+	{
+		&self.username
+	}
+}
+
+/// The identity provider which users may log in through, and how its claims are mapped onto
+/// [`User`](crate::schema::User)s.
+#[derive(Clone)]
+pub struct Oidc
+{
+	/// Where users go to log in with the identity provider.
+	authorization_endpoint: Url,
+
+	/// The client which talks to the identity provider.
+	client: Client,
+
+	/// The ID which the server is registered with at the identity provider.
+	client_id: String,
+
+	/// The secret which the server is registered with at the identity provider, if it has one.
+	client_secret: Option<String>,
+
+	/// The claim of the ID token which lists the groups of the user.
+	groups_claim: String,
+
+	/// The URL of the identity provider.
+	issuer: String,
+
+	/// Whether a user who logs in through the identity provider, but does not exist yet, is created.
+	provision: bool,
+
+	/// Where the identity provider sends users after they log in.
+	redirect_url: String,
+
+	/// The scopes which are requested, separated by spaces.
+	scopes: String,
+
+	/// Where codes are exchanged for ID tokens.
+	token_endpoint: Url,
+
+	/// The claim of the ID token which is the username of the user.
+	username_claim: String,
+}
+
+impl Oidc
+{
+	/// Find out the endpoints of the identity provider with the `issuer` URL, which the server is registered with as
+	/// the client with the `client_id` and `client_secret` (if any). Users are sent to the `redirect_url` after they
+	/// log in.
+	///
+	/// By default, the `openid profile email` scopes are requested, the username of a user is their
+	/// `preferred_username` claim, their groups are their `groups` claim, and users are not created when they do not
+	/// exist.
+	pub async fn discover(
+		issuer: String,
+		client_id: String,
+		client_secret: Option<String>,
+		redirect_url: String,
+	) -> DynResult<Self>
+	{
+		let client = Client::builder().timeout(TIMEOUT).build()?;
+		let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+		let bytes = client.get(url).send().await?.error_for_status()?.bytes().await?;
+
+		let discovery = serde_json::from_slice::<Discovery>(&bytes)?;
+		if discovery.issuer != issuer
+		{
+			return Err(format!("The identity provider at {issuer} claims to be {}", discovery.issuer).into());
+		}
+
+		Ok(Self {
+			authorization_endpoint: discovery.authorization_endpoint,
+			client,
+			client_id,
+			client_secret,
+			groups_claim: "groups".into(),
+			issuer,
+			provision: false,
+			redirect_url,
+			scopes: "openid profile email".into(),
+			token_endpoint: discovery.token_endpoint,
+			username_claim: "preferred_username".into(),
+		})
+	}
+
+	/// Start logging in, and return the URL of the identity provider which the user should go to, along with the
+	/// [`Pending`] login which must be kept until they come back. If a user is logged in already, their [`Id`] is the
+	/// one to `link` the identity to.
+	pub fn authorize(&self, link: Option<Id>) -> (String, Pending)
+	{
+		// the TTL is a constant which is well within range
+		let ttl = chrono::Duration::from_std(PENDING_TTL).unwrap();
+		let pending = Pending {
+			expires: Utc::now() + ttl,
+			link,
+			nonce: hex::encode(rand::random::<[u8; 32]>()),
+			state: hex::encode(rand::random::<[u8; 32]>()),
+			verifier: URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>()),
+		};
+
+		let mut url = self.authorization_endpoint.clone();
+		url.query_pairs_mut()
+			.append_pair("response_type", "code")
+			.append_pair("client_id", &self.client_id)
+			.append_pair("redirect_uri", &self.redirect_url)
+			.append_pair("scope", &self.scopes)
+			.append_pair("state", &pending.state)
+			.append_pair("nonce", &pending.nonce)
+			.append_pair("code_challenge", &challenge(&pending.verifier))
+			.append_pair("code_challenge_method", "S256");
+
+		(url.into(), pending)
+	}
+
+	/// Finish the `pending` login by exchanging the `code` which the identity provider sent the user back with (along
+	/// with the `state`), and return the [`Claims`] of the user.
+	///
+	/// The ID token is received directly from the identity provider, so its signature is not checked (as
+	/// [allowed](https://openid.net/specs/openid-connect-core-1_0.html#IDTokenValidation) by the specification). Its
+	/// issuer, audience, expiry, and nonce are.
+	pub async fn claims(&self, code: &str, state: &str, pending: Option<Pending>) -> Result<Claims, Status>
+	{
+		let invalid = |message: String| Status::new(Code::InvalidCredentials, message);
+		let unreachable = |e: reqwest::Error| {
+			tracing::error!("Could not exchange a code with the identity provider: {e}");
+			Status::new(Code::LoginError, format!("Could not reach the identity provider: {e}"))
+		};
+
+		let Pending { nonce, verifier, .. } = pending
+			.filter(|p| p.state == state && p.expires > Utc::now())
+			.ok_or_else(|| invalid("There is no such login in progress, or it has expired".into()))?;
+
+		let mut form = vec![
+			("grant_type", "authorization_code"),
+			("code", code),
+			("redirect_uri", self.redirect_url.as_str()),
+			("client_id", self.client_id.as_str()),
+			("code_verifier", verifier.as_str()),
+		];
+
+		if let Some(s) = &self.client_secret
+		{
+			form.push(("client_secret", s.as_str()));
+		}
+
+		let bytes = self
+			.client
+			.post(self.token_endpoint.clone())
+			.form(&form)
+			.send()
+			.await
+			.and_then(Response::error_for_status)
+			.map_err(unreachable)?
+			.bytes()
+			.await
+			.map_err(unreachable)?;
+
+		let claims = serde_json::from_slice::<TokenResponse>(&bytes)
+			.ok()
+			.and_then(|r| r.id_token.split('.').nth(1).and_then(|p| URL_SAFE_NO_PAD.decode(p).ok()))
+			.and_then(|payload| serde_json::from_slice::<Map<String, Value>>(&payload).ok())
+			.ok_or_else(|| invalid("The identity provider did not respond with an ID token".into()))?;
+
+		if claims.get("iss").and_then(Value::as_str) != Some(self.issuer.as_str())
+		{
+			return Err(invalid("The ID token was not issued by the identity provider".into()));
+		}
+
+		let audience = match claims.get("aud")
+		{
+			Some(Value::String(s)) => s == &self.client_id,
+			Some(Value::Array(a)) => a.iter().any(|v| v.as_str() == Some(self.client_id.as_str())),
+			_ => false,
+		};
+
+		if !audience
+		{
+			return Err(invalid("The ID token is not meant for this server".into()));
+		}
+
+		if claims.get("exp").and_then(Value::as_i64).map_or(true, |exp| exp <= Utc::now().timestamp())
+		{
+			return Err(invalid("The ID token has expired".into()));
+		}
+
+		if claims.get("nonce").and_then(Value::as_str) != Some(nonce.as_str())
+		{
+			return Err(invalid("The ID token is not for this login".into()));
+		}
+
+		let subject = claims
+			.get("sub")
+			.and_then(Value::as_str)
+			.filter(|s| !s.is_empty())
+			.ok_or_else(|| invalid("The ID token has no sub claim".into()))?;
+
+		let username = claims
+			.get(&self.username_claim)
+			.and_then(Value::as_str)
+			.filter(|u| !u.is_empty())
+			.ok_or_else(|| invalid(format!("The ID token has no {} claim", self.username_claim)))?;
+
+		let groups = claims
+			.get(&self.groups_claim)
+			.and_then(Value::as_array)
+			.map(|a| a.iter().filter_map(Value::as_str).map(str::to_owned).collect())
+			.unwrap_or_default();
+
+		Ok(Claims {
+			groups,
+			issuer: self.issuer.clone(),
+			subject: subject.to_owned(),
+			username: username.to_owned(),
+		})
+	}
+
+	/// Whether a user who logs in through the identity provider, but does not exist yet, is created.
+	pub const fn provision(&self) -> bool
+	{
+		self.provision
+	}
+
+	/// Take the username of users from the `username` claim, and their groups from the `groups` claim.
+	pub fn with_claims(mut self, username: String, groups: String) -> Self
+	{
+		self.groups_claim = groups;
+		self.username_claim = username;
+		self
+	}
+
+	/// Set whether a user who logs in through the identity provider, but does not exist yet, is created.
+	pub const fn with_provision(mut self, provision: bool) -> Self
+	{
+		self.provision = provision;
+		self
+	}
+
+	/// Request the `scopes` (in addition to `openid`, which is always requested).
+	pub fn with_scopes(mut self, scopes: &[String]) -> Self
+	{
+		self.scopes = ["openid"]
+			.into_iter()
+			.chain(scopes.iter().map(String::as_str).filter(|s| *s != "openid"))
+			.collect::<Vec<_>>()
+			.join(" ");
+		self
+	}
+}
+
+/// The PKCE code challenge for the `verifier`, using the `S256` method.
+fn challenge(verifier: &str) -> String
+{
+	URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+impl Debug for Oidc
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+	{
+		f.debug_struct("Oidc")
+			.field("client_id", &self.client_id)
+			.field("issuer", &self.issuer)
+			.field("provision", &self.provision)
+			.field("redirect_url", &self.redirect_url)
+			.field("scopes", &self.scopes)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use reqwest::Url;
+	use serde_json::{json, Value};
+	use winvoice_schema::chrono::{Duration, Utc};
+
+	use super::{challenge, mock::MockProvider, Oidc, Pending};
+	use crate::{api::Code, dyn_result::DynResult};
+
+	#[tokio::test]
+	async fn claims() -> DynResult<()>
+	{
+		let provider = MockProvider::spawn("client").await?;
+		let oidc = Oidc::discover(provider.issuer().into(), "client".into(), Some("secret".into()), "/cb".into())
+			.await?
+			.with_scopes(&["email".into(), "groups".into()]);
+
+		{
+			let (url, pending) = oidc.authorize(None);
+			let url = Url::parse(&url)?;
+			let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+
+			assert!(url.as_str().starts_with(&format!("{}/authorize?", provider.issuer())));
+			assert_eq!(param("scope").as_deref(), Some("openid email groups"));
+			assert_eq!(param("redirect_uri").as_deref(), Some("/cb"));
+			assert_eq!(param("state"), Some(pending.state.clone()));
+			assert_eq!(param("code_challenge"), Some(challenge(&pending.verifier)));
+			assert_eq!(param("code_challenge_method").as_deref(), Some("S256"));
+		}
+
+		let claims = |pending: &Pending| {
+			json!({
+				"aud": "client",
+				"exp": Utc::now().timestamp() + 60,
+				"groups": ["staff", "admins"],
+				"iss": provider.issuer(),
+				"nonce": pending.nonce,
+				"preferred_username": "someone",
+				"sub": "1234",
+			})
+		};
+
+		let issue = |pending: &Pending, claims: Value| provider.issue(&challenge(&pending.verifier), claims);
+
+		let (_, pending) = oidc.authorize(None);
+		let actual = oidc.claims(&issue(&pending, claims(&pending)), &pending.state, Some(pending.clone())).await?;
+		assert_eq!(actual.groups(), ["staff", "admins"]);
+		assert_eq!(actual.issuer(), provider.issuer());
+		assert_eq!(actual.subject(), "1234");
+		assert_eq!(actual.username(), "someone");
+
+		// a login can only be finished by the client which started it, before it expires
+		let code = issue(&pending, claims(&pending));
+		let expired = Pending { expires: Utc::now() - Duration::minutes(1), ..pending.clone() };
+		let attempts = [
+			("wrong", Some(pending.clone())),
+			(pending.state.as_str(), None),
+			(pending.state.as_str(), Some(expired)),
+		];
+
+		for (state, pending) in attempts
+		{
+			let error = oidc.claims(&code, state, pending).await.unwrap_err();
+			assert_eq!(error.code(), Code::InvalidCredentials, "{}", error.message());
+		}
+
+		let mutations: [fn(&mut Value); 6] = [
+			|c| c["aud"] = json!("someone else"),
+			|c| c["exp"] = json!(Utc::now().timestamp() - 60),
+			|c| c["iss"] = json!("http://localhost:1"),
+			|c| c["nonce"] = json!("wrong"),
+			|c| c["preferred_username"] = Value::Null,
+			|c| c["sub"] = Value::Null,
+		];
+
+		for mutate in mutations
+		{
+			let (_, pending) = oidc.authorize(None);
+			let mut c = claims(&pending);
+			mutate(&mut c);
+
+			let error = oidc.claims(&issue(&pending, c), &pending.state, Some(pending.clone())).await.unwrap_err();
+			assert_eq!(error.code(), Code::InvalidCredentials, "{}", error.message());
+		}
+
+		// the identity provider rejects a code which it did not issue, or which was issued for another login
+		let (_, pending) = oidc.authorize(None);
+		let error = oidc.claims("wrong", &pending.state, Some(pending.clone())).await.unwrap_err();
+		assert_eq!(error.code(), Code::LoginError);
+
+		let (_, other) = oidc.authorize(None);
+		let error = oidc.claims(&issue(&other, claims(&pending)), &pending.state, Some(pending)).await.unwrap_err();
+		assert_eq!(error.code(), Code::LoginError);
+
+		Ok(())
+	}
+}
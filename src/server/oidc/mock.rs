@@ -0,0 +1,85 @@
+//! Contains a [`MockProvider`] of identities to test logging in through [OpenID Connect](super).
+
+use std::{
+	collections::HashMap,
+	net::TcpListener,
+	sync::{Arc, Mutex},
+};
+
+use axum::{extract::State, http::StatusCode, routing, Form, Json, Router};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::{json, Value};
+
+use super::challenge;
+use crate::dyn_result::DynResult;
+
+/// An identity provider on the local machine, which issues whatever claims it is told to.
+#[derive(Clone, Debug)]
+pub struct MockProvider
+{
+	/// The PKCE code challenge and claims of the ID tokens which have been issued, by the code which they are
+	/// exchanged for.
+	codes: Arc<Mutex<HashMap<String, (String, Value)>>>,
+
+	/// The URL of the provider.
+	issuer: String,
+}
+
+impl MockProvider
+{
+	/// Start a provider which only accepts the client with the `client_id`.
+	pub async fn spawn(client_id: &'static str) -> DynResult<Self>
+	{
+		let listener = TcpListener::bind("127.0.0.1:0")?;
+		let provider = Self { codes: Arc::default(), issuer: format!("http://{}", listener.local_addr()?) };
+
+		let discovery = json!({
+			"authorization_endpoint": format!("{}/authorize", provider.issuer),
+			"issuer": provider.issuer,
+			"token_endpoint": format!("{}/token", provider.issuer),
+		});
+
+		let router = Router::new()
+			.route("/.well-known/openid-configuration", routing::get(|| async move { Json(discovery) }))
+			.route(
+				"/token",
+				routing::post(
+					move |State(codes): State<Arc<Mutex<HashMap<String, (String, Value)>>>>,
+					      Form(form): Form<HashMap<String, String>>| async move {
+						let claims = form
+							.get("code")
+							.filter(|_| form.get("client_id").map(String::as_str) == Some(client_id))
+							.and_then(|code| codes.lock().unwrap().remove(code))
+							.filter(|(c, _)| {
+								form.get("code_verifier").map(String::as_str).map(challenge).as_ref() == Some(c)
+							})
+							.map(|(_, claims)| claims)
+							.ok_or(StatusCode::BAD_REQUEST)?;
+
+						let encode = |value: &Value| URL_SAFE_NO_PAD.encode(value.to_string());
+						let id_token = format!("{}.{}.", encode(&json!({ "alg": "none" })), encode(&claims));
+						Ok::<_, StatusCode>(Json(json!({ "access_token": "mock", "id_token": id_token })))
+					},
+				),
+			)
+			.with_state(provider.codes.clone());
+
+		tokio::spawn(axum::Server::from_tcp(listener)?.serve(router.into_make_service()));
+		Ok(provider)
+	}
+
+	/// Issue an ID token with the `claims` to the login which sent the PKCE code `challenge`, and return the code which
+	/// it is exchanged for.
+	pub fn issue(&self, challenge: &str, claims: Value) -> String
+	{
+		let code = hex::encode(rand::random::<[u8; 16]>());
+		self.codes.lock().unwrap().insert(code.clone(), (challenge.to_owned(), claims));
+		code
+	}
+
+	/// The URL of the provider.
+	pub fn issuer(&self) -> &str
+	{
+		&self.issuer
+	}
+}
@@ -0,0 +1,49 @@
+//! Contains the configuration of the tokens which reset the passwords of users.
+
+use core::{
+	fmt::{self, Debug, Formatter},
+	time::Duration,
+};
+use std::sync::Arc;
+
+use super::notifier::Notifier;
+
+/// How the tokens which reset the passwords of users are delivered, and how long they last.
+#[derive(Clone)]
+pub struct PasswordResets
+{
+	/// What delivers the tokens to users.
+	notifier: Arc<dyn Notifier>,
+
+	/// How long a token lasts before it expires.
+	ttl: Duration,
+}
+
+impl PasswordResets
+{
+	/// Create a new [`PasswordResets`] configuration.
+	pub fn new(notifier: impl Notifier + 'static, ttl: Duration) -> Self
+	{
+		Self { notifier: Arc::new(notifier), ttl }
+	}
+
+	/// What delivers the tokens to users.
+	pub fn notifier(&self) -> &dyn Notifier
+	{
+		self.notifier.as_ref()
+	}
+
+	/// How long a token lasts before it expires.
+	pub const fn ttl(&self) -> Duration
+	{
+		self.ttl
+	}
+}
+
+impl Debug for PasswordResets
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+	{
+		f.debug_struct("PasswordResets").field("ttl", &self.ttl).finish_non_exhaustive()
+	}
+}
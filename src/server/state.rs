@@ -2,23 +2,52 @@
 
 mod clone;
 
+use core::time::Duration;
+
 use casbin::{CoreApi, Enforcer};
+use futures::{future::BoxFuture, FutureExt};
 use sqlx::{Database, Pool};
+use tokio::sync::broadcast;
 
 use super::response::Response;
 use crate::{
-	api::{Code, Status},
+	api::{Code, Entity, Event, EventKind, Status},
 	bool_ext::BoolExt,
 	lock::Lock,
 	permissions::{Action, Object},
-	schema::User,
+	schema::{EventAdapter, User},
 };
 
+/// How many [`Event`]s may be waiting for the slowest subscriber before it starts missing them.
+const EVENT_CAPACITY: usize = 256;
+
+/// How long to wait before listening for [`Event`]s again, after the last attempt failed.
+const RELISTEN_DELAY: Duration = Duration::from_secs(5);
+
+/// Stores an [`Event`] with the `connection` of the transaction which made the changes it describes, so that it is
+/// published if and only if they are committed.
+type Outbox<Db> = for<'con> fn(&'con mut <Db as Database>::Connection, Event) -> BoxFuture<'con, sqlx::Result<()>>;
+
+/// [Publish](EventAdapter::publish) the `event` with `E`, as an [`Outbox`].
+fn store<E>(connection: &mut <E::Db as Database>::Connection, event: Event) -> BoxFuture<'_, sqlx::Result<()>>
+where
+	E: EventAdapter,
+{
+	async move { E::publish(connection, &event).await }.boxed()
+}
+
 /// The state which is shared by the server.
 pub struct ServerState<Db>
 where
 	Db: Database,
 {
+	/// The [`Event`]s which were published by every replica of the server.
+	events: broadcast::Sender<Event>,
+
+	/// Where [`Event`]s are stored to be published to every replica of the server. If [`None`], events are only sent
+	/// to the subscribers of this replica, as soon as they are published.
+	outbox: Option<Outbox<Db>>,
+
 	/// The user permissions.
 	permissions: Lock<Enforcer>,
 
@@ -125,8 +154,72 @@ where
 		Ok(object)
 	}
 
+	/// Tell every subscriber that the `entities` were changed in some way.
+	///
+	/// The [`Event`] is stored with the `connection` of the transaction which changed the `entities`, so it should be
+	/// published before that transaction is committed. Then, the event is only published if the changes are, and
+	/// the changes are only committed if the event can be published too.
+	pub async fn publish<T>(
+		&self,
+		connection: &mut Db::Connection,
+		kind: EventKind,
+		entities: impl IntoIterator<Item = T>,
+	) -> sqlx::Result<()>
+	where
+		T: Into<Entity>,
+	{
+		let entities = entities.into_iter().map(Into::into).collect::<Vec<_>>();
+		if entities.is_empty()
+		{
+			return Ok(());
+		}
+
+		let event = Event::new(kind, entities);
+		match self.outbox
+		{
+			Some(outbox) => outbox(connection, event).await,
+
+			// `Err` only means that there are no subscribers right now
+			None =>
+			{
+				self.events.send(event).ok();
+				Ok(())
+			},
+		}
+	}
+
+	/// Receive the [`Event`]s which are [`publish`](Self::publish)ed from now on.
+	pub fn subscribe(&self) -> broadcast::Receiver<Event>
+	{
+		self.events.subscribe()
+	}
+
+	/// Use `E` to [`publish`](Self::publish) [`Event`]s to every replica of the server, and to receive theirs.
+	///
+	/// The [`EventAdapter`] should already be [initialized](EventAdapter::init).
+	pub fn with_event_adapter<E>(self) -> Self
+	where
+		E: 'static + EventAdapter<Db = Db>,
+	{
+		let pool = self.pool.clone();
+		let events = self.events.clone();
+		tokio::spawn(async move {
+			loop
+			{
+				if let Err(e) = E::listen(&pool, &events).await
+				{
+					tracing::error!("Stopped listening for events: {e}");
+				}
+
+				tokio::time::sleep(RELISTEN_DELAY).await;
+			}
+		});
+
+		Self { outbox: Some(store::<E>), ..self }
+	}
+
 	/// Check whether `user` has permission to perform an `action` on the `object`.
-	async fn has_permission<R>(&self, user: &User, object: Object, action: Action) -> Result<bool, Response<R>>
+	pub async fn has_permission<R>(&self, user: &User, object: Object, action: Action) -> Result<bool, Response<R>>
 	where
 		R: AsRef<Code> + From<Status>,
 	{
@@ -159,9 +252,16 @@ where
 	}
 
 	/// Create new [`State`]
-	pub const fn new(permissions: Lock<Enforcer>, pool: Pool<Db>) -> Self
+	pub fn new(permissions: Lock<Enforcer>, pool: Pool<Db>) -> Self
+	{
+		let (events, _) = broadcast::channel(EVENT_CAPACITY);
+		Self { events, outbox: None, pool, permissions }
+	}
+
+	/// The permissions which users are checked against.
+	pub const fn permissions(&self) -> &Lock<Enforcer>
 	{
-		Self { pool, permissions }
+		&self.permissions
 	}
 
 	/// Post the [`Pool`] of connections to the [`Database`].
@@ -8,6 +8,11 @@ where
 {
 	fn clone(&self) -> Self
 	{
-		Self { permissions: self.permissions.clone(), pool: self.pool.clone() }
+		Self {
+			events: self.events.clone(),
+			outbox: self.outbox.clone(),
+			permissions: self.permissions.clone(),
+			pool: self.pool.clone(),
+		}
 	}
 }
@@ -0,0 +1,22 @@
+//! Contains the [`Storage`] of files, such as the receipts which are attached to expenses.
+
+mod local;
+
+use std::io::Result;
+
+pub use local::LocalStorage;
+use winvoice_schema::Id;
+
+/// Implementors of this trait may store the contents of files.
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync
+{
+	/// Delete the contents of the file with the `id`. Does nothing if there is no such file.
+	async fn delete(&self, id: Id) -> Result<()>;
+
+	/// Read the contents of the file with the `id`.
+	async fn read(&self, id: Id) -> Result<Vec<u8>>;
+
+	/// Write the `contents` of the file with the `id`, replacing any which were there before.
+	async fn write(&self, id: Id, contents: &[u8]) -> Result<()>;
+}
@@ -0,0 +1,90 @@
+//! Contains a [`Storage`] in a local directory.
+
+use std::{
+	io::{ErrorKind, Result},
+	path::PathBuf,
+};
+
+use tokio::fs;
+use winvoice_schema::Id;
+
+use super::Storage;
+
+/// Stores each file in a local directory, named after its [`Id`].
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LocalStorage
+{
+	/// The directory which files are stored in.
+	root: PathBuf,
+}
+
+impl LocalStorage
+{
+	/// Create a new [`LocalStorage`] in the `root` directory. It is created when the first file is written.
+	pub const fn new(root: PathBuf) -> Self
+	{
+		Self { root }
+	}
+
+	/// The path of the file with the `id`.
+	fn path(&self, id: Id) -> PathBuf
+	{
+		self.root.join(id.to_string())
+	}
+}
+
+#[async_trait::async_trait]
+impl Storage for LocalStorage
+{
+	async fn delete(&self, id: Id) -> Result<()>
+	{
+		match fs::remove_file(self.path(id)).await
+		{
+			Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+			result => result,
+		}
+	}
+
+	async fn read(&self, id: Id) -> Result<Vec<u8>>
+	{
+		fs::read(self.path(id)).await
+	}
+
+	async fn write(&self, id: Id, contents: &[u8]) -> Result<()>
+	{
+		fs::create_dir_all(&self.root).await?;
+		fs::write(self.path(id), contents).await
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::env;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::Id;
+
+	use super::{LocalStorage, Storage};
+	use crate::dyn_result::DynResult;
+
+	#[tokio::test]
+	async fn storage() -> DynResult<()>
+	{
+		let storage = LocalStorage::new(env::temp_dir().join(format!("winvoice-server-{}", Id::new_v4())));
+		let id = Id::new_v4();
+
+		storage.write(id, b"receipt").await?;
+		assert_eq!(storage.read(id).await?, b"receipt");
+
+		storage.write(id, b"other receipt").await?;
+		assert_eq!(storage.read(id).await?, b"other receipt");
+
+		storage.delete(id).await?;
+		assert!(storage.read(id).await.is_err());
+
+		// deleting a file which does not exist is not an error
+		storage.delete(id).await?;
+		Ok(())
+	}
+}
@@ -4,6 +4,7 @@
 mod postgres;
 
 use core::{iter, time::Duration};
+use std::{env, path::PathBuf};
 
 use axum_test_helper::TestClient;
 use casbin::{CoreApi, Enforcer};
@@ -60,6 +61,7 @@ use crate::{
 		response::{Export, Login, Logout, Post, Put, Version, WhoAmI},
 		Code,
 		Status,
+		Violation,
 	},
 	lock,
 	permissions::{Action, Object},
@@ -69,6 +71,9 @@ use crate::{
 	utils,
 };
 
+const DEFAULT_ATTACHMENT_MAX_SIZE: usize = 1024;
+const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(60 * 2);
+const DEFAULT_PASSWORD_RESET_TTL: Duration = Duration::from_secs(60 * 2);
 const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(60 * 2);
 const DEFAULT_TIMEOUT: Option<Duration> = Some(Duration::from_secs(60 * 3));
 
@@ -113,6 +118,12 @@ fn job_args_fmt() -> (Option<DateTime<Utc>>, DateTime<Utc>, String, Invoice, Str
 	(args.0, args.1, fmt_duration(args.2), args.3, args.4, args.5)
 }
 
+/// The file which the [`LogFileNotifier`] of the `test` appends to.
+fn notification_log(test: &str) -> PathBuf
+{
+	env::temp_dir().join(format!("winvoice-server-{test}-notifications.log"))
+}
+
 /// The fields for a [`Location`]
 fn location_args() -> (Option<Currency>, String, Option<Location>)
 {
@@ -149,6 +160,13 @@ macro_rules! fn_setup {
 		///
 		/// * `(client, pool, admin, admin_password, guest, guest_password)`
 		async fn setup(test: &str) -> DynResult<TestData<$Db>>
+		{
+			setup_with(test, None, None).await
+		}
+
+		/// Same as [`setup`], but users may also be authenticated by the `ldap` directory, or log in through the `oidc`
+		/// identity provider.
+		async fn setup_with(test: &str, ldap: Option<Ldap>, oidc: Option<Oidc>) -> DynResult<TestData<$Db>>
 		{
 			let mut role_names = ::std::collections::BTreeSet::new();
 			while role_names.len() < 4
@@ -177,12 +195,16 @@ macro_rules! fn_setup {
 						admin(Object::Department)?;
 						admin(Object::Employee)?;
 						admin(Object::Expenses)?;
+						admin(Object::InvoicedJob)?;
 						admin(Object::Job)?;
 						admin(Object::Location)?;
 						admin(Object::Organization)?;
+						admin(Object::Rate)?;
 						admin(Object::Role)?;
+						admin(Object::SavedQuery)?;
 						admin(Object::Timesheet)?;
 						admin(Object::User)?;
+						admin(Object::Webhook)?;
 					}
 
 					{
@@ -206,6 +228,11 @@ macro_rules! fn_setup {
 				policy_csv.serialize(("p", &grunt_role_name, Object::EmployeeSelf, Action::Update))?;
 				policy_csv.serialize(("p", &grunt_role_name, Object::UserSelf, Action::Retrieve))?;
 				policy_csv.serialize(("p", &grunt_role_name, Object::UserSelf, Action::Update))?;
+				policy_csv.serialize(("p", &admin_role_name, Object::Timesheet, Action::Approve))?;
+				policy_csv.serialize(("p", &admin_role_name, Object::Timesheet, Action::Submit))?;
+				policy_csv.serialize(("p", &grunt_role_name, Object::CreatedTimesheet, Action::Submit))?;
+				policy_csv.serialize(("p", &manager_role_name, Object::TimesheetInDepartment, Action::Approve))?;
+				policy_csv.serialize(("p", &manager_role_name, Object::TimesheetInDepartment, Action::Submit))?;
 
 				let inner = policy_csv.into_inner()?;
 				String::from_utf8(inner)?
@@ -227,9 +254,19 @@ macro_rules! fn_setup {
 
 			let pool = $connect();
 			let server = Server::<$Adapter>::router(
+				Attachments::new(
+					DEFAULT_ATTACHMENT_MAX_SIZE,
+					LocalStorage::new(env::temp_dir().join("winvoice-server-attachments")),
+					vec!["application/pdf".into(), "text/plain".into()],
+				),
+				Bootstrap::default(),
 				None,
 				utils::cookie_secret(),
 				Vec::default(),
+				DEFAULT_IDEMPOTENCY_TTL,
+				ldap,
+				oidc,
+				PasswordResets::new(LogFileNotifier::new(notification_log(test)), DEFAULT_PASSWORD_RESET_TTL),
 				ServerState::<$Db>::new(enforcer, pool.clone()),
 				DEFAULT_SESSION_TTL,
 				DEFAULT_TIMEOUT,
@@ -330,6 +367,14 @@ macro_rules! fn_setup {
 
 						let response = client.post_builder(route).body("").send().await;
 						assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+						assert_eq!(response.json::<Status>().await.code(), Code::InvalidContentType);
+
+						let response = client.post_builder(route).json(&request::Post::new(5)).send().await;
+						assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+						let status = response.json::<Status>().await;
+						assert_eq!(status.code(), Code::ValidationError);
+						assert_eq!(status.violations().iter().map(Violation::path).collect::<Vec<_>>(), ["condition"]);
 
 						client.logout().await;
 					}
@@ -0,0 +1,146 @@
+use axum::http::header;
+use pretty_assertions::assert_eq;
+use winvoice_schema::Id;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::schema::{postgres::PgAttachment, Attachment, AttachmentAdapter};
+
+#[tokio::test]
+#[traced_test]
+async fn attachment() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, pool, .. } = setup("attachment").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let (job_, timesheet) = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		let (_, time_begin, time_end, work_notes) = timesheet_args();
+		let t = PgTimesheet::create(
+			&mut tx,
+			grunt.0.employee().unwrap().clone(),
+			vec![(words::word(), Money::new(20_00, 2, j.invoice.hourly_rate.currency), words::sentence(3))],
+			j.clone(),
+			time_begin,
+			time_end,
+			work_notes,
+		)
+		.await?;
+
+		tx.commit().await?;
+		(j, t)
+	};
+
+	let expense_id = timesheet.expenses[0].id;
+	let upload = |content_type: &'static str, contents: Vec<u8>| {
+		client
+			.put_builder(&format!("{}?expense={expense_id}&name=receipt.txt", routes::EXPENSE_ATTACHMENT))
+			.header(header::CONTENT_TYPE, content_type)
+			.body(contents)
+			.send()
+	};
+
+	let list = || client.post_builder(routes::EXPENSE_ATTACHMENT).json(&request::Post::new(expense_id)).send();
+
+	client.login(&admin.0, &admin.1).await;
+
+	let attachment = {
+		let response = upload("text/plain", b"receipt".to_vec()).await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		response.json::<Put<Attachment>>().await.into_entity().unwrap()
+	};
+
+	assert_eq!(attachment.expense_id(), expense_id);
+	assert_eq!(attachment.size(), 7);
+	assert_eq!(list().await.json::<Post<Attachment>>().await.into_entities(), [attachment.clone()]);
+
+	{
+		let uri = format!("{}?id={}", routes::EXPENSE_ATTACHMENT, attachment.id());
+		let response = client.get_builder(&uri).send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+		assert_eq!(response.bytes().await.as_ref(), b"receipt");
+	}
+
+	assert_eq!(
+		upload("image/gif", b"receipt".to_vec()).await.status(),
+		StatusCode::from(Code::AttachmentTypeNotAllowed),
+	);
+
+	assert_eq!(
+		upload("text/plain", vec![b'a'; DEFAULT_ATTACHMENT_MAX_SIZE + 1]).await.status(),
+		StatusCode::from(Code::AttachmentTooLarge),
+	);
+
+	client.logout().await;
+
+	client.login(&guest.0, &guest.1).await;
+	assert_eq!(list().await.status(), StatusCode::from(Code::Unauthorized));
+	client.logout().await;
+
+	client.login(&admin.0, &admin.1).await;
+	{
+		// if any of the attachments cannot be deleted, none of them are
+		let response = client
+			.delete_builder(routes::EXPENSE_ATTACHMENT)
+			.json(&request::Delete::new(vec![attachment.id(), Id::new_v4()]))
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::from(Code::InvalidReference));
+		assert_eq!(list().await.json::<Post<Attachment>>().await.into_entities(), [attachment.clone()]);
+
+		let response = client
+			.delete_builder(routes::EXPENSE_ATTACHMENT)
+			.json(&request::Delete::new(vec![attachment.id()]))
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		assert!(list().await.json::<Post<Attachment>>().await.entities().is_empty());
+	}
+
+	let orphan = upload("text/plain", b"receipt".to_vec()).await.json::<Put<Attachment>>().await.into_entity().unwrap();
+	client.logout().await;
+
+	// the contents of attachments which are deleted along with their expense must still be removed
+	{
+		let storage = LocalStorage::new(env::temp_dir().join("winvoice-server-attachments"));
+		assert!(storage.read(orphan.id()).await.is_ok());
+		PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+
+		// the server may already have removed them
+		let deleted = PgAttachment::deleted(&pool, i64::MAX).await?.contains(&orphan.id());
+		assert!(deleted || storage.read(orphan.id()).await.is_err());
+
+		let attachments = Attachments::new(DEFAULT_ATTACHMENT_MAX_SIZE, storage.clone(), Vec::new());
+		assert!(attachments.remove::<PgAttachment>(&pool, &[orphan.id()]).await);
+		assert!(storage.read(orphan.id()).await.is_err());
+		assert!(!PgAttachment::deleted(&pool, i64::MAX).await?.contains(&orphan.id()));
+	}
+
+	PgJob::delete(&pool, [&job_].into_iter()).await?;
+	PgOrganization::delete(&pool, [&organization].into_iter()).await?;
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,162 @@
+use pretty_assertions::assert_eq;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::{
+	request::{Arg, Batch, Operation},
+	Entity,
+};
+
+#[tokio::test]
+#[traced_test]
+async fn batch() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("batch").await?;
+
+	let location_operation = || {
+		let (currency, name, _) = location_args();
+		Operation::Location { currency, name, outer: None }
+	};
+
+	let operations = {
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let (expenses, time_begin, time_end, work_notes) = timesheet_args();
+		vec![
+			location_operation(),
+			Operation::Organization { location: Arg::Ref { r#ref: 0 }, name: company::company() },
+			Operation::Department { name: rand_department_name() },
+			Operation::Job {
+				client: Arg::Ref { r#ref: 1 },
+				date_close,
+				date_open,
+				departments: vec![Arg::Ref { r#ref: 2 }],
+				increment,
+				invoice,
+				notes,
+				objectives,
+			},
+			Operation::Employee { department: Arg::Ref { r#ref: 2 }, name: name::full(), title: job::title() },
+			Operation::Timesheet {
+				employee: Arg::Ref { r#ref: 4 },
+				expenses,
+				job: Arg::Ref { r#ref: 3 },
+				time_begin,
+				time_end,
+				work_notes,
+			},
+			Operation::Expenses { expenses: vec![expense_args()], timesheet: Arg::Ref { r#ref: 5 } },
+		]
+	};
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client.put_builder(routes::BATCH).json(&Batch::new(operations.clone())).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Unauthorized));
+		assert!(actual.content().status().message().starts_with("operation 0:"));
+		client.logout().await;
+	}
+
+	client.login(&admin.0, &admin.1).await;
+
+	// each operation is validated like a PUT to its route would be
+	{
+		let response = client
+			.put_builder(routes::BATCH)
+			.json(&Batch::new(vec![location_operation(), Operation::Department { name: " ".into() }]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::ValidationError));
+		assert_eq!(actual.content().status().violations().iter().map(Violation::path).collect::<Vec<_>>(), [
+			"operations[1].department.name"
+		]);
+	}
+
+	// a bad reference rolls back the operations before it
+	{
+		let location = location_operation();
+		let response = client
+			.put_builder(routes::BATCH)
+			.json(&Batch::new(vec![location.clone(), Operation::Organization {
+				location: Arg::Ref { r#ref: 1 },
+				name: company::company(),
+			}]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::InvalidReference));
+		assert_eq!(actual.content().status().code(), Code::InvalidReference);
+		assert!(actual.content().entities().is_empty());
+
+		let name = match location
+		{
+			Operation::Location { name, .. } => name,
+			_ => unreachable!("`location_operation` creates a location"),
+		};
+
+		let rows = PgLocation::retrieve(&pool, MatchLocation { name: name.into(), ..Default::default() }).await?;
+		assert!(rows.is_empty());
+	}
+
+	let entities = {
+		let response = client.put_builder(routes::BATCH).json(&Batch::new(operations)).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		actual.content().entities().to_vec()
+	};
+
+	client.logout().await;
+
+	let mut entities = entities.into_iter();
+	macro_rules! next {
+		($Variant:ident) => {
+			match entities.next()
+			{
+				Some(Entity::$Variant(e)) => e,
+				e => panic!("Expected the batch to create a {}, but got {e:?}", stringify!($Variant)),
+			}
+		};
+	}
+
+	let location = next!(Location);
+	let organization = next!(Organization);
+	let department = next!(Department);
+	let job_ = next!(Job);
+	let employee = next!(Employee);
+	let timesheet = next!(Timesheet);
+	let expenses = next!(Expenses);
+
+	assert_eq!(organization.location, location);
+	assert_eq!(job_.client, organization);
+	assert_eq!(job_.departments, [department.clone()].into_iter().collect());
+	assert_eq!(employee.department, department);
+	assert_eq!((timesheet.employee.id, timesheet.job.id), (employee.id, job_.id));
+	assert_eq!(expenses.iter().map(|x| x.timesheet_id).collect::<Vec<_>>(), [timesheet.id]);
+
+	let row = PgTimesheet::retrieve(&pool, timesheet.id.into()).await.map(|mut v| v.remove(0))?;
+	assert_eq!(row.id, timesheet.id);
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgExpenses::delete(&pool, expenses.iter()).await?;
+	PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+	futures::try_join!(
+		PgUser::delete(&pool, users.iter()),
+		PgJob::delete(&pool, [&job_].into_iter()),
+		PgEmployee::delete(&pool, [&employee].into_iter()),
+	)?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+		PgDepartment::delete(&pool, [&department].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,132 @@
+use pretty_assertions::assert_eq;
+use winvoice_schema::{Id, Timesheet};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::response::Patch,
+	schema::{postgres::PgBudget, Allowance, Budget, BudgetAdapter, BudgetUsage},
+};
+
+/// Set the `budget` as the logged in user, and assert that it results in the `code`.
+async fn assert_set(client: &TestClient, budget: &Budget, code: Code)
+{
+	tracing::trace!("Asserting PUT {} with {budget:?} results in Code::{code:?}", routes::JOB_BUDGET);
+	let response = client.put_builder(routes::JOB_BUDGET).json(&request::Put::new(budget)).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Put<Budget>>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+}
+
+/// Retrieve the [`BudgetUsage`] of the job with the `id` as the logged in user.
+async fn usage(client: &TestClient, id: Id) -> Vec<BudgetUsage>
+{
+	let response = client.post_builder(routes::JOB_BUDGET).json(&request::Post::new(MatchJob::from(id))).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Post<BudgetUsage>>().await);
+	assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+	actual.content().entities().to_vec()
+}
+
+#[tokio::test]
+#[traced_test]
+async fn budget() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("budget").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let job_ = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		tx.commit().await?;
+		j
+	};
+
+	let budget = Budget::new(Allowance::Time(Duration::from_secs(4 * 60 * 60)), job_.id, [50, 100].into());
+
+	client.login(&grunt.0, &grunt.1).await;
+	assert_set(&client, &budget, Code::Unauthorized).await;
+	client.logout().await;
+
+	client.login(&guest.0, &guest.1).await;
+	assert_set(&client, &budget, Code::Unauthorized).await;
+	client.logout().await;
+
+	client.login(&manager.0, &manager.1).await;
+	let nothing = Budget::new(Allowance::Time(Duration::ZERO), job_.id, [50].into());
+	assert_set(&client, &nothing, Code::ValidationError).await;
+	assert_set(&client, &budget, Code::SuccessForPermissions).await;
+	assert_eq!(PgBudget::retrieve(&pool, &[job_.id]).await?, [budget.clone()]);
+
+	let timesheet = {
+		// three of the four hours in the budget, which passes the 50% threshold but not the 100% one
+		let (expenses, time_begin, time_end, work_notes) = timesheet_args();
+		let args = (grunt.0.employee().unwrap().clone(), expenses, job_.clone(), time_begin, time_end, work_notes);
+		let response = client.put_builder(routes::TIMESHEET).json(&request::Put::new(&args)).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Put<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+
+		let warnings = actual.content().status().warnings();
+		assert_eq!(warnings.len(), 1);
+		assert!(warnings[0].contains("passing the 50% threshold"), "unexpected warning: {}", warnings[0]);
+		actual.content().entity().cloned().unwrap()
+	};
+
+	let usages = usage(&client, job_.id).await;
+	assert_eq!(usages.iter().map(|u| (u.consumed(), u.remaining())).collect::<Vec<_>>(), [(
+		&Allowance::Time(Duration::from_secs(3 * 60 * 60)),
+		&Allowance::Time(Duration::from_secs(60 * 60))
+	)]);
+
+	// five of the four hours, which passes the 100% threshold, but only once
+	let patched = Timesheet { time_end: Some(timesheet.time_begin + chrono::Duration::hours(5)), ..timesheet.clone() };
+	for expected in [1, 0]
+	{
+		let response = client.patch_builder(routes::TIMESHEET).json(&request::Patch::new(vec![&patched])).send().await;
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+
+		let warnings = actual.content().status().warnings();
+		assert_eq!(warnings.len(), expected);
+		assert!(warnings.iter().all(|w| w.contains("passing the 100% threshold")), "unexpected warnings: {warnings:?}");
+	}
+
+	let response = client.delete_builder(routes::JOB_BUDGET).json(&request::Delete::new(vec![job_.id])).send().await;
+	assert_eq!(response.status(), StatusCode::from(Code::SuccessForPermissions));
+	assert!(usage(&client, job_.id).await.is_empty());
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,122 @@
+use axum::http::header;
+use pretty_assertions::assert_eq;
+use reqwest::Url;
+use winvoice_schema::Location;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::schema::SavedQuery;
+
+/// The path and query string of a `GET` request to the `route`, with the `params`.
+fn uri(route: &str, params: &[(&str, &str)]) -> String
+{
+	let url = Url::parse_with_params("http://localhost", params).unwrap();
+	format!("{route}?{}", url.query().unwrap())
+}
+
+#[tokio::test]
+#[traced_test]
+async fn conditional() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, pool, .. } = setup("conditional").await?;
+
+	let (mut location, mut outer) = {
+		let (currency, _, outer) = location_args();
+		let outer = PgLocation::create(&pool, currency, words::sentence(5), outer).await?;
+		(PgLocation::create(&pool, currency, words::sentence(5), Some(outer.clone())).await?, outer)
+	};
+
+	let condition = MatchLocation { id: location.id.into(), ..Default::default() };
+	let json = serde_json::to_string(&condition)?;
+
+	client.login(&admin.0, &admin.1).await;
+
+	let etag = {
+		let response = client.get_builder(&uri(routes::LOCATION, &[("condition", &json)])).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+
+		let etag = response.headers()[header::ETAG].clone();
+		assert_eq!(response.json::<Post<Location>>().await.entities(), [location.clone()]);
+		etag
+	};
+
+	{
+		let response = client
+			.get_builder(&uri(routes::LOCATION, &[("condition", &json)]))
+			.header(header::IF_NONE_MATCH, etag.clone())
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+		assert_eq!(response.headers()[header::ETAG], etag);
+	}
+
+	let etag = {
+		// changing a location which is nested inside of the retrieved one changes the response, so it is not cached
+		outer.name = words::sentence(5);
+		location.outer = Some(outer.clone().into());
+
+		let mut tx = pool.begin().await?;
+		PgLocation::update(&mut tx, [&outer].into_iter()).await?;
+		tx.commit().await?;
+
+		let response = client
+			.get_builder(&uri(routes::LOCATION, &[("condition", &json)]))
+			.header(header::IF_NONE_MATCH, etag.clone())
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		assert_ne!(response.headers()[header::ETAG], etag);
+
+		let etag = response.headers()[header::ETAG].clone();
+		assert_eq!(response.json::<Post<Location>>().await.entities(), [location.clone()]);
+		etag
+	};
+
+	let saved = {
+		let response = client
+			.put_builder(routes::QUERY)
+			.json(&request::Put::new((serde_json::to_value(&condition)?, routes::LOCATION.to_owned())))
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		response.json::<Put<SavedQuery>>().await.into_entity().unwrap()
+	};
+
+	{
+		let id = saved.id().to_string();
+		let response = client.get_builder(&uri(routes::LOCATION, &[("query", &id)])).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		assert_eq!(response.headers()[header::ETAG], etag);
+		assert_eq!(response.json::<Post<Location>>().await.entities(), [location.clone()]);
+
+		// a saved query can only be used with the route it was saved for
+		let response = client.get_builder(&uri(routes::ORGANIZATION, &[("query", &id)])).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::InvalidReference));
+	}
+
+	client.logout().await;
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client
+			.put_builder(routes::QUERY)
+			.json(&request::Put::new((serde_json::to_value(&condition)?, routes::LOCATION.to_owned())))
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::from(Code::Unauthorized));
+
+		// a saved query can only be used by the user who saved it
+		let response = client.get_builder(&uri(routes::LOCATION, &[("query", &saved.id().to_string())])).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::InvalidReference));
+		client.logout().await;
+	}
+
+	sqlx::query("DELETE FROM saved_queries WHERE id = $1").bind(saved.id()).execute(&pool).await?;
+	PgLocation::delete(&pool, [&location, &outer].into_iter()).await?;
+
+	Ok(())
+}
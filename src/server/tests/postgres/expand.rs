@@ -0,0 +1,123 @@
+use pretty_assertions::assert_eq;
+use reqwest::Url;
+use winvoice_schema::Timesheet;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::{request::Expand, response::SideLoaded};
+
+#[tokio::test]
+#[traced_test]
+async fn expand() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("expand").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let (job_, timesheet) = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		let (expenses, time_begin, time_end, work_notes) = timesheet_args();
+		let t = PgTimesheet::create(
+			&mut tx,
+			grunt.0.employee().unwrap().clone(),
+			expenses,
+			j.clone(),
+			time_begin,
+			time_end,
+			work_notes,
+		)
+		.await?;
+
+		tx.commit().await?;
+		(j, t)
+	};
+
+	client.login(&admin.0, &admin.1).await;
+
+	{
+		let response = client
+			.post_builder(routes::TIMESHEET)
+			.json(&request::Post::new(MatchTimesheet::from(timesheet.id)).with_expand(Expand::Ids))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<SideLoaded>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().status().code(), Code::Success);
+
+		let entities = actual.content().entities();
+		let related = actual.content().related();
+		assert_eq!(entities.len(), 1);
+		assert_eq!(entities[0]["job"], serde_json::to_value(job_.id)?);
+		assert_eq!(entities[0]["employee"], serde_json::to_value(timesheet.employee.id)?);
+
+		// the organization is referred to by the job, so it is side-loaded once, and refers to its location by id
+		assert_eq!(related["job"].get(&job_.id).map(|j| &j["client"]), Some(&serde_json::to_value(organization.id)?));
+		assert_eq!(
+			related["organization"].get(&organization.id).map(|o| &o["location"]),
+			Some(&serde_json::to_value(location.id)?),
+		);
+
+		assert!(related["location"].contains_key(&location.id));
+		assert!(related["department"].contains_key(&grunt.0.department().unwrap().id));
+	}
+
+	{
+		// nesting is the default, so the response has the same shape as it always has
+		let response = client
+			.post_builder(routes::TIMESHEET)
+			.json(&request::Post::new(MatchTimesheet::from(timesheet.id)))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().entities(), [timesheet.clone()]);
+	}
+
+	{
+		let json = serde_json::to_string(&MatchJob::from(job_.id))?;
+		let url = Url::parse_with_params("http://localhost", &[("condition", json.as_str()), ("expand", "ids")])?;
+		let response = client.get_builder(&format!("{}?{}", routes::JOB, url.query().unwrap())).send().await;
+
+		let actual = Response::new(response.status(), response.json::<SideLoaded>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().entities().iter().map(|j| &j["client"]).collect::<Vec<_>>(), [
+			&serde_json::to_value(organization.id)?
+		]);
+	}
+
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
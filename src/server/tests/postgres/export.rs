@@ -4,12 +4,13 @@ use winvoice_schema::Contact;
 
 #[allow(clippy::wildcard_imports)]
 use super::*;
+use crate::schema::{Rate, RateScope};
 
 #[tokio::test]
 #[traced_test]
 async fn export() -> DynResult<()>
 {
-	let TestData { admin, client, pool, .. } = setup("export").await?;
+	let TestData { admin, client, guest, pool, .. } = setup("export").await?;
 
 	let contacts = {
 		let (kind, label) = contact_args();
@@ -78,8 +79,11 @@ async fn export() -> DynResult<()>
 	let history = HistoricalExchangeRates::history().await?;
 
 	{
-		let response =
-			client.post_builder(routes::EXPORT).json(&request::Export::new(None, vec![job_.clone()])).send().await;
+		let response = client
+			.post_builder(routes::EXPORT)
+			.json(&request::Export::new(false, None, vec![job_.clone()]))
+			.send()
+			.await;
 
 		let actual = Response::new(response.status(), response.json::<Export>().await);
 		let job_rates = HistoricalExchangeRates::index_ref_from(&history, Some(job_.date_open.into()));
@@ -107,7 +111,7 @@ async fn export() -> DynResult<()>
 	{
 		let response = client
 			.post_builder(routes::EXPORT)
-			.json(&request::Export::new(Currency::Nok.into(), vec![job_.clone()]))
+			.json(&request::Export::new(false, Currency::Nok.into(), vec![job_.clone()]))
 			.send()
 			.await;
 
@@ -134,9 +138,54 @@ async fn export() -> DynResult<()>
 		assert_eq!(actual, expected);
 	}
 
+	{
+		let rate = Rate::new(employee.id, Money::new(45_00, 2, Currency::Eur), RateScope::Job(job_.id));
+		let response = client.put_builder(routes::RATE).json(&request::Put::new(&rate)).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+
+		let response = client
+			.post_builder(routes::EXPORT)
+			.json(&request::Export::new(false, Currency::Nok.into(), vec![job_.clone()]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Export>().await);
+		let job_rates = HistoricalExchangeRates::index_ref_from(&history, Some(job_.date_open.into()));
+		let mut priced = timesheet.clone();
+		priced.job.invoice.hourly_rate = *rate.hourly_rate();
+
+		let expected = Response::from(Export::new(
+			[(
+				format!("{}--{}.{}", job_client.name.replace(' ', "-"), job_.id, Format::Markdown.extension()),
+				Format::Markdown
+					.export_job(&job_.clone().exchange(Currency::Nok, &job_rates), &contacts, &[priced
+						.exchange_historically(
+							Currency::Nok,
+							HistoricalExchangeRates::index_ref_from(&history, Some(timesheet.time_begin.into())),
+							job_rates,
+						)])
+					.unwrap(),
+			)]
+			.into_iter()
+			.collect(),
+			Code::Success.into(),
+		));
+
+		assert_eq!(actual, expected);
+
+		let response = client.get_builder(routes::RATE).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Rate>>().await);
+		assert!(actual.content().entities().contains(&rate));
+	}
+
+	client.logout().await;
+
+	client.login(&guest.0, &guest.1).await;
+	let response = client.get_builder(routes::RATE).send().await;
+	assert_eq!(response.status(), StatusCode::from(Code::Unauthorized));
 	client.logout().await;
 
-	let users: Vec<_> = [&admin.0].into_iter().cloned().collect();
+	let users: Vec<_> = [&admin.0, &guest.0].into_iter().cloned().collect();
 	let roles: Vec<_> = users.iter().map(|u| u.role().clone()).collect();
 
 	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()),)?;
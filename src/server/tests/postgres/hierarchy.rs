@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use pretty_assertions::assert_eq;
+use serde::de::DeserializeOwned;
+use winvoice_schema::{Id, Location, Organization};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::response::Patch;
+
+/// POST the `id` of a location to the `route`, and assert that it results in the `code`.
+async fn assert_post<T>(client: &TestClient, route: &str, id: Id, code: Code) -> Vec<T>
+where
+	T: DeserializeOwned,
+{
+	tracing::trace!("Asserting POST {route} of {id} results in Code::{code:?}");
+	let response = client.post_builder(route).json(&request::Post::new(id)).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Post<T>>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+	actual.content().entities().to_vec()
+}
+
+/// Move each location of the `moves` inside of the other location, and assert that it results in the `code`.
+async fn assert_move(client: &TestClient, moves: Vec<(Id, Option<Id>)>, code: Code) -> Vec<Location>
+{
+	tracing::trace!("Asserting PATCH {} of {moves:?} results in Code::{code:?}", routes::LOCATION_MOVE);
+	let response = client.patch_builder(routes::LOCATION_MOVE).json(&request::Patch::new(moves)).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Post<Location>>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+	actual.content().entities().to_vec()
+}
+
+#[tokio::test]
+#[traced_test]
+async fn hierarchy() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("hierarchy").await?;
+
+	let country = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+	let state = PgLocation::create(&pool, None, words::sentence(3), Some(country.clone())).await?;
+	let city = PgLocation::create(&pool, None, words::sentence(3), Some(state.clone())).await?;
+	let elsewhere = PgLocation::create(&pool, None, words::sentence(3), None).await?;
+
+	let organization = PgOrganization::create(&pool, city.clone(), company::company()).await?;
+	let ids = |vec: Vec<Location>| vec.into_iter().map(|l| l.id).collect::<Vec<_>>();
+
+	client.login(&guest.0, &guest.1).await;
+	assert_post::<Location>(&client, routes::LOCATION_DESCENDANTS, country.id, Code::Unauthorized).await;
+	assert_move(&client, vec![(city.id, Some(elsewhere.id))], Code::Unauthorized).await;
+	client.logout().await;
+
+	client.login(&admin.0, &admin.1).await;
+
+	{
+		let ancestors = assert_post(&client, routes::LOCATION_ANCESTORS, city.id, Code::Success).await;
+		assert_eq!(ids(ancestors), [state.id, country.id]);
+
+		let descendants = assert_post(&client, routes::LOCATION_DESCENDANTS, country.id, Code::Success).await;
+		assert_eq!(ids(descendants).into_iter().collect::<HashSet<_>>(), [state.id, city.id].into_iter().collect());
+
+		let organizations =
+			assert_post::<Organization>(&client, routes::LOCATION_ORGANIZATIONS, country.id, Code::Success).await;
+		assert_eq!(organizations.into_iter().map(|o| o.id).collect::<Vec<_>>(), [organization.id]);
+		assert!(assert_post::<Organization>(&client, routes::LOCATION_ORGANIZATIONS, elsewhere.id, Code::Success)
+			.await
+			.is_empty());
+	}
+
+	{
+		// neither a location nor one of the locations inside of it can become its outer location
+		assert_move(&client, vec![(state.id, Some(state.id))], Code::LocationCycle).await;
+		assert_move(&client, vec![(country.id, Some(city.id))], Code::LocationCycle).await;
+
+		// nor can two moves which are valid alone create a cycle together
+		assert_move(&client, vec![(state.id, Some(elsewhere.id)), (elsewhere.id, Some(city.id))], Code::LocationCycle)
+			.await;
+		let row = PgLocation::retrieve(&pool, state.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.outer.map(|o| o.id), Some(country.id));
+
+		let moved = assert_move(&client, vec![(state.id, Some(elsewhere.id))], Code::Success).await;
+		assert_eq!(moved.iter().map(|l| l.outer.as_deref().map(|o| o.id)).collect::<Vec<_>>(), [Some(elsewhere.id)]);
+
+		let ancestors = assert_post(&client, routes::LOCATION_ANCESTORS, city.id, Code::Success).await;
+		assert_eq!(ids(ancestors), [state.id, elsewhere.id]);
+		assert!(assert_post::<Organization>(&client, routes::LOCATION_ORGANIZATIONS, country.id, Code::Success)
+			.await
+			.is_empty());
+	}
+
+	// a location cannot be moved into a cycle by updating it, either
+	{
+		let city = PgLocation::retrieve(&pool, city.id.into()).await.map(|mut v| v.remove(0))?;
+		let elsewhere = Location { outer: Some(city.into()), ..elsewhere.clone() };
+		let response = client.patch_builder(routes::LOCATION).json(&request::Patch::new(vec![elsewhere])).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::LocationCycle));
+		assert_eq!(actual.content().status().code(), Code::LocationCycle);
+
+		let row = PgLocation::retrieve(&pool, elsewhere.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.outer, None);
+	}
+
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	futures::try_join!(
+		PgUser::delete(&pool, users.iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+	PgLocation::delete(&pool, [&city, &state, &elsewhere, &country].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,74 @@
+use pretty_assertions::assert_eq;
+use winvoice_schema::Location;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::{IDEMPOTENCY_HEADER, IDEMPOTENCY_REPLAY_HEADER},
+	server::idempotency,
+};
+
+#[tokio::test]
+#[traced_test]
+async fn idempotency() -> DynResult<()>
+{
+	let TestData { admin, client, pool, .. } = setup("idempotency").await?;
+
+	let key = words::sentence(3);
+	let args = {
+		// the name must be unique, so that the rows created by this test can be found
+		let (currency, _, outer) = location_args();
+		(currency, words::sentence(5), outer)
+	};
+
+	let put_args = |key: &str, args| {
+		let builder = client.put_builder(routes::LOCATION).header(IDEMPOTENCY_HEADER, key);
+		builder.json(&request::Put::new(args)).send()
+	};
+
+	let put = |key: &str| put_args(key, args.clone());
+
+	client.login(&admin.0, &admin.1).await;
+
+	let location = {
+		let response = put(&key).await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		assert!(response.headers().get(IDEMPOTENCY_REPLAY_HEADER).is_none());
+		response.json::<Put<Location>>().await.into_entity().unwrap()
+	};
+
+	{
+		let response = put(&key).await;
+		assert_eq!(response.status(), StatusCode::from(Code::Success));
+		assert_eq!(response.headers()[IDEMPOTENCY_REPLAY_HEADER], "true");
+		assert_eq!(response.json::<Put<Location>>().await.into_entity(), Some(location.clone()));
+	}
+
+	// the same key with a different body is not the same request
+	{
+		let (currency, _, outer) = args.clone();
+		let response = put_args(&key, (currency, words::sentence(5), outer)).await;
+		assert_eq!(response.status(), StatusCode::from(Code::IdempotencyKeyReused));
+		assert_eq!(response.json::<Put<Location>>().await.status().code(), Code::IdempotencyKeyReused);
+	}
+
+	// bodies which are too large to fingerprint are rejected, rather than read into memory
+	{
+		let (currency, _, outer) = args.clone();
+		let response = put_args(&words::sentence(4), (currency, "a".repeat(idempotency::BODY_LIMIT), outer)).await;
+		assert_eq!(response.status(), StatusCode::from(Code::RequestTooLarge));
+		assert_eq!(response.json::<Put<Location>>().await.status().code(), Code::RequestTooLarge);
+	}
+
+	let rows = PgLocation::retrieve(&pool, MatchLocation { name: args.1.clone().into(), ..Default::default() }).await?;
+	assert_eq!(rows, [location.clone()]);
+
+	// a different key is a different request
+	let other = put(&words::sentence(4)).await.json::<Put<Location>>().await.into_entity().unwrap();
+	assert_ne!(other.id, location.id);
+
+	client.logout().await;
+	PgLocation::delete(&pool, [&location, &other].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use pretty_assertions::assert_eq;
+use winvoice_match::MatchStr;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::{
+	request::{Import, ImportKind},
+	Entity,
+};
+
+#[tokio::test]
+#[traced_test]
+async fn import() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("import").await?;
+
+	let location = {
+		let (currency, name, outer) = location_args();
+		PgLocation::create(&pool, currency, name, outer).await?
+	};
+
+	let names = [company::company(), company::company()];
+	let csv = |location: &str| -> DynResult<String> {
+		let mut writer = WriterBuilder::new().from_writer(Vec::new());
+		writer.write_record(["Company", "Town"])?;
+		names.iter().try_for_each(|n| writer.write_record([n.as_str(), location]))?;
+		Ok(String::from_utf8(writer.into_inner()?)?)
+	};
+
+	let columns: BTreeMap<String, String> =
+		[("location".into(), "Town".into()), ("name".into(), "Company".into())].into();
+	let import = |csv: String, dry_run: bool| Import::new(columns.clone(), csv, dry_run, ImportKind::Organization);
+	let retrieve = || {
+		PgOrganization::retrieve(&pool, MatchOrganization {
+			name: MatchStr::Or(names.iter().cloned().map(Into::into).collect()),
+			..Default::default()
+		})
+	};
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client.put_builder(routes::IMPORT).json(&import(csv(&location.name)?, false)).send().await;
+		assert_eq!(response.status(), StatusCode::from(Code::Unauthorized));
+		client.logout().await;
+	}
+
+	client.login(&admin.0, &admin.1).await;
+
+	// every row which cannot be imported is reported, and nothing is created
+	{
+		let response = client.put_builder(routes::IMPORT).json(&import(csv("not a location")?, false)).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::EncodingError));
+		assert_eq!(actual.content().status().warnings().len(), names.len());
+		assert!(actual.content().status().warnings()[0].starts_with("line 2:"));
+		assert!(retrieve().await?.is_empty());
+	}
+
+	{
+		let response = client.put_builder(routes::IMPORT).json(&import(csv(&location.name)?, true)).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().entities().len(), names.len());
+		assert!(retrieve().await?.is_empty());
+	}
+
+	let organizations = {
+		let response = client.put_builder(routes::IMPORT).json(&import(csv(&location.name)?, false)).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		actual
+			.content()
+			.entities()
+			.iter()
+			.map(|e| match e
+			{
+				Entity::Organization(o) => o.clone(),
+				e => panic!("Expected the import to create an Organization, but got {e:?}"),
+			})
+			.collect::<Vec<_>>()
+	};
+
+	client.logout().await;
+
+	// an import can only refer to what the user can retrieve
+	{
+		let mut writer = WriterBuilder::new().from_writer(Vec::new());
+		writer.write_record(["client", "date_open", "hourly_rate", "increment", "objectives"])?;
+		writer.write_record([names[0].as_str(), "2023-01-01T00:00:00Z", "20.00 USD", "15m", "objectives"])?;
+		let csv = String::from_utf8(writer.into_inner()?)?;
+
+		client.login(&manager.0, &manager.1).await;
+		let request = Import::new(BTreeMap::new(), csv, true, ImportKind::Job);
+		let response = client.put_builder(routes::IMPORT).json(&request).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::EncodingError));
+		assert!(actual.content().status().warnings()[0].contains("there is no organization named"));
+		client.logout().await;
+	}
+
+	// each row is validated like a PUT to its route would be
+	{
+		let mut writer = WriterBuilder::new().from_writer(Vec::new());
+		writer.write_record(["client", "date_open", "hourly_rate", "increment", "objectives"])?;
+		writer.write_record([names[0].as_str(), "2023-01-01T00:00:00Z", "-20.00 USD", "15m", "objectives"])?;
+		let csv = String::from_utf8(writer.into_inner()?)?;
+
+		client.login(&admin.0, &admin.1).await;
+		let request = Import::new(BTreeMap::new(), csv, true, ImportKind::Job);
+		let response = client.put_builder(routes::IMPORT).json(&request).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Entity>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::EncodingError));
+		assert!(actual.content().status().warnings()[0].contains("job.invoice.hourly_rate: must not be negative"));
+		client.logout().await;
+	}
+
+	assert_eq!(organizations.iter().map(|o| &o.name).collect::<Vec<_>>(), names.iter().collect::<Vec<_>>());
+	assert!(organizations.iter().all(|o| o.location == location));
+	assert_eq!(retrieve().await?.len(), names.len());
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	futures::try_join!(PgRole::delete(&pool, roles.into_iter()), PgOrganization::delete(&pool, organizations.iter()))?;
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
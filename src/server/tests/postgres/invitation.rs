@@ -0,0 +1,88 @@
+use humantime_serde::Serde;
+use pretty_assertions::assert_eq;
+use winvoice_schema::Employee;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::schema::{postgres::PgInvitation, Invitation, InvitationAdapter};
+
+/// Send a `POST` request with the `password` to the `link` of an invitation, and assert that it results in the `code`.
+async fn assert_accept(client: &TestClient, link: &str, password: &str, code: Code) -> Option<User>
+{
+	tracing::trace!("Asserting POST {link} results in Code::{code:?}");
+	let response = client.post_builder(link).json(&password).send().await;
+	assert_eq!(response.status(), StatusCode::from(code));
+
+	let login = response.json::<Login>().await;
+	assert_eq!(login.status().code(), code);
+	login.user().cloned()
+}
+
+#[tokio::test]
+#[traced_test]
+async fn invitation() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("invitation").await?;
+
+	let username = internet::username();
+	let args = |ttl: Duration| (None::<Employee>, guest.0.role().clone(), Serde::from(ttl), username.clone());
+
+	client.test_post_unauthorized(routes::INVITATION, &guest.0, &guest.1, args(Duration::from_secs(60 * 60))).await;
+
+	let (invitation, expired) = {
+		client.login(&admin.0, &admin.1).await;
+		let put = |ttl: Duration| client.put_builder(routes::INVITATION).json(&request::Put::new(args(ttl))).send();
+
+		let response = put(Duration::ZERO).await;
+		assert_eq!(response.status(), StatusCode::from(Code::ValidationError));
+
+		let mut created = Vec::with_capacity(2);
+		for ttl in [Duration::from_secs(60 * 60), Duration::from_millis(1)]
+		{
+			let response = put(ttl).await;
+			let actual = Response::new(response.status(), response.json::<Put<Invitation>>().await);
+			assert_eq!(actual.status(), StatusCode::from(Code::Success));
+			created.push(actual.content().entity().unwrap().clone());
+		}
+
+		let response = client.get_builder(routes::INVITATION).send().await;
+		let retrieved = response.json::<Post<Invitation>>().await;
+		assert!(created.iter().all(|c| retrieved.entities().iter().any(|r| r.id() == c.id() && r.link().is_none())));
+
+		client.logout().await;
+		let expired = created.pop().unwrap();
+		(created.pop().unwrap(), expired)
+	};
+
+	let link = invitation.link().unwrap();
+	assert_eq!(invitation.role_id(), guest.0.role().id());
+	assert_eq!(invitation.username(), username);
+
+	tokio::time::sleep(Duration::from_millis(10)).await;
+	assert_accept(&client, expired.link().unwrap(), "password", Code::InvalidCredentials).await;
+	assert_accept(&client, &format!("{}?token=wrong", routes::INVITATION_ACCEPT), "password", Code::InvalidCredentials)
+		.await;
+	assert_accept(&client, link, "", Code::ValidationError).await;
+
+	// redeeming the invitation logs in as the new user
+	let user = assert_accept(&client, link, "password", Code::Success).await.unwrap();
+	assert_eq!(user.role(), guest.0.role());
+	assert_eq!(user.username(), username);
+	client.logout().await;
+
+	// an invitation can only be redeemed once
+	assert_accept(&client, link, "another password", Code::InvalidCredentials).await;
+	client.login(&user, "password").await;
+	client.logout().await;
+
+	PgInvitation::delete(&pool, &[expired.id()]).await?;
+	assert!(!PgInvitation::retrieve(&pool).await?.iter().any(|i| i.id() == invitation.id() || i.id() == expired.id()));
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0, &user].into_iter().cloned().collect();
+	let roles: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,83 @@
+use axum_login::axum_sessions::async_session::base64;
+use pretty_assertions::assert_eq;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	schema::{postgres::PgIdentity, Identity, IdentityAdapter},
+	server::ldap::fixture,
+};
+
+/// Log in with the `username` and `password`, and assert that it results in the `code`.
+async fn assert_login(client: &TestClient, username: &str, password: &str, code: Code) -> Option<User>
+{
+	tracing::trace!("Asserting login as {username} results in Code::{code:?}");
+	let response = client
+		.post_builder(routes::LOGIN)
+		.header(header::AUTHORIZATION, format!("Basic {}", base64::encode(format!("{username}:{password}"))))
+		.send()
+		.await;
+
+	assert_eq!(response.status(), StatusCode::from(code));
+
+	let login = response.json::<Login>().await;
+	assert_eq!(login.status().code(), code);
+	login.user().cloned()
+}
+
+#[tokio::test]
+#[traced_test]
+async fn ldap() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } =
+		setup_with("ldap", Some(fixture::ldap().with_provision(true)), None).await?;
+
+	let role = PgRole::create(&pool, fixture::GROUP.into(), None).await?;
+
+	// a user who only exists in the database is not logged in by the directory, even if it has a user of that name
+	{
+		let password = password::generate(true, true, true, 8);
+		let local = PgUser::create(&pool, None, password.clone(), role.clone(), fixture::USERNAME.into()).await?;
+		assert_login(&client, fixture::USERNAME, fixture::PASSWORD, Code::InvalidCredentials).await;
+
+		let user = assert_login(&client, fixture::USERNAME, &password, Code::Success).await.unwrap();
+		assert_eq!(user.id(), local.id());
+		client.logout().await;
+
+		PgUser::delete(&pool, [&local].into_iter()).await?;
+	}
+
+	// a user who the directory authenticates is created with the role which their groups name
+	let user = assert_login(&client, fixture::USERNAME, fixture::PASSWORD, Code::Success).await.unwrap();
+	assert_eq!(user.role(), &role);
+	assert_eq!(user.username(), fixture::USERNAME);
+	client.logout().await;
+
+	let user = assert_login(&client, fixture::USERNAME, fixture::PASSWORD, Code::Success).await.unwrap();
+	assert_eq!(user.role(), &role);
+	client.logout().await;
+
+	assert_login(&client, fixture::USERNAME, "wrong", Code::InvalidCredentials).await;
+	assert_login(&client, fixture::USERNAME, "", Code::InvalidCredentials).await;
+
+	// users who are not in the directory log in with their password instead
+	assert_login(&client, admin.0.username(), &admin.1, Code::Success).await;
+	client.logout().await;
+	assert_login(&client, admin.0.username(), "wrong", Code::InvalidCredentials).await;
+
+	// …but users who are managed by the directory never do
+	let ldap = fixture::ldap();
+	let identity = Identity::new(ldap.url().into(), ldap.dn(fixture::USERNAME), user.id());
+	assert_eq!(PgIdentity::retrieve(&pool, &[user.id()]).await?, [identity]);
+
+	PgIdentity::link(&pool, &Identity::new(ldap.url().into(), words::sentence(3), grunt.0.id())).await?;
+	assert_login(&client, grunt.0.username(), &grunt.1, Code::InvalidCredentials).await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0, &user].into_iter().cloned().collect();
+	let roles: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().map(User::role).chain([&role]).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
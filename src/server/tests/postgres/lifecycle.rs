@@ -0,0 +1,144 @@
+use pretty_assertions::assert_eq;
+use winvoice_schema::{Job, Timesheet};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::response::Patch;
+
+#[tokio::test]
+#[traced_test]
+async fn lifecycle() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("lifecycle").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let (job_, timesheet) = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		let (expenses, time_begin, time_end, work_notes) = timesheet_args();
+		let t = PgTimesheet::create(
+			&mut tx,
+			grunt.0.employee().unwrap().clone(),
+			expenses,
+			j.clone(),
+			time_begin,
+			time_end,
+			work_notes,
+		)
+		.await?;
+
+		tx.commit().await?;
+		(j, t)
+	};
+
+	client.login(&admin.0, &admin.1).await;
+
+	// the lifecycle cannot be skipped by updating the job directly
+	{
+		let response = client
+			.patch_builder(routes::JOB)
+			.json(&request::Patch::new(vec![Job { date_close: Some(Utc::now()), ..job_.clone() }]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::InvalidTransition));
+		assert_eq!(actual.content().status().code(), Code::InvalidTransition);
+
+		let row = PgJob::retrieve(&pool, job_.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.date_close, None);
+	}
+
+	for (route, code) in [
+		(routes::JOB_REOPEN, Code::InvalidTransition),
+		(routes::JOB_CLOSE, Code::Success),
+		(routes::JOB_CLOSE, Code::InvalidTransition),
+		(routes::JOB_INVOICE_PAY, Code::InvalidTransition),
+		(routes::JOB_INVOICE_ISSUE, Code::Success),
+		(routes::JOB_REOPEN, Code::InvalidTransition),
+		(routes::JOB_INVOICE_PAY, Code::Success),
+		(routes::JOB_INVOICE_PAY, Code::InvalidTransition),
+	]
+	{
+		tracing::trace!("Asserting admin PATCH {route} results in Code::{code:?}");
+		let response = client.patch_builder(route).json(&request::Patch::new(vec![job_.id])).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Job>>().await);
+		assert_eq!(actual.status(), StatusCode::from(code));
+		assert_eq!(actual.content().status().code(), code);
+	}
+
+	client.logout().await;
+
+	let invoiced = PgJob::retrieve(&pool, job_.id.into()).await.map(|mut v| v.remove(0))?;
+	assert!(invoiced.date_close.is_some());
+	assert!(invoiced.invoice.date.map_or(false, |d| d.paid.is_some()));
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client
+			.patch_builder(routes::TIMESHEET)
+			.json(&request::Patch::new(vec![Timesheet { work_notes: words::sentence(5), ..timesheet.clone() }]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+
+		let row = PgTimesheet::retrieve(&pool, timesheet.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.work_notes, timesheet.work_notes);
+		client.logout().await;
+	}
+
+	// …nor can a timer which was left running when the invoice was issued be stopped
+	let running = {
+		let mut tx = pool.begin().await?;
+		let (expenses, time_begin, _, work_notes) = timesheet_args();
+		let employee = grunt.0.employee().unwrap().clone();
+		let t = PgTimesheet::create(&mut tx, employee, expenses, invoiced.clone(), time_begin, None, work_notes).await?;
+		tx.commit().await?;
+
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client.patch_builder(routes::TIMESHEET_STOP).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+		assert!(actual.content().entities().is_empty());
+
+		let row = PgTimesheet::retrieve(&pool, t.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.time_end, None);
+		client.logout().await;
+		row
+	};
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgTimesheet::delete(&pool, [&timesheet, &running].into_iter()).await?;
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&invoiced].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
@@ -1,9 +1,30 @@
+mod attachment;
+mod batch;
+mod budget;
+mod conditional;
 mod delete;
+mod expand;
 mod export;
 mod healthy;
+mod hierarchy;
+mod idempotency;
+mod import;
+mod invitation;
+#[cfg(feature = "test-ldap")]
+mod ldap;
+mod lifecycle;
+mod oidc;
+mod password;
 mod patch;
 mod post;
 mod put;
+mod reassign;
+mod reset;
+mod review;
+mod search;
+mod timer;
+mod validate;
+mod webhook;
 mod who_am_i;
 
 use sqlx::Postgres;
@@ -0,0 +1,146 @@
+use pretty_assertions::assert_eq;
+use reqwest::Url;
+use serde_json::json;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::response::Redirect,
+	schema::{postgres::PgIdentity, Identity, IdentityAdapter},
+	server::oidc::mock::MockProvider,
+};
+
+/// Start logging in through the identity provider, and return the URL which the `client` is sent to.
+async fn start(client: &TestClient) -> Url
+{
+	let response = client.get_builder(routes::LOGIN_OIDC).send().await;
+	assert_eq!(response.status(), StatusCode::OK);
+	Url::parse(response.json::<Redirect>().await.url().unwrap()).unwrap()
+}
+
+/// Finish the login which sent the `client` to the `url`, as the `subject` with the `username` and `groups`, and assert
+/// that it results in the `code`.
+async fn finish(
+	client: &TestClient,
+	provider: &MockProvider,
+	url: &Url,
+	subject: &str,
+	username: &str,
+	groups: &[&str],
+	code: Code,
+) -> Option<User>
+{
+	let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned()).unwrap();
+	assert!(url.as_str().starts_with(provider.issuer()));
+
+	let claims = json!({
+		"aud": "winvoice",
+		"exp": Utc::now().timestamp() + 60,
+		"groups": groups,
+		"iss": provider.issuer(),
+		"nonce": param("nonce"),
+		"preferred_username": username,
+		"sub": subject,
+	});
+
+	let code_from_provider = provider.issue(&param("code_challenge"), claims);
+
+	tracing::trace!("Asserting login through the identity provider as {username} results in Code::{code:?}");
+	let callback = format!("{}?code={code_from_provider}&state={}", routes::LOGIN_OIDC_CALLBACK, param("state"));
+	let response = client.post_builder(&callback).send().await;
+	assert_eq!(response.status(), StatusCode::from(code));
+
+	let login = response.json::<Login>().await;
+	assert_eq!(login.status().code(), code);
+	login.user().cloned()
+}
+
+/// Log in through the `provider` as the `subject` with the `username` and `groups`, and assert that it results in
+/// the `code`.
+async fn assert_login(
+	client: &TestClient,
+	provider: &MockProvider,
+	subject: &str,
+	username: &str,
+	groups: &[&str],
+	code: Code,
+) -> Option<User>
+{
+	let url = start(client).await;
+	finish(client, provider, &url, subject, username, groups, code).await
+}
+
+#[tokio::test]
+#[traced_test]
+async fn oidc() -> DynResult<()>
+{
+	let provider = MockProvider::spawn("winvoice").await?;
+	let oidc = Oidc::discover(provider.issuer().into(), "winvoice".into(), None, "http://localhost/callback".into())
+		.await?
+		.with_provision(true);
+
+	let TestData { admin, client, grunt, guest, manager, pool } = setup_with("oidc", None, Some(oidc)).await?;
+	let (guest_role, manager_role) = (guest.0.role().name(), manager.0.role().name());
+	let (grunt_subject, subject) = (internet::username(), internet::username());
+
+	// an identity provider cannot log in as a user who has not linked their identity to it
+	assert_login(&client, &provider, &grunt_subject, grunt.0.username(), &[], Code::InvalidCredentials).await;
+
+	client.login(&grunt.0, &grunt.1).await;
+	let user = assert_login(&client, &provider, &grunt_subject, "anything", &[], Code::Success).await.unwrap();
+	assert_eq!(user.id(), grunt.0.id());
+	assert_eq!(
+		PgIdentity::retrieve(&pool, &[grunt.0.id()]).await?,
+		[Identity::new(provider.issuer().into(), grunt_subject.clone(), grunt.0.id())],
+	);
+	client.logout().await;
+
+	// …nor link an identity which belongs to someone else
+	client.login(&manager.0, &manager.1).await;
+	assert_login(&client, &provider, &grunt_subject, "anything", &[], Code::InvalidCredentials).await;
+	client.logout().await;
+
+	// a user with a linked identity is given the role which their groups name
+	let groups = ["unknown", manager_role];
+	let user = assert_login(&client, &provider, &grunt_subject, "anything", &groups, Code::Success).await.unwrap();
+	assert_eq!(user.id(), grunt.0.id());
+	assert_eq!(user.role(), manager.0.role());
+	client.logout().await;
+
+	// …and keeps their role when none of their groups name one
+	let user = assert_login(&client, &provider, &grunt_subject, "anything", &[], Code::Success).await.unwrap();
+	assert_eq!(user.role(), manager.0.role());
+	client.logout().await;
+
+	// a user who does not exist can only be created when one of their groups names a role
+	let username = internet::username();
+	assert_login(&client, &provider, &subject, &username, &["unknown"], Code::InvalidCredentials).await;
+
+	let created = assert_login(&client, &provider, &subject, &username, &[guest_role], Code::Success).await.unwrap();
+	assert_eq!(created.role(), guest.0.role());
+	assert_eq!(created.username(), username);
+	client.logout().await;
+
+	// …and is found by their identity afterwards, rather than their username
+	let user = assert_login(&client, &provider, &subject, "anything", &[], Code::Success).await.unwrap();
+	assert_eq!(user.id(), created.id());
+	client.logout().await;
+
+	// a login cannot be finished without starting it first
+	let callback = format!("{}?code=wrong&state=wrong", routes::LOGIN_OIDC_CALLBACK);
+	let response = client.post_builder(&callback).send().await;
+	assert_eq!(response.status(), StatusCode::from(Code::InvalidCredentials));
+
+	// …and only the login which the client started last can be finished
+	let url = start(&client).await;
+	start(&client).await;
+	finish(&client, &provider, &url, &subject, &username, &[], Code::InvalidCredentials).await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0, &created].into_iter().cloned().collect();
+	let roles: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,64 @@
+use axum_login::axum_sessions::async_session::base64;
+use pretty_assertions::assert_eq;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+
+/// Send a `POST` request to the `route` with the `username` and `password` of a basic authorization header, and the
+/// `body` (if any). Assert that it results in the `code`.
+async fn assert_post(client: &TestClient, route: &str, username: &str, password: &str, body: Option<&str>, code: Code)
+{
+	tracing::trace!("Asserting POST {route} results in Code::{code:?}");
+	let mut builder = client
+		.post_builder(route)
+		.header(header::AUTHORIZATION, format!("Basic {}", base64::encode(format!("{username}:{password}"))));
+
+	if let Some(b) = body
+	{
+		builder = builder.json(&b);
+	}
+
+	let response = builder.send().await;
+	assert_eq!(response.status(), StatusCode::from(code));
+	assert_eq!(response.json::<Login>().await.status().code(), code);
+}
+
+#[tokio::test]
+#[traced_test]
+async fn password() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("password").await?;
+	let (user, password) = (grunt.0.username(), grunt.1.as_str());
+
+	{
+		let expired = User { password_set: DateTime::<Utc>::UNIX_EPOCH, ..grunt.0.clone() };
+		let mut tx = pool.begin().await?;
+		PgUser::update(&mut tx, [&expired].into_iter()).await?;
+		tx.commit().await?;
+	}
+
+	assert_post(&client, routes::LOGIN, user, password, None, Code::PasswordExpired).await;
+	assert_post(&client, routes::PASSWORD, user, "wrong", Some("changed"), Code::InvalidCredentials).await;
+	assert_post(&client, routes::PASSWORD, user, password, Some(password), Code::ValidationError).await;
+	assert_post(&client, routes::PASSWORD, user, password, Some(""), Code::ValidationError).await;
+
+	// changing the password logs in with it
+	assert_post(&client, routes::PASSWORD, user, password, Some("changed"), Code::Success).await;
+	client.logout().await;
+
+	assert_post(&client, routes::LOGIN, user, password, None, Code::InvalidCredentials).await;
+	assert_post(&client, routes::LOGIN, user, "changed", None, Code::Success).await;
+	client.logout().await;
+
+	let row = PgUser::retrieve(&pool, grunt.0.id().into()).await.map(|mut v| v.remove(0))?;
+	assert!(row.password_set() > DateTime::<Utc>::UNIX_EPOCH);
+	assert!(row.password_expires().transpose()?.map_or(true, |date| date > Utc::now()));
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
@@ -1,4 +1,5 @@
 use pretty_assertions::assert_eq;
+use winvoice_schema::{Id, Timesheet};
 
 #[allow(clippy::wildcard_imports)]
 use super::*;
@@ -107,6 +108,32 @@ async fn put() -> DynResult<()>
 		})
 		.await;
 
+	{
+		client.login(&admin.0, &admin.1).await;
+		let args = {
+			let args = timesheet_args();
+			(employee.clone(), args.0, job_.clone(), timesheet.time_begin + chrono::Duration::hours(1), args.2, args.3)
+		};
+
+		let response = client.put_builder(routes::TIMESHEET).json(&request::Put::new(&args)).send().await;
+		let actual = Response::new(response.status(), response.json::<Put<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::TimesheetOverlap));
+		assert_eq!(actual.content().status().code(), Code::TimesheetOverlap);
+		assert_eq!(actual.content().entity(), None);
+
+		let response = client
+			.put_builder(routes::TIMESHEET)
+			.header(api::OVERLAP_HEADER, api::OVERLAP_WARN)
+			.json(&request::Put::new(&args))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Put<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().status().warnings().len(), 1);
+		client.logout().await;
+	}
+
 	let timesheet2 = client
 		.test_post_success::<PgTimesheet, _>(&pool, routes::TIMESHEET, &manager.0, &manager.1, {
 			let args = timesheet_args();
@@ -136,6 +163,17 @@ async fn put() -> DynResult<()>
 		};
 
 		assert_eq!(actual, expected);
+
+		// a timesheet which does not exist is reported as such
+		let response = client
+			.put_builder(routes::EXPENSE)
+			.json(&request::Put::new((vec![expense_args()], Id::new_v4())))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Put<Vec<Expense>>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::InvalidReference));
+		assert_eq!(actual.content().status().code(), Code::InvalidReference);
 		client.logout().await;
 	}
 
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::response::Delete;
+
+/// Send a `DELETE` request with the `entities` to the `uri`, and assert that it results in the `code`.
+async fn assert_delete<T>(client: &TestClient, uri: &str, entities: Vec<T>, code: Code)
+where
+	T: Serialize,
+{
+	tracing::trace!("Asserting DELETE {uri} results in Code::{code:?}");
+	let response = client.delete_builder(uri).json(&request::Delete::new(entities)).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Delete>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+}
+
+#[tokio::test]
+#[traced_test]
+async fn reassign() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("reassign").await?;
+
+	client.login(&admin.0, &admin.1).await;
+
+	{
+		let role = grunt.0.role().clone();
+		assert_delete(&client, routes::ROLE, vec![role.clone()], Code::RoleInUse).await;
+
+		let into_itself = format!("{}?reassign_to={}", routes::ROLE, role.id());
+		assert_delete(&client, &into_itself, vec![role.clone()], Code::InvalidReference).await;
+
+		let to_guest = format!("{}?reassign_to={}", routes::ROLE, guest.0.role().id());
+		assert_delete(&client, &to_guest, vec![role.clone()], Code::Success).await;
+
+		let row = PgUser::retrieve(&pool, grunt.0.id().into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.role(), guest.0.role());
+		assert!(PgRole::retrieve(&pool, role.id().into()).await?.is_empty());
+	}
+
+	{
+		// the admin is the only user who can manage users and roles
+		let to_guest = format!("{}?reassign_to={}", routes::ROLE, guest.0.role().id());
+		assert_delete(&client, &to_guest, vec![admin.0.role().clone()], Code::LastManager).await;
+		assert_delete(&client, routes::USER, vec![admin.0.clone()], Code::LastManager).await;
+
+		let demoted = User { role: guest.0.role().clone(), ..admin.0.clone() };
+		let response = client.patch_builder(routes::USER).json(&request::Patch::new(vec![demoted])).send().await;
+		let actual = Response::new(response.status(), response.json::<Delete>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::LastManager));
+
+		let row = PgUser::retrieve(&pool, admin.0.id().into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.role(), admin.0.role());
+	}
+
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = [&admin.0, &guest.0, &manager.0].into_iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,120 @@
+use axum_login::axum_sessions::async_session::base64;
+use pretty_assertions::assert_eq;
+use tokio::fs;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::schema::{
+	postgres::{PgIdentity, PgPasswordReset},
+	Identity,
+	IdentityAdapter,
+	PasswordResetAdapter,
+};
+
+/// Wait for the [`LogFileNotifier`] of the `test` to deliver a password reset token to the `username` other than the
+/// `previous` one, and return it.
+async fn delivered(test: &str, username: &str, previous: Option<&str>) -> String
+{
+	let recipient = format!(" to {username}: ");
+	for _ in 0..100
+	{
+		let token = fs::read_to_string(notification_log(test)).await.ok().and_then(|contents| {
+			contents
+				.lines()
+				.filter(|l| l.contains(&recipient))
+				.filter_map(|l| l.split_once("?token="))
+				.map(|(_, t)| t.chars().take_while(char::is_ascii_hexdigit).collect::<String>())
+				.last()
+		});
+
+		match token
+		{
+			Some(t) if previous != Some(t.as_str()) => return t,
+			_ => tokio::time::sleep(Duration::from_millis(50)).await,
+		}
+	}
+
+	panic!("No password reset was delivered to {username}");
+}
+
+/// Send a `POST` request with the `body` to the `route`, and assert that it results in the `code`.
+async fn assert_post(client: &TestClient, route: &str, body: &str, code: Code) -> Option<User>
+{
+	tracing::trace!("Asserting POST {route} results in Code::{code:?}");
+	let response = client.post_builder(route).json(&body).send().await;
+	assert_eq!(response.status(), StatusCode::from(code));
+
+	let login = response.json::<Login>().await;
+	assert_eq!(login.status().code(), code);
+	login.user().cloned()
+}
+
+/// Assert that logging in with the `username` and `password` results in the `code`.
+async fn assert_login(client: &TestClient, username: &str, password: &str, code: Code)
+{
+	let response = client
+		.post_builder(routes::LOGIN)
+		.header(header::AUTHORIZATION, format!("Basic {}", base64::encode(format!("{username}:{password}"))))
+		.send()
+		.await;
+
+	assert_eq!(response.status(), StatusCode::from(code));
+}
+
+#[tokio::test]
+#[traced_test]
+async fn reset() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("reset").await?;
+	let username = grunt.0.username();
+	let redeem = |token: &str| format!("{}?token={token}", routes::PASSWORD_RESET_REDEEM);
+
+	// the response does not reveal whether the user exists
+	assert_post(&client, routes::PASSWORD_RESET, &internet::username(), Code::Success).await;
+
+	assert_post(&client, routes::PASSWORD_RESET, username, Code::Success).await;
+	let replaced = delivered("reset", username, None).await;
+
+	// only the latest token of a user can be redeemed
+	assert_post(&client, routes::PASSWORD_RESET, username, Code::Success).await;
+	let token = delivered("reset", username, Some(&replaced)).await;
+
+	assert_post(&client, &redeem(&replaced), "changed", Code::InvalidCredentials).await;
+	assert_post(&client, &redeem("wrong"), "changed", Code::InvalidCredentials).await;
+	assert_post(&client, &redeem(&token), "", Code::ValidationError).await;
+
+	// redeeming the token logs in with the new password
+	let user = assert_post(&client, &redeem(&token), "changed", Code::Success).await.unwrap();
+	assert_eq!(user.id(), grunt.0.id());
+	client.logout().await;
+
+	// a token can only be redeemed once
+	assert_post(&client, &redeem(&token), "changed again", Code::InvalidCredentials).await;
+
+	assert_login(&client, username, &grunt.1, Code::InvalidCredentials).await;
+	assert_login(&client, username, "changed", Code::Success).await;
+	client.logout().await;
+
+	// the password of a user who logs in through an identity provider is managed there instead
+	{
+		let expires = Utc::now() + chrono::Duration::minutes(5);
+		let token = PgPasswordReset::create(&pool, manager.0.id(), expires).await?;
+		let identity = Identity::new("ldap://localhost".into(), internet::username(), manager.0.id());
+		PgIdentity::link(&pool, &identity).await?;
+
+		assert_post(&client, routes::PASSWORD_RESET, manager.0.username(), Code::Success).await;
+		assert_post(&client, &redeem(&token), "changed", Code::InvalidCredentials).await;
+		assert_login(&client, manager.0.username(), &manager.1, Code::Success).await;
+		client.logout().await;
+	}
+
+	fs::remove_file(notification_log("reset")).await?;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgUser::delete(&pool, users.iter()).await?;
+	PgRole::delete(&pool, roles.into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,180 @@
+use pretty_assertions::assert_eq;
+use winvoice_schema::{Expense, Id, Timesheet};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::response::Patch,
+	schema::{postgres::PgReview, Review, ReviewAdapter, ReviewStatus},
+};
+
+/// Review the timesheet with the `id` as the logged in user, and assert that it results in the `code`.
+async fn assert_review(client: &TestClient, id: Id, status: ReviewStatus, code: Code) -> Vec<Review>
+{
+	tracing::trace!("Asserting PATCH {} with {status:?} results in Code::{code:?}", routes::TIMESHEET_REVIEW);
+	let review = Review::new(words::sentence(4).into(), None, status, id, None);
+	let response = client.patch_builder(routes::TIMESHEET_REVIEW).json(&request::Patch::new(vec![review])).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Post<Review>>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+	actual.content().entities().to_vec()
+}
+
+/// Submit the timesheet with the `id` as the logged in user, and assert that it results in the `code`.
+async fn assert_submit(client: &TestClient, id: Id, code: Code) -> Vec<Review>
+{
+	tracing::trace!("Asserting PATCH {} results in Code::{code:?}", routes::TIMESHEET_SUBMIT);
+	let response = client.patch_builder(routes::TIMESHEET_SUBMIT).json(&request::Patch::new(vec![id])).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Post<Review>>().await);
+	assert_eq!(actual.status(), StatusCode::from(code));
+	assert_eq!(actual.content().status().code(), code);
+	actual.content().entities().to_vec()
+}
+
+#[tokio::test]
+#[traced_test]
+async fn review() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("review").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let (job_, timesheet) = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		let (expenses, time_begin, time_end, work_notes) = timesheet_args();
+		let t = PgTimesheet::create(
+			&mut tx,
+			grunt.0.employee().unwrap().clone(),
+			expenses,
+			j.clone(),
+			time_begin,
+			time_end,
+			work_notes,
+		)
+		.await?;
+
+		tx.commit().await?;
+		(j, t)
+	};
+
+	let expenses = PgExpenses::create(&pool, vec![expense_args()], (timesheet.id, timesheet.time_begin)).await?;
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+
+		// users may not review their own timesheets
+		assert_review(&client, timesheet.id, ReviewStatus::Approved, Code::Unauthorized).await;
+
+		let submitted = assert_submit(&client, timesheet.id, Code::SuccessForPermissions).await;
+		assert_eq!(submitted.iter().map(|r| (r.timesheet_id(), r.status())).collect::<Vec<_>>(), [(
+			timesheet.id,
+			ReviewStatus::Submitted
+		)]);
+
+		assert_submit(&client, timesheet.id, Code::InvalidTransition).await;
+
+		// a submitted timesheet is locked
+		let response = client
+			.patch_builder(routes::TIMESHEET)
+			.json(&request::Patch::new(vec![Timesheet { work_notes: words::sentence(5), ..timesheet.clone() }]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+
+		let row = PgTimesheet::retrieve(&pool, timesheet.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.work_notes, timesheet.work_notes);
+
+		// so are its expenses
+		let response = client
+			.patch_builder(routes::EXPENSE)
+			.json(&request::Patch::new(vec![Expense { description: words::sentence(5), ..expenses[0].clone() }]))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Patch>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+		assert_eq!(PgExpenses::retrieve(&pool, MatchExpense::from(Match::from(expenses[0].id))).await?, expenses);
+		client.logout().await;
+	}
+
+	{
+		client.login(&manager.0, &manager.1).await;
+
+		// a draft cannot be reviewed, nor can a review be anything other than an approval or rejection
+		assert_review(&client, timesheet.id, ReviewStatus::Draft, Code::InvalidTransition).await;
+
+		let rejected = assert_review(&client, timesheet.id, ReviewStatus::Rejected, Code::SuccessForPermissions).await;
+		assert_eq!(rejected.iter().map(|r| r.reviewer_id()).collect::<Vec<_>>(), [Some(manager.0.id())]);
+		assert_eq!(PgReview::retrieve(&pool, &[timesheet.id]).await?[0].status(), ReviewStatus::Rejected);
+
+		assert_review(&client, timesheet.id, ReviewStatus::Approved, Code::InvalidTransition).await;
+		client.logout().await;
+	}
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		assert_submit(&client, timesheet.id, Code::SuccessForPermissions).await;
+		client.logout().await;
+
+		client.login(&manager.0, &manager.1).await;
+		assert_review(&client, timesheet.id, ReviewStatus::Approved, Code::SuccessForPermissions).await;
+		client.logout().await;
+		assert_eq!(PgReview::retrieve(&pool, &[timesheet.id]).await?[0].status(), ReviewStatus::Approved);
+
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client
+			.post_builder(routes::TIMESHEET_REVIEW)
+			.json(&request::Post::new(MatchTimesheet::from(timesheet.id)))
+			.send()
+			.await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Review>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::SuccessForPermissions));
+		assert_eq!(actual.content().entities().iter().map(Review::status).collect::<Vec<_>>(), [
+			ReviewStatus::Approved
+		]);
+
+		client.logout().await;
+	}
+
+	client.login(&guest.0, &guest.1).await;
+	assert_submit(&client, timesheet.id, Code::Unauthorized).await;
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgExpenses::delete(&pool, expenses.iter()).await?;
+	PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
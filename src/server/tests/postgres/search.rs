@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::api::Entity;
+
+#[tokio::test]
+#[traced_test]
+async fn search() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, pool, .. } = setup("search").await?;
+
+	// a word which is unlikely to appear in any other test's data
+	let word = format!("zq{}", words::word().to_lowercase());
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), format!("{} {word}", company::company())).await?;
+	let job_ = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, _) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			format!("fix the {word}"),
+		)
+		.await?;
+
+		tx.commit().await?;
+		j
+	};
+
+	let search = |user: &(User, String)| {
+		let (user, password) = user.clone();
+		let client = &client;
+		let word = word.clone();
+		async move {
+			client.login(&user, &password).await;
+			let response = client.post_builder(routes::SEARCH).json(&request::Post::new(word)).send().await;
+			assert_eq!(response.status(), StatusCode::from(Code::Success));
+
+			let entities = response.json::<Post<Entity>>().await.into_entities();
+			client.logout().await;
+			entities
+		}
+	};
+
+	let mut entities = search(&admin).await;
+	entities.sort_by_key(|e| matches!(e, Entity::Organization(_)));
+	assert_eq!(entities, [Entity::Job(job_.clone()), Entity::Organization(organization.clone())]);
+
+	assert!(search(&guest).await.is_empty());
+
+	PgJob::delete(&pool, [&job_].into_iter()).await?;
+	PgOrganization::delete(&pool, [&organization].into_iter()).await?;
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,105 @@
+use pretty_assertions::assert_eq;
+use winvoice_schema::{Job, Timesheet};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+
+#[tokio::test]
+#[traced_test]
+async fn timer() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("timer").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let job_ = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		tx.commit().await?;
+		j
+	};
+
+	let start_args = |job: &Job| (Vec::<(String, Money, String)>::new(), job.clone(), words::sentence(5));
+
+	client.test_post_unauthorized(routes::TIMESHEET_START, &guest.0, &guest.1, start_args(&job_)).await;
+
+	let timesheet = {
+		client.login(&grunt.0, &grunt.1).await;
+		let response =
+			client.put_builder(routes::TIMESHEET_START).json(&request::Put::new(start_args(&job_))).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Put<Timesheet>>().await);
+		let entity = actual.content().entity().unwrap().clone();
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(entity.employee, grunt.0.employee().unwrap().clone());
+		assert_eq!(entity.time_end, None);
+
+		let row = PgTimesheet::retrieve(&pool, entity.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row, Timesheet { time_begin: entity.time_begin.pg_sanitize(), ..entity.clone() });
+
+		// a second timer cannot be started while the first is running
+		let response =
+			client.put_builder(routes::TIMESHEET_START).json(&request::Put::new(start_args(&job_))).send().await;
+		let actual = Response::new(response.status(), response.json::<Put<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::TimesheetRunning));
+		assert_eq!(actual.content().status().code(), Code::TimesheetRunning);
+
+		client.logout().await;
+		row
+	};
+
+	{
+		client.login(&grunt.0, &grunt.1).await;
+		let response = client.patch_builder(routes::TIMESHEET_STOP).send().await;
+
+		let actual = Response::new(response.status(), response.json::<Post<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+		assert_eq!(actual.content().entities().len(), 1);
+
+		let stopped = actual.content().entities()[0].clone();
+		assert_eq!(stopped.id, timesheet.id);
+		assert!(stopped.time_end.is_some());
+
+		let row = PgTimesheet::retrieve(&pool, timesheet.id.into()).await.map(|mut v| v.remove(0))?;
+		assert_eq!(row.time_end, stopped.time_end.map(|t| t.pg_sanitize()));
+
+		// there is nothing left to stop
+		let response = client.patch_builder(routes::TIMESHEET_STOP).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Timesheet>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::TimesheetNotRunning));
+		assert_eq!(actual.content().status().code(), Code::TimesheetNotRunning);
+
+		client.logout().await;
+	}
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	PgTimesheet::delete(&pool, [&timesheet].into_iter()).await?;
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
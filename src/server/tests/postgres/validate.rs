@@ -0,0 +1,93 @@
+use pretty_assertions::assert_eq;
+use serde::{de::DeserializeOwned, Serialize};
+use winvoice_schema::{Organization, Timesheet};
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::EventKind,
+	schema::{SavedQuery, Webhook},
+};
+
+/// Assert that a PUT of the `args` to the `route` is rejected, with a violation at each of the `paths`.
+async fn assert_invalid<Args, T>(client: &TestClient, route: &str, args: Args, paths: &[&str])
+where
+	Args: Serialize,
+	T: DeserializeOwned,
+{
+	tracing::trace!("Asserting PUT {route} is rejected with violations at {paths:?}");
+	let response = client.put_builder(route).json(&request::Put::new(args)).send().await;
+
+	let actual = Response::new(response.status(), response.json::<Put<T>>().await);
+	assert_eq!(actual.status(), StatusCode::from(Code::ValidationError));
+	assert_eq!(actual.content().status().code(), Code::ValidationError);
+	assert_eq!(actual.content().status().violations().iter().map(Violation::path).collect::<Vec<_>>(), paths);
+}
+
+#[tokio::test]
+#[traced_test]
+async fn validate() -> DynResult<()>
+{
+	let TestData { admin, client, grunt, guest, manager, pool } = setup("validate").await?;
+
+	let location = {
+		let (currency, address_, outer) = location_args();
+		PgLocation::create(&pool, currency, address_, outer).await?
+	};
+
+	let organization = PgOrganization::create(&pool, location.clone(), company::company()).await?;
+	let job_ = {
+		let mut tx = pool.begin().await?;
+		let (date_close, date_open, increment, invoice, notes, objectives) = job_args();
+		let j = PgJob::create(
+			&mut tx,
+			organization.clone(),
+			date_close,
+			date_open,
+			[grunt.0.department().unwrap().clone()].into_iter().collect(),
+			increment,
+			invoice,
+			notes,
+			objectives,
+		)
+		.await?;
+
+		tx.commit().await?;
+		j
+	};
+
+	client.login(&admin.0, &admin.1).await;
+	assert_invalid::<_, Organization>(&client, routes::ORGANIZATION, (location.clone(), " "), &["args[1]"]).await;
+
+	{
+		let (_, time_begin, _, work_notes) = timesheet_args();
+		let expenses = vec![(words::word(), Money::new(-20_00, 2, Default::default()), words::sentence(5))];
+		let time_end = time_begin - chrono::Duration::hours(1);
+		let employee = grunt.0.employee().unwrap().clone();
+		let args = (employee, expenses, job_.clone(), time_begin, Some(time_end), work_notes);
+		assert_invalid::<_, Timesheet>(&client, routes::TIMESHEET, args, &["args[1][0][1]", "args[4]"]).await;
+	}
+
+	{
+		let args = (vec![EventKind::Create], " ", "ftp://localhost/");
+		assert_invalid::<_, Webhook>(&client, routes::WEBHOOK, args, &["args[1]", "args[2]"]).await;
+
+		let args = (serde_json::to_value(MatchLocation::default())?, routes::LOGIN);
+		assert_invalid::<_, SavedQuery>(&client, routes::QUERY, args, &["args[1]"]).await;
+	}
+
+	client.logout().await;
+
+	let users: Vec<_> = [&admin.0, &guest.0, &grunt.0, &manager.0].into_iter().cloned().collect();
+	let roles: Vec<_> = users.iter().map(User::role).collect();
+
+	futures::try_join!(PgUser::delete(&pool, users.iter()), PgJob::delete(&pool, [&job_].into_iter()))?;
+	futures::try_join!(
+		PgRole::delete(&pool, roles.into_iter()),
+		PgOrganization::delete(&pool, [&organization].into_iter()),
+	)?;
+
+	PgLocation::delete(&pool, [&location].into_iter()).await?;
+
+	Ok(())
+}
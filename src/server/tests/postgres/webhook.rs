@@ -0,0 +1,119 @@
+use axum::{body::Bytes, routing};
+use pretty_assertions::assert_eq;
+use tokio::sync::mpsc;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use crate::{
+	api::{Entity, Event, EventKind, WEBHOOK_DELIVERY_HEADER, WEBHOOK_SIGNATURE_HEADER},
+	schema::{Delivery, Webhook},
+	server::webhook::sign,
+};
+
+#[tokio::test]
+#[traced_test]
+async fn webhook() -> DynResult<()>
+{
+	let TestData { admin, client, guest, pool, .. } = setup("webhook").await?;
+
+	let (sender, mut receiver) = mpsc::unbounded_channel::<(HeaderMap, Bytes)>();
+	let address = {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+		let address = listener.local_addr()?;
+		let router = Router::new().route(
+			"/",
+			routing::post(move |headers: HeaderMap, body: Bytes| {
+				let sender = sender.clone();
+				async move {
+					sender.send((headers, body)).ok();
+					StatusCode::OK
+				}
+			}),
+		);
+
+		tokio::spawn(axum::Server::from_tcp(listener)?.serve(router.into_make_service()));
+		address
+	};
+
+	let secret = words::sentence(5);
+	let args = |url: String| (vec![EventKind::Create], secret.clone(), url);
+
+	client.test_post_unauthorized(routes::WEBHOOK, &guest.0, &guest.1, args(format!("http://{address}/"))).await;
+
+	let webhook = {
+		client.login(&admin.0, &admin.1).await;
+		let put = |url: String| client.put_builder(routes::WEBHOOK).json(&request::Put::new(args(url))).send();
+
+		let response = put("ftp://localhost/".into()).await;
+		assert_eq!(response.status(), StatusCode::from(Code::ValidationError));
+
+		let response = put(format!("http://{address}/")).await;
+
+		let actual = Response::new(response.status(), response.json::<Put<Webhook>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+
+		let webhook = actual.content().entity().unwrap().clone();
+		assert_eq!(webhook.events(), [EventKind::Create]);
+		assert_eq!(webhook.secret(), "", "the secret should never be sent back to clients");
+
+		client.logout().await;
+		webhook
+	};
+
+	let department = client
+		.test_post_success::<PgDepartment, _>(&pool, routes::DEPARTMENT, &admin.0, &admin.1, rand_department_name())
+		.await;
+
+	// other tests create entities at the same time, so wait for the one which was created here
+	let delivery_id = tokio::time::timeout(Duration::from_secs(30), async {
+		loop
+		{
+			let (headers, body) = receiver.recv().await.unwrap();
+			let event = serde_json::from_slice::<Event>(&body).unwrap();
+			if event.entities().contains(&Entity::Department(department.clone()))
+			{
+				assert_eq!(event.kind(), EventKind::Create);
+				assert_eq!(headers[WEBHOOK_SIGNATURE_HEADER].to_str().unwrap(), sign(&secret, &body));
+				break headers[WEBHOOK_DELIVERY_HEADER].to_str().unwrap().parse::<i64>().unwrap();
+			}
+		}
+	})
+	.await?;
+
+	client.login(&admin.0, &admin.1).await;
+
+	// the delivery is recorded after the receiver has responded
+	let mut delivered = false;
+	for _ in 0..10
+	{
+		let response =
+			client.post_builder(routes::WEBHOOK_DELIVERIES).json(&request::Post::new(webhook.id())).send().await;
+		let actual = Response::new(response.status(), response.json::<Post<Delivery>>().await);
+		assert_eq!(actual.status(), StatusCode::from(Code::Success));
+
+		let delivery = actual.content().entities().iter().find(|d| d.id() == delivery_id).cloned().unwrap();
+		if delivery.delivered().is_some()
+		{
+			assert_eq!(delivery.status(), Some(StatusCode::OK.as_u16()));
+			assert_eq!(delivery.next_attempt(), None);
+			delivered = true;
+			break;
+		}
+
+		tokio::time::sleep(Duration::from_millis(250)).await;
+	}
+
+	assert!(delivered, "delivery {delivery_id} was never marked as delivered");
+
+	let response = client.delete_builder(routes::WEBHOOK).json(&request::Delete::new(vec![webhook.id()])).send().await;
+	assert_eq!(response.status(), StatusCode::from(Code::Success));
+
+	let response = client.get_builder(routes::WEBHOOK).send().await;
+	let actual = Response::new(response.status(), response.json::<Post<Webhook>>().await);
+	assert!(actual.content().entities().iter().all(|w| w.id() != webhook.id()));
+
+	client.logout().await;
+	PgDepartment::delete(&pool, [&department].into_iter()).await?;
+
+	Ok(())
+}
@@ -0,0 +1,161 @@
+//! Contains the delivery of [`Event`](crate::api::Event)s to [`Webhook`]s.
+
+use core::time::Duration;
+
+use futures::future;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Client};
+use sha2::Sha256;
+use sqlx::Pool;
+
+use crate::{
+	api,
+	schema::{Delivery, Webhook, WebhookAdapter},
+};
+
+/// How many [`Delivery`]s are [claimed](WebhookAdapter::claim) at once.
+const BATCH_SIZE: i64 = 16;
+
+/// How long a claimed [`Delivery`] is reserved for this replica.
+const LEASE: Duration = Duration::from_secs(60);
+
+/// The longest that a failed [`Delivery`] will wait before being tried again.
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// How many times a [`Delivery`] is attempted before giving up.
+const MAX_ATTEMPTS: i32 = 10;
+
+/// How long to wait before checking for due [`Delivery`]s again, after there were none.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a [`Webhook`] has to respond to a [`Delivery`].
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait before trying a [`Delivery`] again, after it has been attempted `attempts` times.
+///
+/// Starts at 10 seconds and doubles each time, up to [`MAX_BACKOFF`].
+pub fn backoff(attempts: i32) -> Duration
+{
+	let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default().min(16);
+	Duration::from_secs(10 * 2_u64.pow(exponent)).min(MAX_BACKOFF)
+}
+
+/// [Enqueue](WebhookAdapter::enqueue) the [`Event`](crate::api::Event)s which have been published, and then
+/// [claim](WebhookAdapter::claim) and send the due [`Delivery`]s of `W`, forever.
+///
+/// Deliveries are queued from the events which were stored in the database along with the changes they describe,
+/// rather than from the memory of any one replica, so that a replica which stops before it could deliver an event does
+/// not lose it.
+pub async fn deliver<W>(pool: Pool<W::Db>)
+where
+	W: WebhookAdapter,
+{
+	let client = match Client::builder().timeout(TIMEOUT).build()
+	{
+		Ok(c) => c,
+		Err(e) => return tracing::error!("Could not build the client for webhook deliveries: {e}"),
+	};
+
+	loop
+	{
+		if let Err(e) = W::enqueue(&pool).await
+		{
+			tracing::error!("Could not queue events for delivery to webhooks: {e}");
+		}
+
+		match W::claim(&pool, BATCH_SIZE, LEASE).await
+		{
+			// there may be more which are due
+			Ok(claimed) if !claimed.is_empty() =>
+			{
+				future::join_all(claimed.into_iter().map(|(d, w)| send::<W>(&pool, &client, d, w))).await;
+				continue;
+			},
+
+			Ok(_) => (),
+			Err(e) => tracing::error!("Could not claim webhook deliveries: {e}"),
+		}
+
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// Send the `delivery` to the `webhook` using the `client`, and record whether it was accepted.
+async fn send<W>(pool: &Pool<W::Db>, client: &Client, delivery: Delivery, webhook: Webhook)
+where
+	W: WebhookAdapter,
+{
+	let result = match serde_json::to_vec(delivery.event())
+	{
+		Ok(body) => client
+			.post(webhook.url())
+			.header(CONTENT_TYPE, "application/json")
+			.header(api::WEBHOOK_DELIVERY_HEADER, delivery.id().to_string())
+			.header(api::WEBHOOK_SIGNATURE_HEADER, sign(webhook.secret(), &body))
+			.body(body)
+			.send()
+			.await
+			.map_err(|e| (e.status().map(|s| s.as_u16()), e.to_string())),
+
+		Err(e) => Err((None, e.to_string())),
+	};
+
+	let (status, error) = match result
+	{
+		Ok(response) if response.status().is_success() =>
+		{
+			if let Err(e) = W::accept(pool, delivery.id(), response.status().as_u16()).await
+			{
+				tracing::error!("Could not record that delivery {} was accepted: {e}", delivery.id());
+			}
+
+			return;
+		},
+
+		Ok(response) => (Some(response.status().as_u16()), format!("The webhook responded with {}", response.status())),
+		Err(e) => e,
+	};
+
+	let retry = (delivery.attempts() < MAX_ATTEMPTS).then(|| backoff(delivery.attempts()));
+	if let Err(e) = W::reject(pool, delivery.id(), status, error, retry).await
+	{
+		tracing::error!("Could not record that delivery {} failed: {e}", delivery.id());
+	}
+}
+
+/// Sign the `body` of a [`Delivery`] using the `secret` of its [`Webhook`], as it should appear in the
+/// [`WEBHOOK_SIGNATURE_HEADER`](api::WEBHOOK_SIGNATURE_HEADER).
+pub fn sign(secret: &str, body: &[u8]) -> String
+{
+	// HMAC accepts keys of any length, so this cannot fail
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+	mac.update(body);
+	format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use super::{backoff, sign, MAX_BACKOFF};
+
+	#[test]
+	fn backoff_doubles()
+	{
+		assert_eq!(backoff(1), Duration::from_secs(10));
+		assert_eq!(backoff(2), Duration::from_secs(20));
+		assert_eq!(backoff(4), Duration::from_secs(80));
+		assert_eq!(backoff(100), MAX_BACKOFF);
+	}
+
+	#[test]
+	fn signature()
+	{
+		// RFC 4231, test case 2
+		assert_eq!(
+			sign("Jefe", b"what do ya want for nothing?"),
+			"sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+		);
+	}
+}
@@ -1,6 +1,6 @@
 //! Misc. utilities for [`winvoice_server`] which do not have a more specific category.
 
-use rand::Rng;
+use rand::{distributions::Alphanumeric, Rng};
 use winvoice_schema::chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Timelike, Utc};
 #[cfg(test)]
 use {
@@ -27,6 +27,13 @@ pub fn cookie_secret() -> Vec<u8>
 	arr.to_vec()
 }
 
+/// Create a cryptographically-secure, randomly generated password of letters and digits.
+#[allow(dead_code)]
+pub fn password() -> String
+{
+	rand::thread_rng().sample_iter(Alphanumeric).take(24).map(char::from).collect()
+}
+
 #[allow(dead_code)]
 #[cfg(test)]
 pub enum Model